@@ -2,11 +2,33 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, AnchorProvider, web3 } from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram, Connection, Keypair } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
 import { SolanaInsuranceProtocol } from "../target/types/solana_insurance_protocol";
 import idl from "../target/idl/solana_insurance_protocol.json";
 import bs58 from 'bs58';
 
+// Borsh indexes of the program's CapitalPoolType and ClaimStatus enums. A pool
+// type's index is also its PDA seed.
+export enum CapitalPoolType {
+  LowRisk = 1,
+  MediumRisk = 2,
+  HighRisk = 3,
+}
+
+export enum ClaimStatus {
+  Pending,
+  Approved,
+  Rejected,
+  Paid,
+  Vetoed,
+}
+
+const POOL_TYPE_ARGS = {
+  [CapitalPoolType.LowRisk]: { lowRisk: {} },
+  [CapitalPoolType.MediumRisk]: { mediumRisk: {} },
+  [CapitalPoolType.HighRisk]: { highRisk: {} },
+};
+
 export class InsuranceProtocolClient {
   private program: Program<SolanaInsuranceProtocol>;
   private provider: AnchorProvider;
@@ -52,34 +74,119 @@ export class InsuranceProtocolClient {
     );
   }
   
-  async getProtocolInfoPda(authority: PublicKey): Promise<[PublicKey, number]> {
+  async getProtocolEntryPda(index: number): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("protocol-entry"), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      this.programId
+    );
+  }
+  
+  // Seeded by the authority the protocol registered under, not the current one after a
+  // rotation; the protocol's entry (getProtocolEntryPda) holds the address either way
+  async getProtocolInfoPda(registeringAuthority: PublicKey): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("protocol-info"), registeringAuthority.toBuffer()],
+      this.programId
+    );
+  }
+  
+  async getTreasuryPda(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("treasury")],
+      this.programId
+    );
+  }
+  
+  async getTreasuryVaultPda(mint: PublicKey): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("treasury-vault"), mint.toBuffer()],
+      this.programId
+    );
+  }
+  
+  async getConfigPda(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("config")],
+      this.programId
+    );
+  }
+  
+  async getGlobalStatsPda(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("global-stats")],
+      this.programId
+    );
+  }
+  
+  async getMintRegistryPda(): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
-      [Buffer.from("protocol-info"), authority.toBuffer()],
+      [Buffer.from("mint-registry")],
       this.programId
     );
   }
   
-  async getCapitalPoolPda(poolType: number): Promise<[PublicKey, number]> {
+  async getCapitalPoolPda(poolType: CapitalPoolType): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
       [Buffer.from("capital-pool"), Buffer.from([poolType])],
       this.programId
     );
   }
   
-  async getPolicyPda(insured: PublicKey, protocolInfo: PublicKey): Promise<[PublicKey, number]> {
+  async getPolicyCounterPda(insured: PublicKey): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("policy-counter"), insured.toBuffer()],
+      this.programId
+    );
+  }
+  
+  // `index` is the insured's policy count when the policy was bought
+  async getPolicyPda(insured: PublicKey, protocolInfo: PublicKey, index: number = 0): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("policy"), insured.toBuffer(), protocolInfo.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      this.programId
+    );
+  }
+  
+  // Holds a policy's premium, net of fees, until it's earned or refunded
+  async getPremiumEscrowPda(policy: PublicKey): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
-      [Buffer.from("policy"), insured.toBuffer(), protocolInfo.toBuffer()],
+      [Buffer.from("premium-escrow"), policy.toBuffer()],
       this.programId
     );
   }
   
-  async getClaimPda(policy: PublicKey): Promise<[PublicKey, number]> {
+  // `index` is the policy's claimCount before the claim was filed, 0 for its first claim
+  async getClaimPda(policy: PublicKey, index: number = 0): Promise<[PublicKey, number]> {
+    const indexBuffer = Buffer.alloc(4);
+    indexBuffer.writeUInt32LE(index);
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("claim"), policy.toBuffer(), indexBuffer],
+      this.programId
+    );
+  }
+  
+  // Where a policy's single claim was filed before claims were indexed
+  async getLegacyClaimPda(policy: PublicKey): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
       [Buffer.from("claim"), policy.toBuffer()],
       this.programId
     );
   }
   
+  async getWatcherRegistryPda(): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("watcher-registry")],
+      this.programId
+    );
+  }
+  
+  async getWatcherPda(owner: PublicKey): Promise<[PublicKey, number]> {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from("watcher"), owner.toBuffer()],
+      this.programId
+    );
+  }
+  
   async getCapitalProviderPda(owner: PublicKey, capitalPool: PublicKey): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
       [Buffer.from("capital-provider"), owner.toBuffer(), capitalPool.toBuffer()],
@@ -115,6 +222,14 @@ export class InsuranceProtocolClient {
   ): Promise<string> {
     const [protocolInfoPda] = await this.getProtocolInfoPda(authority.publicKey);
     const [registryPda] = await this.getProtocolRegistryPda();
+    const registry = await this.program.account.protocolRegistry.fetch(registryPda);
+    const [protocolEntryPda] = await this.getProtocolEntryPda(registry.nextIndex.toNumber());
+    const registrationApproval = registry.allowlistEnabled
+      ? (await PublicKey.findProgramAddress(
+          [Buffer.from("registration-approval"), authority.publicKey.toBuffer()],
+          this.programId
+        ))[0]
+      : null;
     
     const tx = await this.program.methods
       .registerProtocol(protocolName, new anchor.BN(tvlUsd))
@@ -122,6 +237,9 @@ export class InsuranceProtocolClient {
         authority: authority.publicKey,
         protocolInfo: protocolInfoPda,
         registry: registryPda,
+        protocolEntry: protocolEntryPda,
+        registrationApproval,
+        directory: registry.directory.equals(PublicKey.default) ? null : registry.directory,
         systemProgram: SystemProgram.programId,
       })
       .signers([authority])
@@ -137,9 +255,43 @@ export class InsuranceProtocolClient {
     premiumAmount: number,
     durationDays: number,
     insuredToken: PublicKey,
-    treasuryToken: PublicKey
+    tokenMint: PublicKey, // premiums go to the treasury vault for this mint
+    poolType: CapitalPoolType,
+    feeVault: PublicKey | null = null, // required while a protocol fee is charged
+    withStakerDiscount: boolean = false // the insured stakes the governance token
   ): Promise<string> {
-    const [policyPda] = await this.getPolicyPda(insured.publicKey, protocolInfo);
+    // The insured's next policy goes at their current policy count
+    const [policyCounterPda] = await this.getPolicyCounterPda(insured.publicKey);
+    const policyCounter = await this.program.account.policyCounter.fetchNullable(policyCounterPda);
+    const [policyPda] = await this.getPolicyPda(
+      insured.publicKey,
+      protocolInfo,
+      policyCounter ? policyCounter.nextIndex.toNumber() : 0
+    );
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [capitalPoolPda] = await this.getCapitalPoolPda(poolType);
+    const [configPda] = await this.getConfigPda();
+    const [treasuryVaultPda] = await this.getTreasuryVaultPda(tokenMint);
+    const [premiumEscrowPda] = await this.getPremiumEscrowPda(policyPda);
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    
+    // Part of the fee goes to the protocol's treasury when it takes a revenue share
+    const protocol = await this.program.account.protocolInfo.fetch(protocolInfo);
+    const revenueShareToken = protocol.revenueShareBps.toNumber() > 0 ? protocol.revenueShareAccount : null;
+    
+    let governance: PublicKey | null = null;
+    let governanceStake: PublicKey | null = null;
+    if (withStakerDiscount) {
+      [governance] = await PublicKey.findProgramAddress(
+        [Buffer.from("governance")],
+        this.programId
+      );
+      [governanceStake] = await PublicKey.findProgramAddress(
+        [Buffer.from("governance-stake"), insured.publicKey.toBuffer()],
+        this.programId
+      );
+    }
     
     const tx = await this.program.methods
       .createPolicy(
@@ -149,12 +301,25 @@ export class InsuranceProtocolClient {
       )
       .accounts({
         insured: insured.publicKey,
+        policyCounter: policyCounterPda,
         policy: policyPda,
         protocolInfo,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        config: configPda,
+        capitalPool: capitalPoolPda,
         insuredToken,
-        treasuryToken,
+        treasuryVault: treasuryVaultPda,
+        feeVault,
+        revenueShareToken,
+        governance,
+        governanceStake,
+        premiumMint: tokenMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        premiumEscrow: premiumEscrowPda,
+        memoProgram: null,
+        mintRegistry: mintRegistryPda,
       })
       .signers([insured])
       .rpc();
@@ -162,11 +327,11 @@ export class InsuranceProtocolClient {
     return tx;
   }
   
+  // `authority` has to be a risk committee member
   async updateProtocolRisk(
     authority: Keypair,
     protocolInfo: PublicKey,
     codeRiskParams: {
-      auditCount: number,
       bugBountySize: number,
       complexityScore: number
     },
@@ -176,16 +341,20 @@ export class InsuranceProtocolClient {
     },
     operationalRiskParams: {
       governanceCount: number,
-      adminCount: number,
-      oracleDependency: boolean
+      adminCount: number
     }
   ): Promise<string> {
     const [protocolStatePda] = await this.getProtocolStatePda();
+    const [registryPda] = await this.getProtocolRegistryPda();
+    const registry = await this.program.account.protocolRegistry.fetch(registryPda);
+    const [riskCommitteePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("risk-committee")],
+      this.programId
+    );
     
     const tx = await this.program.methods
       .updateProtocolRisk(
         {
-          auditCount: codeRiskParams.auditCount,
           bugBountySize: new anchor.BN(codeRiskParams.bugBountySize),
           complexityScore: codeRiskParams.complexityScore,
         },
@@ -196,13 +365,59 @@ export class InsuranceProtocolClient {
         {
           governanceCount: operationalRiskParams.governanceCount,
           adminCount: operationalRiskParams.adminCount,
-          oracleDependency: operationalRiskParams.oracleDependency,
         }
       )
       .accounts({
         authority: authority.publicKey,
+        riskCommittee: riskCommitteePda,
         protocolInfo,
         protocolState: protocolStatePda,
+        registry: registryPda,
+        directory: registry.directory.equals(PublicKey.default) ? null : registry.directory,
+      })
+      .signers([authority])
+      .rpc();
+    
+    return tx;
+  }
+  
+  async initializeMintRegistry(authority: Keypair): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    
+    const tx = await this.program.methods
+      .initializeMintRegistry()
+      .accounts({
+        authority: authority.publicKey,
+        mintRegistry: mintRegistryPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+    
+    return tx;
+  }
+  
+  // Lists a token for premiums, pools and payouts; caps and a minimum premium of 0 are off
+  async registerMint(
+    authority: Keypair,
+    mint: PublicKey,
+    priceFeedId: number[],
+    maxPolicyCoverage: number = 0,
+    maxPoolCapital: number = 0,
+    minPremium: number = 0
+  ): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    
+    const tx = await this.program.methods
+      .registerMint(priceFeedId, new anchor.BN(maxPolicyCoverage), new anchor.BN(maxPoolCapital), new anchor.BN(minPremium))
+      .accounts({
+        authority: authority.publicKey,
+        mintRegistry: mintRegistryPda,
+        mint,
+        protocolState: protocolStatePda,
       })
       .signers([authority])
       .rpc();
@@ -212,16 +427,17 @@ export class InsuranceProtocolClient {
   
   async initializeCapitalPool(
     authority: Keypair,
-    poolType: number,
+    poolType: CapitalPoolType,
     yieldRateBps: number,
     tokenMint: PublicKey,
     poolTokenAccount: PublicKey
   ): Promise<string> {
     const [protocolStatePda] = await this.getProtocolStatePda();
     const [capitalPoolPda] = await this.getCapitalPoolPda(poolType);
+    const [mintRegistryPda] = await this.getMintRegistryPda();
     
     const tx = await this.program.methods
-      .initializeCapitalPool(poolType, new anchor.BN(yieldRateBps))
+      .initializeCapitalPool(POOL_TYPE_ARGS[poolType] as any, new anchor.BN(yieldRateBps))
       .accounts({
         authority: authority.publicKey,
         capitalPool: capitalPoolPda,
@@ -231,6 +447,7 @@ export class InsuranceProtocolClient {
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        mintRegistry: mintRegistryPda,
       })
       .signers([authority])
       .rpc();
@@ -246,6 +463,11 @@ export class InsuranceProtocolClient {
     poolTokenAccount: PublicKey
   ): Promise<string> {
     const [capitalProviderPda] = await this.getCapitalProviderPda(owner.publicKey, capitalPool);
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    const [configPda] = await this.getConfigPda();
+    const pool = await this.program.account.capitalPool.fetch(capitalPool);
     
     const tx = await this.program.methods
       .provideCapital(new anchor.BN(amount))
@@ -255,9 +477,16 @@ export class InsuranceProtocolClient {
         capitalPool,
         providerToken,
         poolTokenAccount,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        tokenMint: pool.tokenMint,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        mintRegistry: mintRegistryPda,
+        governance: null,
+        governanceStake: null,
+        config: configPda,
       })
       .signers([owner])
       .rpc();
@@ -273,6 +502,10 @@ export class InsuranceProtocolClient {
     poolTokenAccount: PublicKey
   ): Promise<string> {
     const [capitalProviderPda] = await this.getCapitalProviderPda(owner.publicKey, capitalPool);
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [configPda] = await this.getConfigPda();
+    const pool = await this.program.account.capitalPool.fetch(capitalPool);
     
     const tx = await this.program.methods
       .withdrawCapital(new anchor.BN(amount))
@@ -282,8 +515,66 @@ export class InsuranceProtocolClient {
         capitalPool,
         providerToken,
         poolTokenAccount,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        tokenMint: pool.tokenMint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        governance: null,
+        governanceStake: null,
+        config: configPda,
+      })
+      .signers([owner])
+      .rpc();
+    
+    return tx;
+  }
+  
+  // Withdraws all of the owner's capital and closes the position in one transaction
+  async withdrawAndClosePosition(
+    owner: Keypair,
+    capitalPool: PublicKey,
+    providerToken: PublicKey,
+    poolTokenAccount: PublicKey
+  ): Promise<string> {
+    const [capitalProviderPda] = await this.getCapitalProviderPda(owner.publicKey, capitalPool);
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [configPda] = await this.getConfigPda();
+    const pool = await this.program.account.capitalPool.fetch(capitalPool);
+    
+    const tx = await this.program.methods
+      .withdrawAndClosePosition()
+      .accounts({
+        owner: owner.publicKey,
+        capitalProvider: capitalProviderPda,
+        capitalPool,
+        providerToken,
+        poolTokenAccount,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        tokenMint: pool.tokenMint,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        governance: null,
+        governanceStake: null,
+        config: configPda,
+      })
+      .signers([owner])
+      .rpc();
+    
+    return tx;
+  }
+  
+  // Returns the rent of a fully withdrawn position
+  async closePosition(owner: Keypair, capitalPool: PublicKey): Promise<string> {
+    const [capitalProviderPda] = await this.getCapitalProviderPda(owner.publicKey, capitalPool);
+    
+    const tx = await this.program.methods
+      .closePosition()
+      .accounts({
+        owner: owner.publicKey,
+        capitalProvider: capitalProviderPda,
       })
       .signers([owner])
       .rpc();
@@ -295,17 +586,27 @@ export class InsuranceProtocolClient {
     claimant: Keypair,
     policy: PublicKey,
     amount: number,
-    evidence: string
+    evidence: string,
+    lossEvent: PublicKey | null = null
   ): Promise<string> {
-    const [claimPda] = await this.getClaimPda(policy);
+    const policyAccount = await this.program.account.policy.fetch(policy);
+    const [claimPda] = await this.getClaimPda(policy, policyAccount.claimCount);
+    const [legacyClaimPda] = await this.getLegacyClaimPda(policy);
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [configPda] = await this.getConfigPda();
     
     const tx = await this.program.methods
       .submitClaim(new anchor.BN(amount), evidence)
       .accounts({
         claimant: claimant.publicKey,
         policy,
+        protocolInfo: policyAccount.protocol,
+        protocolState: protocolStatePda,
+        config: configPda,
+        lossEvent,
         claim: claimPda,
         systemProgram: SystemProgram.programId,
+        legacyClaim: legacyClaimPda,
       })
       .signers([claimant])
       .rpc();
@@ -319,11 +620,17 @@ export class InsuranceProtocolClient {
     policy: PublicKey,
     protocolInfo: PublicKey,
     capitalPool: PublicKey,
-    poolTokenAccount: PublicKey,
-    claimantToken: PublicKey,
     approve: boolean,
     resolutionNotes: string
   ): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [configPda] = await this.getConfigPda();
+    const [claimsCommitteePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("claims-committee")],
+      this.programId
+    );
+    
     const tx = await this.program.methods
       .resolveClaim(approve, resolutionNotes)
       .accounts({
@@ -331,10 +638,138 @@ export class InsuranceProtocolClient {
         claim,
         policy,
         protocolInfo,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        config: configPda,
+        capitalPool,
+        claimsCommittee: claimsCommitteePda,
+      })
+      .signers([resolver])
+      .rpc();
+    
+    return tx;
+  }
+  
+  async addClaimResolver(
+    authority: Keypair,
+    resolver: PublicKey
+  ): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    
+    const tx = await this.program.methods
+      .addClaimResolver(resolver)
+      .accounts({
+        authority: authority.publicKey,
+        protocolState: protocolStatePda,
+      })
+      .signers([authority])
+      .rpc();
+    
+    return tx;
+  }
+  
+  // Approved claims can only be paid once their challenge window has passed
+  async payClaim(
+    caller: Keypair,
+    claim: PublicKey,
+    policy: PublicKey,
+    protocolInfo: PublicKey,
+    capitalPool: PublicKey,
+    poolTokenAccount: PublicKey,
+    tokenMint: PublicKey,
+    insured: PublicKey,
+    unwrapSol: boolean = false // pay a wrapped SOL pool's claim out as lamports
+  ): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    
+    // Token payouts go to the insured's associated token account, created by the program
+    // if the insured doesn't have one yet
+    let claimantToken: PublicKey | null = null;
+    let solPayout: PublicKey | null = null;
+    if (unwrapSol) {
+      [solPayout] = await PublicKey.findProgramAddress(
+        [Buffer.from("sol-payout"), claim.toBuffer()],
+        this.programId
+      );
+    } else {
+      claimantToken = await getAssociatedTokenAddress(tokenMint, insured);
+    }
+    
+    const tx = await this.program.methods
+      .payClaim()
+      .accounts({
+        caller: caller.publicKey,
+        claim,
+        policy,
+        protocolInfo,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
         capitalPool,
         poolTokenAccount,
+        tokenMint,
+        insured,
         claimantToken,
+        solPayout,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        memoProgram: null,
+        mintRegistry: mintRegistryPda,
+      })
+      .signers([caller])
+      .rpc();
+    
+    return tx;
+  }
+  
+  // Approves a claim and pays it out at once; only for tiers without a challenge window
+  async resolveAndPayClaim(
+    resolver: Keypair,
+    claim: PublicKey,
+    policy: PublicKey,
+    protocolInfo: PublicKey,
+    capitalPool: PublicKey,
+    poolTokenAccount: PublicKey,
+    tokenMint: PublicKey,
+    insured: PublicKey,
+    resolutionNotes: string
+  ): Promise<string> {
+    const [protocolStatePda] = await this.getProtocolStatePda();
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const [configPda] = await this.getConfigPda();
+    const [mintRegistryPda] = await this.getMintRegistryPda();
+    const [claimsCommitteePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("claims-committee")],
+      this.programId
+    );
+    
+    const tx = await this.program.methods
+      .resolveAndPayClaim(resolutionNotes)
+      .accounts({
+        resolver: resolver.publicKey,
+        config: configPda,
+        claimsCommittee: claimsCommitteePda,
+        pay: {
+          caller: resolver.publicKey,
+          claim,
+          policy,
+          protocolInfo,
+          protocolState: protocolStatePda,
+          globalStats: globalStatsPda,
+          capitalPool,
+          poolTokenAccount,
+          tokenMint,
+          insured,
+          claimantToken: await getAssociatedTokenAddress(tokenMint, insured),
+          solPayout: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          memoProgram: null,
+          mintRegistry: mintRegistryPda,
+        },
       })
       .signers([resolver])
       .rpc();
@@ -342,14 +777,39 @@ export class InsuranceProtocolClient {
     return tx;
   }
   
+  // Folds tokens sent straight to the pool vault into available capital, or writes off
+  // a shortfall, so the pool's accounting matches its vault balance
+  async syncPoolBalance(poolType: CapitalPoolType): Promise<string> {
+    const [capitalPoolPda] = await this.getCapitalPoolPda(poolType);
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    const capitalPool = await this.program.account.capitalPool.fetch(capitalPoolPda);
+    
+    const tx = await this.program.methods
+      .syncPoolBalance()
+      .accounts({
+        capitalPool: capitalPoolPda,
+        poolTokenAccount: capitalPool.tokenAccount,
+        globalStats: globalStatsPda,
+      })
+      .rpc();
+    
+    return tx;
+  }
+  
   async createExploitAlert(
     authority: Keypair,
     protocolInfo: PublicKey,
-    anomalyType: number,
-    severity: number,
-    details: string
+    anomalyType: AnomalyType,
+    severity: AlertSeverity,
+    detailsHash: number[],
+    detailsUri: string,
+    affectedPool: PublicKey | null = null
   ): Promise<string> {
     const [protocolStatePda] = await this.getProtocolStatePda();
+    const [watcherPda] = await this.getWatcherPda(authority.publicKey);
+    const [watcherRegistryPda] = await this.getWatcherRegistryPda();
+    const [registryPda] = await this.getProtocolRegistryPda();
+    const registry = await this.program.account.protocolRegistry.fetch(registryPda);
     
     // Create a timestamp-based seed for the exploit alert PDA
     const timestamp = new anchor.BN(Date.now());
@@ -361,13 +821,18 @@ export class InsuranceProtocolClient {
     );
     
     const tx = await this.program.methods
-      .createExploitAlert(anomalyType, severity, details)
+      .createExploitAlert(anomalyType, severity, detailsHash, detailsUri)
       .accounts({
         authority: authority.publicKey,
         exploitAlert: exploitAlertPda,
         protocolInfo,
         protocolState: protocolStatePda,
+        affectedPool,
+        watcher: watcherPda,
+        watcherRegistry: watcherRegistryPda,
         systemProgram: SystemProgram.programId,
+        registry: registryPda,
+        directory: registry.directory.equals(PublicKey.default) ? null : registry.directory,
       })
       .signers([authority])
       .rpc();
@@ -377,6 +842,11 @@ export class InsuranceProtocolClient {
   
   // Helper methods to retrieve account data
   
+  async getGlobalStats(): Promise<any> {
+    const [globalStatsPda] = await this.getGlobalStatsPda();
+    return await this.program.account.globalStats.fetch(globalStatsPda);
+  }
+  
   async getProtocolState(): Promise<any> {
     const [protocolStatePda] = await this.getProtocolStatePda();
     return await this.program.account.protocolState.fetch(protocolStatePda);
@@ -387,22 +857,34 @@ export class InsuranceProtocolClient {
     return await this.program.account.protocolRegistry.fetch(registryPda);
   }
   
-  async getProtocolInfo(authority: PublicKey): Promise<any> {
-    const [protocolInfoPda] = await this.getProtocolInfoPda(authority);
+  // Every covered protocol in one read; slots are indexed by registry index
+  async getProtocolDirectory(): Promise<any[]> {
+    const registry = await this.getProtocolRegistry();
+    if (registry.directory.equals(PublicKey.default)) {
+      return [];
+    }
+    const directory = await this.program.account.protocolDirectory.fetch(registry.directory);
+    return directory.entries
+      .slice(0, directory.entryCount.toNumber())
+      .filter((entry: any) => entry.isRegistered === 1);
+  }
+  
+  async getProtocolInfo(registeringAuthority: PublicKey): Promise<any> {
+    const [protocolInfoPda] = await this.getProtocolInfoPda(registeringAuthority);
     return await this.program.account.protocolInfo.fetch(protocolInfoPda);
   }
   
-  async getPolicy(insured: PublicKey, protocolInfo: PublicKey): Promise<any> {
-    const [policyPda] = await this.getPolicyPda(insured, protocolInfo);
+  async getPolicy(insured: PublicKey, protocolInfo: PublicKey, index: number = 0): Promise<any> {
+    const [policyPda] = await this.getPolicyPda(insured, protocolInfo, index);
     return await this.program.account.policy.fetch(policyPda);
   }
   
-  async getClaim(policy: PublicKey): Promise<any> {
-    const [claimPda] = await this.getClaimPda(policy);
+  async getClaim(policy: PublicKey, index: number = 0): Promise<any> {
+    const [claimPda] = await this.getClaimPda(policy, index);
     return await this.program.account.claim.fetch(claimPda);
   }
   
-  async getCapitalPool(poolType: number): Promise<any> {
+  async getCapitalPool(poolType: CapitalPoolType): Promise<any> {
     const [capitalPoolPda] = await this.getCapitalPoolPda(poolType);
     return await this.program.account.capitalPool.fetch(capitalPoolPda);
   }
@@ -412,6 +894,32 @@ export class InsuranceProtocolClient {
     return await this.program.account.capitalProvider.fetch(capitalProviderPda);
   }
   
+  // Simulated view instructions, computed by the program itself
+  
+  async getPolicyStatus(insured: PublicKey, protocolInfo: PublicKey, index: number = 0): Promise<any> {
+    const [policyPda] = await this.getPolicyPda(insured, protocolInfo, index);
+    return await this.program.methods
+      .getPolicyStatus()
+      .accounts({ policy: policyPda })
+      .view();
+  }
+  
+  async getPoolStats(poolType: CapitalPoolType): Promise<any> {
+    const [capitalPoolPda] = await this.getCapitalPoolPda(poolType);
+    return await this.program.methods
+      .getPoolStats()
+      .accounts({ capitalPool: capitalPoolPda })
+      .view();
+  }
+  
+  async getClaimStatus(policy: PublicKey, index: number = 0): Promise<any> {
+    const [claimPda] = await this.getClaimPda(policy, index);
+    return await this.program.methods
+      .getClaimStatus()
+      .accounts({ claim: claimPda })
+      .view();
+  }
+  
   // Query methods to get all accounts of a specific type
   
   async getAllProtocols(): Promise<any[]> {
@@ -462,7 +970,7 @@ export class InsuranceProtocolClient {
     ]);
   }
   
-  async getClaimsByStatus(status: number): Promise<any[]> {
+  async getClaimsByStatus(status: ClaimStatus): Promise<any[]> {
     return await this.program.account.claim.all([
       {
         memcmp: {
@@ -488,19 +996,22 @@ export class InsuranceProtocolClient {
   calculatePremium(
     riskScore: number,
     coverageAmount: number,
-    durationDays: number
+    durationDays: number,
+    premiumCurve: { riskScore: number, rateBps: number }[] = DEFAULT_PREMIUM_CURVE
   ): number {
-    // Get premium rate in basis points based on risk score
-    let premiumRateBps: number;
-    
-    if (riskScore <= 25) {
-      premiumRateBps = 25; // 0.25% annual rate for low risk
-    } else if (riskScore <= 50) {
-      premiumRateBps = 50; // 0.5% annual rate for medium-low risk
-    } else if (riskScore <= 75) {
-      premiumRateBps = 100; // 1% annual rate for medium-high risk
-    } else {
-      premiumRateBps = 200; // 2% annual rate for high risk
+    // Get premium rate in basis points by interpolating between the curve's anchor points
+    const score = Math.min(riskScore, 100);
+    let premiumRateBps = premiumCurve[premiumCurve.length - 1].rateBps;
+    
+    for (let i = 1; i < premiumCurve.length; i++) {
+      const lower = premiumCurve[i - 1];
+      const upper = premiumCurve[i];
+      if (score <= upper.riskScore) {
+        const span = upper.riskScore - lower.riskScore;
+        const offset = Math.max(score - lower.riskScore, 0);
+        premiumRateBps = lower.rateBps + Math.floor((upper.rateBps - lower.rateBps) * offset / span);
+        break;
+      }
     }
     
     // Calculate annual premium
@@ -517,6 +1028,14 @@ export class InsuranceProtocolClient {
 }
 
 // Export constants
+export const DEFAULT_PREMIUM_CURVE = [
+  { riskScore: 0, rateBps: 25 },
+  { riskScore: 25, rateBps: 50 },
+  { riskScore: 50, rateBps: 100 },
+  { riskScore: 75, rateBps: 200 },
+  { riskScore: 100, rateBps: 200 },
+];
+
 export const POOL_TYPE_LOW_RISK = 1;
 export const POOL_TYPE_MEDIUM_RISK = 2;
 export const POOL_TYPE_HIGH_RISK = 3;
@@ -525,6 +1044,19 @@ export const CLAIM_STATUS_PENDING = 0;
 export const CLAIM_STATUS_APPROVED = 1;
 export const CLAIM_STATUS_REJECTED = 2;
 
-export const ANOMALY_TVL_DROP = 1;
-export const ANOMALY_PRICE = 2;
-export const ANOMALY_TX_VOLUME = 3;
\ No newline at end of file
+// Anchor enums are passed as single-key objects, e.g. { tvlDrop: {} }
+export type AnomalyType =
+  | { tvlDrop: {} }
+  | { priceAnomaly: {} }
+  | { transactionVolume: {} }
+  | { oracleManipulation: {} }
+  | { flashLoanAttack: {} }
+  | { adminKeyCompromise: {} }
+  | { depeg: {} }
+  | { missedHeartbeat: {} };
+
+export type AlertSeverity =
+  | { low: {} }
+  | { medium: {} }
+  | { high: {} }
+  | { critical: {} };
\ No newline at end of file
@@ -0,0 +1,327 @@
+// solana-program-test harness for end-to-end scenarios. It stands up a deployment from
+// the program's `testing` instruction builders, registers protocols, funds pools and
+// moves the bank's clock, so a scenario only spells out the flow it's exercising.
+//
+// The program runs natively in the bank, so scenarios need no SBF build.
+
+use anchor_lang::AccountDeserialize;
+use solana_insurance_protocol::capital_management::CapitalPoolType;
+use solana_insurance_protocol::integration::{
+    capital_pool_address, policy_address, protocol_info_address, protocol_state_address,
+};
+pub use solana_insurance_protocol::testing;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+
+// Decimals of the token the deployment is set up with
+pub const MINT_DECIMALS: u8 = 6;
+
+// Lamports given to each new signer, enough for the accounts it pays to create
+const SIGNER_FUNDING: u64 = 1_000_000_000;
+
+// Anchor's entrypoint ties the account infos to a single lifetime, which the native
+// processor can't offer, so the infos are leaked for the length of the test
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    solana_insurance_protocol::entry(program_id, accounts, data)
+}
+
+// An Ed25519 program instruction verifying `signer`'s signature over `message`, with the
+// key, signature and message all in its own data, as the program's signature checks expect
+pub fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    const DATA_START: u16 = 16; // Header and one set of offsets
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + 32;
+    let message_data_offset = signature_offset + 64;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_data_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: solana_sdk::ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+pub struct ProtocolTest {
+    pub context: ProgramTestContext,
+    pub mint: Pubkey,                // Registered token every pool is created on
+    pub fee_vault: Pubkey,           // Protocol fees on premiums paid in the token
+    pub protocols_registered: u64,   // Next index in the protocol registry
+}
+
+impl ProtocolTest {
+    // A fresh deployment administered by the bank's payer, with one token registered and
+    // accepted for premiums
+    pub async fn start() -> Self {
+        let program_test = ProgramTest::new(
+            "solana_insurance_protocol",
+            solana_insurance_protocol::ID,
+            processor!(process_instruction),
+        );
+        let context = program_test.start_with_context().await;
+        let mut test = ProtocolTest {
+            context,
+            mint: Pubkey::default(),
+            fee_vault: Pubkey::default(),
+            protocols_registered: 0,
+        };
+        test.mint = test.create_mint(MINT_DECIMALS).await;
+
+        let admin = test.admin();
+        let mint = test.mint;
+        test.process(
+            &[
+                testing::initialize(admin, 100),
+                testing::initialize_config(admin),
+                testing::initialize_global_stats(admin),
+                testing::initialize_mint_registry(admin),
+                testing::register_mint(admin, mint),
+            ],
+            &[],
+        )
+        .await
+        .expect("deployment setup failed");
+
+        let treasury_token = test.create_token_account(mint, testing::treasury_address()).await;
+        test.fee_vault = test.create_token_account(mint, protocol_state_address().0).await;
+        let fee_vault = test.fee_vault;
+        test.process(
+            &[
+                testing::initialize_treasury(admin, admin),
+                testing::add_treasury_vault(admin, mint, treasury_token, u64::MAX),
+                testing::set_fee_vault(admin, fee_vault),
+            ],
+            &[],
+        )
+        .await
+        .expect("treasury setup failed");
+
+        test
+    }
+
+    pub fn admin(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    // Processes the instructions in one transaction paid for by the admin
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    // Moves the clock forward, e.g. past a challenge window or a policy's end_time. The
+    // bank also moves to the next slot, so a repeated transaction isn't a duplicate.
+    pub async fn warp_forward(&mut self, secs: i64) {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        self.context.warp_to_slot(clock.slot + 1).expect("warp");
+
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += secs;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp
+    }
+
+    // Reads a program account, panicking if it doesn't exist
+    pub async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self.context.banks_client
+            .get_account(address)
+            .await
+            .expect("get_account")
+            .unwrap_or_else(|| panic!("account {} not found", address));
+        T::try_deserialize(&mut account.data.as_slice()).expect("account data")
+    }
+
+    // A new keypair holding enough SOL to pay for the accounts it creates
+    pub async fn funded_signer(&mut self) -> Keypair {
+        let signer = Keypair::new();
+        let fund = system_instruction::transfer(&self.admin(), &signer.pubkey(), SIGNER_FUNDING);
+        self.process(&[fund], &[]).await.expect("funding failed");
+        signer
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let admin = self.admin();
+        let rent = self.context.banks_client.get_rent().await.expect("rent");
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &admin,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &admin, None, decimals)
+                    .expect("initialize_mint2"),
+            ],
+            &[&mint],
+        )
+        .await
+        .expect("mint creation failed");
+        mint.pubkey()
+    }
+
+    pub async fn create_token_account(&mut self, mint: Pubkey, owner: Pubkey) -> Pubkey {
+        let token_account = Keypair::new();
+        let admin = self.admin();
+        let rent = self.context.banks_client.get_rent().await.expect("rent");
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &admin,
+                    &token_account.pubkey(),
+                    rent.minimum_balance(spl_token::state::Account::LEN),
+                    spl_token::state::Account::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_account3(&spl_token::ID, &token_account.pubkey(), &mint, &owner)
+                    .expect("initialize_account3"),
+            ],
+            &[&token_account],
+        )
+        .await
+        .expect("token account creation failed");
+        token_account.pubkey()
+    }
+
+    // Mints the deployment's token, whose mint authority is the admin
+    pub async fn mint_to(&mut self, token_account: Pubkey, amount: u64) {
+        let admin = self.admin();
+        let mint_to = spl_token::instruction::mint_to(&spl_token::ID, &self.mint, &token_account, &admin, &[], amount)
+            .expect("mint_to");
+        self.process(&[mint_to], &[]).await.expect("mint_to failed");
+    }
+
+    pub async fn token_balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self.context.banks_client
+            .get_account(token_account)
+            .await
+            .expect("get_account")
+            .expect("token account not found");
+        spl_token::state::Account::unpack(&account.data).expect("token account data").amount
+    }
+
+    // Registers a protocol under a new authority, returning the authority and its ProtocolInfo
+    pub async fn register_protocol(&mut self, protocol_name: &str, tvl_usd: u64) -> (Keypair, Pubkey) {
+        let authority = self.funded_signer().await;
+        let register = testing::register_protocol(
+            authority.pubkey(),
+            self.protocols_registered,
+            protocol_name.to_string(),
+            tvl_usd,
+        );
+        self.process(&[register], &[&authority]).await.expect("register_protocol failed");
+        self.protocols_registered += 1;
+
+        let protocol_info = protocol_info_address(&authority.pubkey()).0;
+        (authority, protocol_info)
+    }
+
+    // Creates the tier's pool on the deployment's token, returning the pool's token account
+    pub async fn initialize_pool(&mut self, pool_type: CapitalPoolType, yield_rate_bps: u64) -> Pubkey {
+        let pool_token_account = self.create_token_account(self.mint, capital_pool_address(pool_type).0).await;
+        let initialize = testing::initialize_capital_pool(
+            self.admin(),
+            pool_type,
+            yield_rate_bps,
+            self.mint,
+            pool_token_account,
+        );
+        self.process(&[initialize], &[]).await.expect("initialize_capital_pool failed");
+        pool_token_account
+    }
+
+    // Provides `amount` to the pool from a new capital provider, returning the provider
+    pub async fn fund_pool(&mut self, pool_type: CapitalPoolType, pool_token_account: Pubkey, amount: u64) -> Keypair {
+        let provider = self.funded_signer().await;
+        let provider_token = self.create_token_account(self.mint, provider.pubkey()).await;
+        self.mint_to(provider_token, amount).await;
+
+        let provide = testing::provide_capital(
+            provider.pubkey(),
+            pool_type,
+            self.mint,
+            provider_token,
+            pool_token_account,
+            amount,
+        );
+        self.process(&[provide], &[&provider]).await.expect("provide_capital failed");
+        provider
+    }
+
+    // Sells a new insured its first policy on the protocol, paying `premium_amount`
+    // from a freshly minted balance. Returns the insured and the policy.
+    pub async fn buy_policy(
+        &mut self,
+        protocol_info: Pubkey,
+        pool_type: CapitalPoolType,
+        coverage_amount: u64,
+        premium_amount: u64,
+    ) -> (Keypair, Pubkey) {
+        let insured = self.funded_signer().await;
+        let insured_token = self.create_token_account(self.mint, insured.pubkey()).await;
+        self.mint_to(insured_token, premium_amount).await;
+
+        let payment = testing::PremiumPayment {
+            insured_token,
+            premium_mint: self.mint,
+            fee_vault: self.fee_vault,
+        };
+        let create = testing::create_policy(
+            insured.pubkey(),
+            protocol_info,
+            0,
+            pool_type,
+            &payment,
+            solana_insurance_protocol::instruction::CreatePolicy {
+                coverage_amount,
+                premium_amount,
+                duration_days: 30,
+            },
+        );
+        self.process(&[create], &[&insured]).await.expect("create_policy failed");
+
+        let policy = policy_address(&insured.pubkey(), &protocol_info, 0).0;
+        (insured, policy)
+    }
+}
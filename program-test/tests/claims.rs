@@ -0,0 +1,58 @@
+use solana_insurance_protocol::capital_management::CapitalPoolType;
+use solana_insurance_protocol::Policy;
+use solana_insurance_protocol_program_test::{testing, ProtocolTest};
+use solana_sdk::signature::Signer;
+
+const CAPITAL: u64 = 1_000_000_000;
+const COVERAGE: u64 = 100_000_000;
+const PREMIUM: u64 = 1_000_000;
+
+#[tokio::test]
+async fn partial_claims_share_the_policy_coverage() {
+    let mut test = ProtocolTest::start().await;
+    let admin = test.admin();
+    let (_, protocol_info) = test.register_protocol("Partial Claims", 10_000_000).await;
+    let pool_token_account = test.initialize_pool(CapitalPoolType::MediumRisk, 500).await;
+    test.fund_pool(CapitalPoolType::MediumRisk, pool_token_account, CAPITAL).await;
+    test.process(&[testing::add_claim_resolver(admin, admin)], &[]).await.unwrap();
+    let (insured, policy) = test.buy_policy(protocol_info, CapitalPoolType::MediumRisk, COVERAGE, PREMIUM).await;
+
+    // Two partial claims, each filed at the policy's next claim index and approved
+    let first_claim = COVERAGE * 2 / 5;
+    let second_claim = COVERAGE - first_claim;
+    for (claim_index, amount) in [(0, first_claim), (1, second_claim)] {
+        let submit = testing::submit_claim(insured.pubkey(), policy, protocol_info, claim_index, amount, String::new());
+        let resolve = testing::resolve_claim(admin, policy, claim_index, protocol_info, CapitalPoolType::MediumRisk, true);
+        test.process(&[submit], &[&insured]).await.unwrap();
+        test.process(&[resolve], &[]).await.unwrap();
+    }
+
+    let policy_account: Policy = test.account(policy).await;
+    assert_eq!(policy_account.claim_count, 2);
+    assert_eq!(policy_account.open_claims, 2); // Approved, waiting out the challenge window
+    assert_eq!(policy_account.pending_reserved, COVERAGE);
+    assert_eq!(policy_account.remaining_coverage().unwrap(), 0);
+
+    // Nothing is left to claim
+    let submit = testing::submit_claim(insured.pubkey(), policy, protocol_info, 2, 1, String::new());
+    assert!(test.process(&[submit], &[&insured]).await.is_err());
+}
+
+#[tokio::test]
+async fn a_claim_cannot_exceed_the_remaining_coverage() {
+    let mut test = ProtocolTest::start().await;
+    let admin = test.admin();
+    let (_, protocol_info) = test.register_protocol("Partial Claims", 10_000_000).await;
+    let pool_token_account = test.initialize_pool(CapitalPoolType::MediumRisk, 500).await;
+    test.fund_pool(CapitalPoolType::MediumRisk, pool_token_account, CAPITAL).await;
+    test.process(&[testing::add_claim_resolver(admin, admin)], &[]).await.unwrap();
+    let (insured, policy) = test.buy_policy(protocol_info, CapitalPoolType::MediumRisk, COVERAGE, PREMIUM).await;
+
+    let submit = testing::submit_claim(insured.pubkey(), policy, protocol_info, 0, COVERAGE / 2, String::new());
+    let resolve = testing::resolve_claim(admin, policy, 0, protocol_info, CapitalPoolType::MediumRisk, true);
+    test.process(&[submit], &[&insured]).await.unwrap();
+    test.process(&[resolve], &[]).await.unwrap();
+
+    let submit = testing::submit_claim(insured.pubkey(), policy, protocol_info, 1, COVERAGE / 2 + 1, String::new());
+    assert!(test.process(&[submit], &[&insured]).await.is_err());
+}
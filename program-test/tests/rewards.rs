@@ -0,0 +1,40 @@
+use solana_insurance_protocol::capital_management::{CapitalPoolType, CapitalProvider, SECONDS_PER_YEAR};
+use solana_insurance_protocol::config::DEFAULT_FEE_RATES;
+use solana_insurance_protocol::integration::{capital_pool_address, capital_provider_address};
+use solana_insurance_protocol_program_test::{testing, ProtocolTest};
+use solana_sdk::signature::Signer;
+
+const CAPITAL: u64 = 1_000_000_000;
+const YIELD_RATE_BPS: u64 = 500;
+
+#[tokio::test]
+async fn rewards_accrue_with_the_clock() {
+    let mut test = ProtocolTest::start().await;
+    let pool_token_account = test.initialize_pool(CapitalPoolType::LowRisk, YIELD_RATE_BPS).await;
+    let provider = test.fund_pool(CapitalPoolType::LowRisk, pool_token_account, CAPITAL).await;
+    let position = capital_provider_address(&provider.pubkey(), &capital_pool_address(CapitalPoolType::LowRisk).0).0;
+    let checkpoint = testing::checkpoint_rewards(provider.pubkey(), CapitalPoolType::LowRisk);
+
+    // Nothing is earned until time passes
+    test.process(&[checkpoint.clone()], &[&provider]).await.unwrap();
+    let capital_provider: CapitalProvider = test.account(position).await;
+    assert_eq!(capital_provider.rewards_earned, 0);
+
+    // A year at the pool's yield, less the performance fee
+    test.warp_forward(SECONDS_PER_YEAR).await;
+    test.process(&[checkpoint], &[&provider]).await.unwrap();
+    let capital_provider: CapitalProvider = test.account(position).await;
+    let gross_rewards = CAPITAL * YIELD_RATE_BPS / 10000;
+    let performance_fee = gross_rewards * DEFAULT_FEE_RATES.lp_performance_fee_bps / 10000;
+    assert_eq!(capital_provider.rewards_earned, gross_rewards - performance_fee);
+    assert_eq!(capital_provider.accrued_until, test.now().await);
+}
+
+#[tokio::test]
+async fn provided_capital_reaches_the_pool() {
+    let mut test = ProtocolTest::start().await;
+    let pool_token_account = test.initialize_pool(CapitalPoolType::MediumRisk, YIELD_RATE_BPS).await;
+    test.fund_pool(CapitalPoolType::MediumRisk, pool_token_account, CAPITAL).await;
+
+    assert_eq!(test.token_balance(pool_token_account).await, CAPITAL);
+}
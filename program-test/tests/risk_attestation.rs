@@ -0,0 +1,62 @@
+use anchor_lang::AnchorSerialize;
+use solana_insurance_protocol::risk_assessment::calculate_composite_risk_score;
+use solana_insurance_protocol::risk_attestation::RiskAttestation;
+use solana_insurance_protocol::timelock::{ParameterChange, CHANGE_TIMELOCK_SECS};
+use solana_insurance_protocol::ProtocolInfo;
+use solana_insurance_protocol_program_test::{ed25519_instruction, testing, ProtocolTest};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+// Makes `oracle` the deployment's risk oracle through the timelock
+async fn set_risk_oracle(test: &mut ProtocolTest, oracle: Pubkey) {
+    let admin = test.admin();
+    let schedule = testing::schedule_change(admin, 0, ParameterChange::RiskOracle { risk_oracle: oracle });
+    test.process(&[schedule], &[]).await.unwrap();
+    test.warp_forward(CHANGE_TIMELOCK_SECS).await;
+    test.process(&[testing::execute_change(admin, 0)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn an_oracle_signed_attestation_rescores_the_protocol() {
+    let mut test = ProtocolTest::start().await;
+    let (_, protocol_info) = test.register_protocol("Attested", 10_000_000).await;
+    let oracle = Keypair::new();
+    set_risk_oracle(&mut test, oracle.pubkey()).await;
+
+    let attestation = RiskAttestation {
+        protocol: protocol_info,
+        code_risk: 10,
+        economic_risk: 20,
+        operational_risk: 30,
+        attested_at: test.now().await,
+    };
+    let signature = ed25519_instruction(&oracle, &attestation.try_to_vec().unwrap());
+    let submit = testing::submit_risk_attestation(attestation.clone());
+    test.process(&[signature, submit], &[]).await.unwrap();
+
+    let info: ProtocolInfo = test.account(protocol_info).await;
+    assert_eq!((info.code_risk, info.economic_risk, info.operational_risk), (10, 20, 30));
+    assert_eq!(info.risk_score, calculate_composite_risk_score(10, 20, 30, 0));
+    assert_eq!(info.last_risk_update, attestation.attested_at);
+}
+
+#[tokio::test]
+async fn an_attestation_signed_by_another_key_is_rejected() {
+    let mut test = ProtocolTest::start().await;
+    let (_, protocol_info) = test.register_protocol("Attested", 10_000_000).await;
+    set_risk_oracle(&mut test, Keypair::new().pubkey()).await;
+
+    let attestation = RiskAttestation {
+        protocol: protocol_info,
+        code_risk: 10,
+        economic_risk: 20,
+        operational_risk: 30,
+        attested_at: test.now().await,
+    };
+    let signature = ed25519_instruction(&Keypair::new(), &attestation.try_to_vec().unwrap());
+    let submit = testing::submit_risk_attestation(attestation);
+    assert!(test.process(&[signature, submit], &[]).await.is_err());
+
+    let info: ProtocolInfo = test.account(protocol_info).await;
+    assert_eq!(info.last_risk_update, 0);
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{Policy, ProtocolInfo, ErrorCode};
+
+// Written by the alert instructions so wallets can poll one account per covered protocol
+#[account]
+pub struct AlertSubscription {
+    pub subscriber: Pubkey,
+    pub protocol: Pubkey,
+    pub notification_count: u32, // Bumped on every alert activation, escalation and resolution
+    pub last_alert: Pubkey,
+    pub last_notification: AlertNotification,
+    pub last_notified_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl AlertSubscription {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // subscriber
+                           32 +     // protocol
+                           4 +      // notification_count
+                           32 +     // last_alert
+                           1 +      // last_notification
+                           8 +      // last_notified_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AlertNotification {
+    None,
+    Activated,
+    Escalated,
+    Resolved,
+}
+
+#[event]
+pub struct AlertSubscriberNotified {
+    pub subscriber: Pubkey,
+    pub protocol: Pubkey,
+    pub alert: Pubkey,
+    pub notification: AlertNotification,
+    pub notification_count: u32,
+}
+
+pub fn subscribe_to_alerts(ctx: Context<SubscribeToAlerts>) -> Result<()> {
+    let subscription = &mut ctx.accounts.alert_subscription;
+    subscription.subscriber = ctx.accounts.subscriber.key();
+    subscription.protocol = ctx.accounts.protocol_info.key();
+    subscription.notification_count = 0;
+    subscription.last_alert = Pubkey::default();
+    subscription.last_notification = AlertNotification::None;
+    subscription.last_notified_at = 0;
+    subscription.bump = ctx.bumps.alert_subscription;
+    subscription.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn unsubscribe_from_alerts(_ctx: Context<UnsubscribeFromAlerts>) -> Result<()> {
+    Ok(())
+}
+
+// Notifies the subscriptions passed in `remaining_accounts`. Each has to be writable,
+// owned by the program and subscribed to the alert's protocol.
+pub fn notify_subscribers(
+    subscriptions: &[AccountInfo],
+    protocol: Pubkey,
+    alert: Pubkey,
+    notification: AlertNotification,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    for subscription_info in subscriptions.iter() {
+        require!(subscription_info.owner == &crate::ID, ErrorCode::InvalidAlertSubscription);
+        require!(subscription_info.is_writable, ErrorCode::InvalidAlertSubscription);
+
+        let mut subscription = AlertSubscription::try_deserialize(&mut &subscription_info.data.borrow()[..])?;
+        require!(subscription.protocol == protocol, ErrorCode::InvalidAlertSubscription);
+
+        subscription.notification_count = subscription.notification_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        subscription.last_alert = alert;
+        subscription.last_notification = notification;
+        subscription.last_notified_at = now;
+        subscription.try_serialize(&mut &mut subscription_info.try_borrow_mut_data()?[..])?;
+
+        emit!(AlertSubscriberNotified {
+            subscriber: subscription.subscriber,
+            protocol,
+            alert,
+            notification,
+            notification_count: subscription.notification_count,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubscribeToAlerts<'info> {
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+
+    #[account(
+        init,
+        payer = subscriber,
+        space = AlertSubscription::SIZE,
+        seeds = [b"alert-subscription", protocol_info.key().as_ref(), subscriber.key().as_ref()],
+        bump
+    )]
+    pub alert_subscription: Account<'info, AlertSubscription>,
+
+    // Only insured users of the protocol can subscribe
+    #[account(
+        constraint = policy.insured == subscriber.key() @ ErrorCode::UnauthorizedAccess,
+        constraint = policy.protocol == protocol_info.key() @ ErrorCode::InvalidAlertSubscription
+    )]
+    pub policy: Account<'info, Policy>,
+
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnsubscribeFromAlerts<'info> {
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+
+    #[account(
+        mut,
+        close = subscriber,
+        seeds = [b"alert-subscription", alert_subscription.protocol.as_ref(), subscriber.key().as_ref()],
+        bump = alert_subscription.bump
+    )]
+    pub alert_subscription: Account<'info, AlertSubscription>,
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolRegistry, ProtocolState, ErrorCode};
+
+// Admin sign-off for one protocol authority to register while the allowlist is on
+#[account]
+pub struct RegistrationApproval {
+    pub authority: Pubkey, // Protocol authority that may register
+    pub approved_by: Pubkey,
+    pub approved_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl RegistrationApproval {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // authority
+                           32 +     // approved_by
+                           8 +      // approved_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct RegistrationAllowlistSet {
+    pub enabled: bool,
+}
+
+#[event]
+pub struct RegistrationApproved {
+    pub authority: Pubkey,
+    pub approved_by: Pubkey,
+}
+
+pub fn set_registration_allowlist(
+    ctx: Context<SetRegistrationAllowlist>,
+    enabled: bool,
+) -> Result<()> {
+    ctx.accounts.registry.allowlist_enabled = enabled;
+
+    emit!(RegistrationAllowlistSet { enabled });
+
+    Ok(())
+}
+
+pub fn approve_protocol_registration(
+    ctx: Context<ApproveProtocolRegistration>,
+    authority: Pubkey,
+) -> Result<()> {
+    let approval = &mut ctx.accounts.registration_approval;
+    approval.authority = authority;
+    approval.approved_by = ctx.accounts.admin.key();
+    approval.approved_at = Clock::get()?.unix_timestamp;
+    approval.bump = ctx.bumps.registration_approval;
+    approval.version = ACCOUNT_VERSION;
+
+    emit!(RegistrationApproved {
+        authority,
+        approved_by: approval.approved_by,
+    });
+
+    Ok(())
+}
+
+// Closing the approval only stops a future registration; a registered protocol stays
+pub fn revoke_registration_approval(_ctx: Context<RevokeRegistrationApproval>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRegistrationAllowlist<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct ApproveProtocolRegistration<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RegistrationApproval::SIZE,
+        seeds = [b"registration-approval", authority.as_ref()],
+        bump
+    )]
+    pub registration_approval: Account<'info, RegistrationApproval>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == admin.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRegistrationApproval<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"registration-approval", registration_approval.authority.as_ref()],
+        bump = registration_approval.bump
+    )]
+    pub registration_approval: Account<'info, RegistrationApproval>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == admin.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
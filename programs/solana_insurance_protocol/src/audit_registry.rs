@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolInfo, ProtocolState, ErrorCode};
+
+pub const MAX_AUDITOR_NAME_LEN: usize = 32;
+pub const MAX_AUDIT_SCOPE_LEN: usize = 64;
+
+#[account]
+pub struct AuditFirm {
+    pub auditor: Pubkey,
+    pub name: String,
+    pub is_active: bool,
+    pub audit_count: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl AuditFirm {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // auditor
+                           36 +     // name (max 32 chars + 4 bytes for string length)
+                           1 +      // is_active
+                           8 +      // audit_count
+                           1 +      // bump
+                           1;       // version
+}
+
+#[account]
+pub struct AuditRecord {
+    pub protocol: Pubkey,
+    pub auditor: Pubkey,
+    pub auditor_name: String,
+    pub audit_date: i64,
+    pub scope: String,
+    pub report_hash: [u8; 32],
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl AuditRecord {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // auditor
+                           36 +     // auditor_name (max 32 chars + 4 bytes for string length)
+                           8 +      // audit_date
+                           68 +     // scope (max 64 chars + 4 bytes for string length)
+                           32 +     // report_hash
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct AuditAttached {
+    pub protocol: Pubkey,
+    pub auditor: Pubkey,
+    pub audit_record: Pubkey,
+    pub audit_date: i64,
+    pub verified_audit_count: u8,
+}
+
+pub fn whitelist_auditor(
+    ctx: Context<WhitelistAuditor>,
+    name: String,
+) -> Result<()> {
+    require!(name.len() <= MAX_AUDITOR_NAME_LEN, ErrorCode::AuditorNameTooLong);
+
+    let audit_firm = &mut ctx.accounts.audit_firm;
+    audit_firm.auditor = ctx.accounts.auditor.key();
+    audit_firm.name = name;
+    audit_firm.is_active = true;
+    audit_firm.audit_count = 0;
+    audit_firm.bump = ctx.bumps.audit_firm;
+    audit_firm.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn set_auditor_status(
+    ctx: Context<SetAuditorStatus>,
+    is_active: bool,
+) -> Result<()> {
+    let audit_firm = &mut ctx.accounts.audit_firm;
+    audit_firm.is_active = is_active;
+
+    Ok(())
+}
+
+pub fn attach_audit(
+    ctx: Context<AttachAudit>,
+    audit_date: i64,
+    scope: String,
+    report_hash: [u8; 32],
+) -> Result<()> {
+    let audit_firm = &mut ctx.accounts.audit_firm;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let audit_record = &mut ctx.accounts.audit_record;
+    let clock = Clock::get()?;
+
+    require!(audit_firm.is_active, ErrorCode::AuditorNotWhitelisted);
+    require!(scope.len() <= MAX_AUDIT_SCOPE_LEN, ErrorCode::AuditScopeTooLong);
+    require!(
+        audit_date > 0 && audit_date <= clock.unix_timestamp,
+        ErrorCode::InvalidAuditDate
+    );
+
+    audit_record.protocol = protocol_info.key();
+    audit_record.auditor = audit_firm.auditor;
+    audit_record.auditor_name = audit_firm.name.clone();
+    audit_record.audit_date = audit_date;
+    audit_record.scope = scope;
+    audit_record.report_hash = report_hash;
+    audit_record.bump = ctx.bumps.audit_record;
+    audit_record.version = ACCOUNT_VERSION;
+
+    audit_firm.audit_count = audit_firm.audit_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    // Code risk is assessed from verified audits only
+    protocol_info.verified_audit_count = protocol_info.verified_audit_count.saturating_add(1);
+    protocol_info.last_audit_date = std::cmp::max(protocol_info.last_audit_date, audit_date);
+
+    emit!(AuditAttached {
+        protocol: protocol_info.key(),
+        auditor: audit_record.auditor,
+        audit_record: audit_record.key(),
+        audit_date,
+        verified_audit_count: protocol_info.verified_audit_count,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WhitelistAuditor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: the audit firm's signing key, only stored
+    pub auditor: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuditFirm::SIZE,
+        seeds = [b"audit-firm", auditor.key().as_ref()],
+        bump
+    )]
+    pub audit_firm: Account<'info, AuditFirm>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuditorStatus<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"audit-firm", audit_firm.auditor.as_ref()],
+        bump = audit_firm.bump
+    )]
+    pub audit_firm: Account<'info, AuditFirm>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(audit_date: i64, scope: String, report_hash: [u8; 32])]
+pub struct AttachAudit<'info> {
+    #[account(mut)]
+    pub auditor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"audit-firm", auditor.key().as_ref()],
+        bump = audit_firm.bump
+    )]
+    pub audit_firm: Account<'info, AuditFirm>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        init,
+        payer = auditor,
+        space = AuditRecord::SIZE,
+        seeds = [b"audit-record", protocol_info.key().as_ref(), report_hash.as_ref()],
+        bump
+    )]
+    pub audit_record: Account<'info, AuditRecord>,
+
+    pub system_program: Program<'info, System>,
+}
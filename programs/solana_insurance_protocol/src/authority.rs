@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::{ProtocolState, ErrorCode};
+
+// Squads v4 multisig program. Its vault PDAs sign for the multisig when an
+// approved transaction is executed, so they can hold the protocol authority.
+pub const SQUADS_PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6hgBo8T7ToQsEeKyAaVHe");
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub multisig: Pubkey, // Default key unless the new authority is a multisig vault
+}
+
+// Address of the vault the Squads multisig signs with for `vault_index`
+pub fn squads_vault_address(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"multisig", multisig.as_ref(), b"vault", &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    ).0
+}
+
+// First step of a two-step handover; a new proposal replaces any pending one
+pub fn propose_authority_transfer(
+    ctx: Context<ProposeAuthorityTransfer>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    protocol_state.pending_authority = new_authority;
+
+    emit!(AuthorityTransferProposed {
+        current_authority: protocol_state.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+// The new authority must sign to accept, which proves it can sign at all. For a
+// multisig this instruction is executed through the multisig program, with the vault
+// PDA signing; passing the multisig account records it after checking the vault.
+pub fn accept_authority_transfer(
+    ctx: Context<AcceptAuthorityTransfer>,
+    vault_index: u8,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    require!(protocol_state.pending_authority != Pubkey::default(), ErrorCode::NoPendingAuthority);
+    require!(protocol_state.pending_authority == new_authority, ErrorCode::UnauthorizedAccess);
+
+    let multisig = match &ctx.accounts.multisig {
+        Some(multisig) => {
+            require!(multisig.owner == &SQUADS_PROGRAM_ID, ErrorCode::InvalidMultisigAuthority);
+            require!(
+                squads_vault_address(multisig.key, vault_index) == new_authority,
+                ErrorCode::InvalidMultisigAuthority
+            );
+            multisig.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    let previous_authority = protocol_state.authority;
+    protocol_state.authority = new_authority;
+    protocol_state.authority_multisig = multisig;
+    protocol_state.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferred {
+        previous_authority,
+        new_authority,
+        multisig,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// CHECK: Only read when the new authority is a Squads vault; the owner and
+    /// vault derivation are checked in the handler
+    pub multisig: Option<UncheckedAccount<'info>>,
+}
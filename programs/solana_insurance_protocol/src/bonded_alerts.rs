@@ -0,0 +1,294 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PERMISSIONLESS_ALERTS};
+use crate::exploit_detection::{
+    AlertCreated, AlertSeverity, AnomalyType, ExploitAlert, SuspiciousTransaction,
+    MAX_AFFECTED_ACCOUNTS, MAX_DETAILS_URI_LEN, MAX_SUSPICIOUS_TXS,
+};
+use crate::incident::record_alert_raised;
+use crate::watchers::{WatcherRegistry, MAX_ALERT_CONFIRMATIONS};
+use crate::{CapitalPool, CapitalPoolType, ProtocolInfo, ProtocolState, ErrorCode};
+
+#[event]
+pub struct AlertBondPosted {
+    pub alert: Pubkey,
+    pub reporter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AlertBondSettled {
+    pub alert: Pubkey,
+    pub reporter: Pubkey,
+    pub amount: u64,
+    pub is_forfeited: bool,
+}
+
+// Lets any wallet raise an alert by posting a bond in the stake mint instead of
+// registering as a watcher. The reporter doesn't count as a confirmation, so the
+// alert only triggers responses once enough staked watchers confirm it.
+pub fn create_bonded_alert(
+    ctx: Context<CreateBondedAlert>,
+    anomaly_type: AnomalyType,
+    severity: AlertSeverity,
+    details_hash: [u8; 32],
+    details_uri: String,
+) -> Result<()> {
+    let watcher_registry = &mut ctx.accounts.watcher_registry;
+    let config = ctx.accounts.protocol_state.alert_response;
+    let clock = Clock::get()?;
+
+    let bond = watcher_registry.alert_bond;
+    require!(
+        ctx.accounts.config.is_enabled(FEATURE_PERMISSIONLESS_ALERTS),
+        ErrorCode::FeatureDisabled
+    );
+    require!(bond > 0, ErrorCode::BondedAlertsDisabled);
+    require!(details_uri.len() <= MAX_DETAILS_URI_LEN, ErrorCode::DetailsUriTooLong);
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.reporter_token.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.reporter.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer_checked(cpi_ctx, bond, ctx.accounts.stake_mint.decimals)?;
+
+    watcher_registry.total_bonded = watcher_registry.total_bonded.checked_add(bond).ok_or(ErrorCode::MathOverflow)?;
+
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    exploit_alert.protocol = protocol_info.key();
+    exploit_alert.reporter = ctx.accounts.reporter.key();
+    exploit_alert.alert_time = clock.unix_timestamp;
+    exploit_alert.anomaly_type = anomaly_type;
+    exploit_alert.severity = severity;
+    exploit_alert.details_hash = details_hash;
+    exploit_alert.details_uri = details_uri;
+    exploit_alert.is_confirmed = false;
+    exploit_alert.is_resolved = false;
+    exploit_alert.resolution_notes = String::new();
+    exploit_alert.response_tier = config.response_tier(severity);
+    exploit_alert.affected_pool = ctx.accounts.affected_pool.as_ref().map_or(Pubkey::default(), |pool| pool.key());
+    exploit_alert.bounty_claimed = false;
+    exploit_alert.is_malicious = false;
+    exploit_alert.expires_at = clock.unix_timestamp.checked_add(config.alert_ttl_secs).ok_or(ErrorCode::MathOverflow)?;
+    exploit_alert.confirmers = [Pubkey::default(); MAX_ALERT_CONFIRMATIONS];
+    exploit_alert.confirmation_count = 0;
+    exploit_alert.required_confirmations = watcher_registry.confirmation_threshold;
+    exploit_alert.is_active = false;
+    exploit_alert.ack_deadline = 0;
+    exploit_alert.is_acknowledged = false;
+    exploit_alert.is_surcharging = false;
+    exploit_alert.bond_amount = bond;
+    exploit_alert.bond_settled = false;
+    exploit_alert.suspicious_txs = [SuspiciousTransaction::EMPTY; MAX_SUSPICIOUS_TXS];
+    exploit_alert.suspicious_tx_count = 0;
+    exploit_alert.affected_accounts = [Pubkey::default(); MAX_AFFECTED_ACCOUNTS];
+    exploit_alert.affected_account_count = 0;
+    exploit_alert.bump = ctx.bumps.exploit_alert;
+    exploit_alert.version = ACCOUNT_VERSION;
+
+    protocol_info.alerts_raised = protocol_info.alerts_raised.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    record_alert_raised(protocol_info)?;
+
+    emit!(AlertCreated {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        reporter: exploit_alert.reporter,
+        anomaly_type,
+        severity,
+        response_tier: exploit_alert.response_tier,
+        slot: clock.slot,
+    });
+    emit!(AlertBondPosted {
+        alert: exploit_alert.key(),
+        reporter: exploit_alert.reporter,
+        amount: bond,
+    });
+
+    Ok(())
+}
+
+// Returns the bond once the alert is resolved as confirmed or expires unresolved.
+// Bonds of false alerts are forfeited when they are resolved.
+pub fn claim_alert_bond(ctx: Context<ClaimAlertBond>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let watcher_registry = &mut ctx.accounts.watcher_registry;
+
+    require!(exploit_alert.is_resolved, ErrorCode::AlertNotResolved);
+    require!(
+        exploit_alert.bond_amount > 0 && !exploit_alert.bond_settled,
+        ErrorCode::AlertBondNotClaimable
+    );
+
+    let amount = exploit_alert.bond_amount;
+    exploit_alert.bond_settled = true;
+    watcher_registry.total_bonded = watcher_registry.total_bonded.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"watcher-registry".as_ref(),
+        &[watcher_registry.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.reporter_token.to_account_info(),
+        authority: watcher_registry.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+    emit!(AlertBondSettled {
+        alert: exploit_alert.key(),
+        reporter: exploit_alert.reporter,
+        amount,
+        is_forfeited: false,
+    });
+
+    Ok(())
+}
+
+// Sends the bond of an alert resolved as false to the treasury
+pub fn forfeit_alert_bond<'info>(
+    exploit_alert: &mut Account<'info, ExploitAlert>,
+    watcher_registry: &mut Account<'info, WatcherRegistry>,
+    stake_vault: &Account<'info, TokenAccount>,
+    treasury_token: &Account<'info, TokenAccount>,
+    stake_mint: &Account<'info, Mint>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let amount = exploit_alert.bond_amount;
+    exploit_alert.bond_settled = true;
+    watcher_registry.total_bonded = watcher_registry.total_bonded.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"watcher-registry".as_ref(),
+        &[watcher_registry.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: stake_vault.to_account_info(),
+        mint: stake_mint.to_account_info(),
+        to: treasury_token.to_account_info(),
+        authority: watcher_registry.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, stake_mint.decimals)?;
+
+    emit!(AlertBondSettled {
+        alert: exploit_alert.key(),
+        reporter: exploit_alert.reporter,
+        amount,
+        is_forfeited: true,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateBondedAlert<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    #[account(
+        init,
+        payer = reporter,
+        space = ExploitAlert::SIZE,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Pool of the protocol's risk tier, whose withdrawals are paused by a critical alert
+    #[account(
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = affected_pool.pool_type == CapitalPoolType::for_risk_score(protocol_info.risk_score) @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        mut,
+        constraint = stake_vault.key() == watcher_registry.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = reporter_token.mint == watcher_registry.stake_mint,
+        constraint = reporter_token.owner == reporter.key()
+    )]
+    pub reporter_token: Account<'info, TokenAccount>,
+
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAlertBond<'info> {
+    pub reporter: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = exploit_alert.reporter == reporter.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        mut,
+        constraint = stake_vault.key() == watcher_registry.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = reporter_token.mint == watcher_registry.stake_mint,
+        constraint = reporter_token.owner == reporter.key()
+    )]
+    pub reporter_token: Account<'info, TokenAccount>,
+
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
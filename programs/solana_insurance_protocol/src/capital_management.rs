@@ -1,15 +1,92 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{ProtocolState, ErrorCode};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::Config;
+use crate::global_stats::{GlobalStats, POOL_TYPE_COUNT};
+use crate::governance::{lp_performance_fee_bps, Governance, GovernanceStake};
+use crate::math::{mul_div_ceil, mul_div_floor};
+use crate::mint_registry::MintRegistry;
+use crate::risk_assessment::{assess_loss_experience, calculate_weighted_risk_score, DEFAULT_RISK_WEIGHTS};
+use crate::{ProtocolInfo, ProtocolState, RiskWeights, ErrorCode};
 
-// Capital pool types
-pub const CAPITAL_POOL_LOW_RISK: u8 = 1;
-pub const CAPITAL_POOL_MEDIUM_RISK: u8 = 2;
-pub const CAPITAL_POOL_HIGH_RISK: u8 = 3;
+// Capital pool tiers. Each variant's Borsh index is the u8 the pool type used to be
+// stored as, and is the pool's PDA seed, so existing pools read back unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CapitalPoolType {
+    Unassigned, // Index 0 was never a pool type
+    LowRisk,
+    MediumRisk,
+    HighRisk,
+}
+
+// Every tier a pool can be created for, in pool type order
+pub const POOL_TYPES: [CapitalPoolType; POOL_TYPE_COUNT] = [
+    CapitalPoolType::LowRisk,
+    CapitalPoolType::MediumRisk,
+    CapitalPoolType::HighRisk,
+];
+
+// Highest risk score (under the pool's own weights) each tier accepts by default
+pub fn default_max_risk_score(pool_type: CapitalPoolType) -> u8 {
+    match pool_type {
+        CapitalPoolType::LowRisk => 25,
+        CapitalPoolType::MediumRisk => 75,
+        _ => 100,
+    }
+}
+
+// Extra controls on a tier's payouts, on top of the protocol-wide challenge window.
+// Zero leaves a limit off, which is how pools from before these limits read back.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PayoutLimits {
+    pub challenge_window_secs: i64, // Minimum wait between approval and payout
+    pub max_claim_payout: u64,      // Largest single payout
+    pub daily_payout_cap: u64,      // Total paid out per UTC day
+}
+
+impl PayoutLimits {
+    pub const SIZE: usize = 8 +     // challenge_window_secs
+                           8 +      // max_claim_payout
+                           8;       // daily_payout_cap
+
+    // With a daily cap set, an uncapped (or larger) single payout could be approved but never paid
+    pub fn is_valid(&self) -> bool {
+        self.challenge_window_secs >= 0 &&
+        (self.daily_payout_cap == 0 || (self.max_claim_payout > 0 && self.max_claim_payout <= self.daily_payout_cap))
+    }
+}
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+// High-risk pools wait longer before paying out by default. Amount caps depend on the
+// pool's token, so they start off and are set through `ParameterChange::PoolPayoutLimits`.
+pub fn default_payout_limits(pool_type: CapitalPoolType) -> PayoutLimits {
+    let challenge_window_secs = match pool_type {
+        CapitalPoolType::HighRisk => 3 * SECS_PER_DAY,
+        CapitalPoolType::MediumRisk => 2 * SECS_PER_DAY,
+        _ => 0,
+    };
+    PayoutLimits {
+        challenge_window_secs,
+        max_claim_payout: 0,
+        daily_payout_cap: 0,
+    }
+}
+
+impl CapitalPoolType {
+    // Tier a protocol's risk score falls in under the default tier ceilings. Exploit alerts
+    // act on this tier's pool, so whoever raises one can't pick which pool gets frozen.
+    pub fn for_risk_score(risk_score: u8) -> Self {
+        [CapitalPoolType::LowRisk, CapitalPoolType::MediumRisk]
+            .into_iter()
+            .find(|pool_type| risk_score <= default_max_risk_score(*pool_type))
+            .unwrap_or(CapitalPoolType::HighRisk)
+    }
+}
 
 #[account]
 pub struct CapitalPool {
-    pub pool_type: u8,
+    pub pool_type: CapitalPoolType,
     pub total_capital: u64,
     pub available_capital: u64,
     pub reserved_capital: u64,
@@ -17,7 +94,20 @@ pub struct CapitalPool {
     pub token_mint: Pubkey,
     pub token_account: Pubkey,
     pub authority: Pubkey,
+    pub risk_weights: RiskWeights,
+    pub max_risk_score: u8,
+    pub active_coverage: u64,
+    pub reinsurance_capacity: u64,
+    pub withdrawals_paused: bool,
+    pub payouts_frozen: bool,     // Set by the emergency council
     pub bump: u8,
+    pub version: u8,
+    // Version 7
+    pub token_decimals: u8,       // Checked on every transfer of the pool's tokens
+    // Version 13
+    pub payout_limits: PayoutLimits,
+    pub payout_day: i64,          // UTC day `paid_in_day` counts payouts for
+    pub paid_in_day: u64,
 }
 
 impl CapitalPool {
@@ -30,7 +120,75 @@ impl CapitalPool {
                            32 +    // token_mint
                            32 +    // token_account
                            32 +    // authority
-                           1;      // bump
+                           RiskWeights::SIZE + // risk_weights
+                           1 +     // max_risk_score
+                           8 +     // active_coverage
+                           8 +     // reinsurance_capacity
+                           1 +     // withdrawals_paused
+                           1 +     // payouts_frozen
+                           1 +     // bump
+                           1 +     // version
+                           1 +     // token_decimals
+                           PayoutLimits::SIZE + // payout_limits
+                           8 +     // payout_day
+                           8;      // paid_in_day
+    
+    // Scores the protocol with this tier's weighting profile and checks it against the tier ceiling
+    pub fn accepts(&self, protocol_info: &ProtocolInfo) -> bool {
+        let loss_experience = assess_loss_experience(
+            protocol_info.premiums_earned,
+            protocol_info.claims_paid,
+        );
+        let tier_risk_score = calculate_weighted_risk_score(
+            protocol_info.code_risk,
+            protocol_info.economic_risk,
+            protocol_info.operational_risk,
+            loss_experience,
+            &self.risk_weights,
+        );
+        
+        tier_risk_score <= self.max_risk_score
+    }
+    
+    // The longer of the protocol-wide challenge window and the tier's own
+    pub fn challenge_window_secs(&self, config_window_secs: i64) -> i64 {
+        config_window_secs.max(self.payout_limits.challenge_window_secs)
+    }
+    
+    // Rejects a payout no single day's cap could ever fit, as well as one over the tier's maximum
+    pub fn check_claim_payout(&self, amount: u64) -> Result<()> {
+        let max_claim_payout = self.payout_limits.max_claim_payout;
+        require!(
+            max_claim_payout == 0 || amount <= max_claim_payout,
+            ErrorCode::ClaimExceedsPoolPayoutMax
+        );
+        let daily_payout_cap = self.payout_limits.daily_payout_cap;
+        require!(
+            daily_payout_cap == 0 || amount <= daily_payout_cap,
+            ErrorCode::DailyPayoutCapExceeded
+        );
+        Ok(())
+    }
+    
+    // Counts a payout against the tier's limits, starting a new day's tally when the UTC day rolls over
+    pub fn record_payout(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.check_claim_payout(amount)?;
+        
+        let day = now.div_euclid(SECS_PER_DAY);
+        if day != self.payout_day {
+            self.payout_day = day;
+            self.paid_in_day = 0;
+        }
+        let paid_in_day = self.paid_in_day.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let daily_payout_cap = self.payout_limits.daily_payout_cap;
+        require!(
+            daily_payout_cap == 0 || paid_in_day <= daily_payout_cap,
+            ErrorCode::DailyPayoutCapExceeded
+        );
+        self.paid_in_day = paid_in_day;
+        
+        Ok(())
+    }
 }
 
 #[account]
@@ -39,8 +197,9 @@ pub struct CapitalProvider {
     pub capital_amount: u64,
     pub pool: Pubkey,
     pub rewards_earned: u64,
-    pub deposit_time: i64,
+    pub accrued_until: i64, // Rewards are checkpointed up to here on every deposit, withdrawal and checkpoint
     pub bump: u8,
+    pub version: u8,
 }
 
 impl CapitalProvider {
@@ -49,23 +208,89 @@ impl CapitalProvider {
                            8 +     // capital_amount
                            32 +    // pool
                            8 +     // rewards_earned
-                           8 +     // deposit_time
-                           1;      // bump
+                           8 +     // accrued_until
+                           1 +     // bump
+                           1;      // version
+    
+    // Adds the rewards accrued on the current capital since the last checkpoint, less the
+    // performance fee. Accrual is per second, so checkpointing again at the same time adds nothing.
+    pub fn checkpoint_rewards(&mut self, yield_rate_bps: u64, performance_fee_bps: u64, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.accrued_until).max(0) as u128;
+        let gross_rewards = mul_div_floor(
+            self.capital_amount,
+            yield_rate_bps as u128 * elapsed,
+            10000 * SECONDS_PER_YEAR as u128,
+        ).ok_or(ErrorCode::MathOverflow)?;
+        let performance_fee = mul_div_ceil(gross_rewards, performance_fee_bps as u128, 10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let rewards = gross_rewards.checked_sub(performance_fee).ok_or(ErrorCode::MathOverflow)?;
+        
+        self.rewards_earned = self.rewards_earned.checked_add(rewards).ok_or(ErrorCode::MathOverflow)?;
+        self.accrued_until = now;
+        
+        Ok(rewards)
+    }
+}
+
+pub const SECONDS_PER_YEAR: i64 = 365 * 86400;
+
+#[event]
+pub struct CapitalPoolInitialized {
+    pub capital_pool: Pubkey,
+    pub pool_type: CapitalPoolType,
+    pub token_mint: Pubkey,
+    pub yield_rate_bps: u64,
+}
+
+#[event]
+pub struct PoolWithdrawalsPausedSet {
+    pub capital_pool: Pubkey,
+    pub withdrawals_paused: bool,
+}
+
+#[event]
+pub struct CapitalProvided {
+    pub capital_pool: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub total_capital: u64,
+    pub available_capital: u64,
+}
+
+#[event]
+pub struct CapitalWithdrawn {
+    pub capital_pool: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub rewards_earned: u64,
+    pub total_capital: u64,
+    pub available_capital: u64,
+}
+
+#[event]
+pub struct RewardsCheckpointed {
+    pub capital_pool: Pubkey,
+    pub provider: Pubkey,
+    pub rewards: u64,
+    pub rewards_earned: u64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub capital_pool: Pubkey,
+    pub provider: Pubkey,
+    pub rewards_earned: u64,
 }
 
 pub fn initialize_capital_pool(
     ctx: Context<InitializeCapitalPool>,
-    pool_type: u8,
+    pool_type: CapitalPoolType,
     yield_rate_bps: u64,
 ) -> Result<()> {
     let capital_pool = &mut ctx.accounts.capital_pool;
     
-    require!(
-        pool_type == CAPITAL_POOL_LOW_RISK || 
-        pool_type == CAPITAL_POOL_MEDIUM_RISK || 
-        pool_type == CAPITAL_POOL_HIGH_RISK,
-        ErrorCode::InvalidPoolType
-    );
+    require!(pool_type != CapitalPoolType::Unassigned, ErrorCode::InvalidPoolType);
+    ctx.accounts.mint_registry.accepted(&ctx.accounts.token_mint)?;
     
     capital_pool.pool_type = pool_type;
     capital_pool.total_capital = 0;
@@ -75,7 +300,40 @@ pub fn initialize_capital_pool(
     capital_pool.token_mint = ctx.accounts.token_mint.key();
     capital_pool.token_account = ctx.accounts.pool_token_account.key();
     capital_pool.authority = ctx.accounts.authority.key();
+    capital_pool.risk_weights = DEFAULT_RISK_WEIGHTS;
+    capital_pool.max_risk_score = default_max_risk_score(pool_type);
+    capital_pool.active_coverage = 0;
+    capital_pool.reinsurance_capacity = 0;
+    capital_pool.withdrawals_paused = false;
+    capital_pool.payouts_frozen = false;
     capital_pool.bump = ctx.bumps.capital_pool;
+    capital_pool.version = ACCOUNT_VERSION;
+    capital_pool.token_decimals = ctx.accounts.token_mint.decimals;
+    capital_pool.payout_limits = default_payout_limits(pool_type);
+    capital_pool.payout_day = 0;
+    capital_pool.paid_in_day = 0;
+    
+    emit!(CapitalPoolInitialized {
+        capital_pool: capital_pool.key(),
+        pool_type,
+        token_mint: capital_pool.token_mint,
+        yield_rate_bps,
+    });
+    
+    Ok(())
+}
+
+pub fn set_pool_withdrawals_paused(
+    ctx: Context<ConfigureCapitalPool>,
+    withdrawals_paused: bool,
+) -> Result<()> {
+    let capital_pool = &mut ctx.accounts.capital_pool;
+    capital_pool.withdrawals_paused = withdrawals_paused;
+    
+    emit!(PoolWithdrawalsPausedSet {
+        capital_pool: capital_pool.key(),
+        withdrawals_paused,
+    });
     
     Ok(())
 }
@@ -89,21 +347,41 @@ pub fn provide_capital(
     let capital_pool = &mut ctx.accounts.capital_pool;
     let clock = Clock::get()?;
     
-    // Initialize the capital provider account
-    capital_provider.owner = ctx.accounts.owner.key();
-    capital_provider.capital_amount = amount;
-    capital_provider.pool = pool_key; 
-    capital_provider.rewards_earned = 0;
-    capital_provider.deposit_time = clock.unix_timestamp;
-    capital_provider.bump = ctx.bumps.capital_provider;
+    require!(!ctx.accounts.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    
+    // A new position starts accruing now; an existing one banks what it earned on its
+    // previous capital before the deposit is added
+    if capital_provider.owner == Pubkey::default() {
+        capital_provider.owner = ctx.accounts.owner.key();
+        capital_provider.capital_amount = 0;
+        capital_provider.pool = pool_key;
+        capital_provider.rewards_earned = 0;
+        capital_provider.accrued_until = clock.unix_timestamp;
+        capital_provider.bump = ctx.bumps.capital_provider;
+        capital_provider.version = ACCOUNT_VERSION;
+    } else {
+        let performance_fee_bps = lp_performance_fee_bps(
+            &ctx.accounts.config.fee_rates,
+            ctx.accounts.governance.as_deref(),
+            ctx.accounts.governance_stake.as_deref(),
+            capital_provider.accrued_until,
+        )?;
+        capital_provider.checkpoint_rewards(capital_pool.yield_rate_bps, performance_fee_bps, clock.unix_timestamp)?;
+    }
+    capital_provider.capital_amount = capital_provider.capital_amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
     
-    // Update the capital pool
-    capital_pool.total_capital = capital_pool.total_capital.checked_add(amount).unwrap();
-    capital_pool.available_capital = capital_pool.available_capital.checked_add(amount).unwrap();
+    // Update the capital pool, within its token's cap
+    capital_pool.total_capital = capital_pool.total_capital.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.mint_registry
+        .accepted(&ctx.accounts.token_mint)?
+        .check_pool_capital(capital_pool.total_capital)?;
+    capital_pool.available_capital = capital_pool.available_capital.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.global_stats.add_pool_capital(capital_pool.pool_type, amount)?;
     
     // Transfer funds from the provider's token account to the pool's token account
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.provider_token.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
         to: ctx.accounts.pool_token_account.to_account_info(),
         authority: ctx.accounts.owner.to_account_info(),
     };
@@ -111,7 +389,15 @@ pub fn provide_capital(
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer_checked(cpi_ctx, amount, capital_pool.token_decimals)?;
+    
+    emit!(CapitalProvided {
+        capital_pool: pool_key,
+        provider: capital_provider.owner,
+        amount,
+        total_capital: capital_pool.total_capital,
+        available_capital: capital_pool.available_capital,
+    });
     
     Ok(())
 }
@@ -120,20 +406,47 @@ pub fn withdraw_capital(
     ctx: Context<WithdrawCapital>,
     amount: u64,
 ) -> Result<()> {
-    let capital_provider = &mut ctx.accounts.capital_provider;
-    let capital_pool = &mut ctx.accounts.capital_pool;
+    withdraw(ctx.accounts, amount)
+}
+
+// Withdraws all of a provider's capital and closes the position in one transaction,
+// returning its rent to the owner
+pub fn withdraw_and_close_position(ctx: Context<WithdrawCapital>) -> Result<()> {
+    let amount = ctx.accounts.capital_provider.capital_amount;
+    withdraw(ctx.accounts, amount)?;
+    
+    let capital_provider = &ctx.accounts.capital_provider;
+    emit!(PositionClosed {
+        capital_pool: capital_provider.pool,
+        provider: capital_provider.owner,
+        rewards_earned: capital_provider.rewards_earned,
+    });
+    
+    ctx.accounts.capital_provider.close(ctx.accounts.owner.to_account_info())
+}
+
+fn withdraw(accounts: &mut WithdrawCapital, amount: u64) -> Result<()> {
+    let capital_provider = &mut accounts.capital_provider;
+    let capital_pool = &mut accounts.capital_pool;
     let clock = Clock::get()?;
     
-    // Calculate rewards based on time and yield rate
-    let time_held = clock.unix_timestamp - capital_provider.deposit_time;
-    let days_held = std::cmp::max(time_held / 86400, 1) as u64; // At least 1 day
-    
-    let annual_yield = (capital_provider.capital_amount * capital_pool.yield_rate_bps) / 10000;
-    let daily_yield = annual_yield / 365;
-    let rewards = daily_yield * days_held;
+    // Critical exploit alerts freeze withdrawals from the affected tier, or from every tier
+    // while the global circuit breaker is tripped
+    require!(!accounts.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    require!(!capital_pool.withdrawals_paused, ErrorCode::PoolWithdrawalsPaused);
+    require!(
+        !accounts.protocol_state.circuit_breaker.is_tripped,
+        ErrorCode::CircuitBreakerTripped
+    );
     
-    // Update rewards earned
-    capital_provider.rewards_earned = capital_provider.rewards_earned.checked_add(rewards).unwrap();
+    // Bank the rewards earned on the capital held so far
+    let performance_fee_bps = lp_performance_fee_bps(
+        &accounts.config.fee_rates,
+        accounts.governance.as_deref(),
+        accounts.governance_stake.as_deref(),
+        capital_provider.accrued_until,
+    )?;
+    capital_provider.checkpoint_rewards(capital_pool.yield_rate_bps, performance_fee_bps, clock.unix_timestamp)?;
     
     // Check if there's enough available capital
     require!(
@@ -147,57 +460,95 @@ pub fn withdraw_capital(
         ErrorCode::InsufficientProviderCapital
     );
     
+    // A vault holding less than the accounting says needs sync_pool_balance first
+    require!(
+        accounts.pool_token_account.amount >= amount,
+        ErrorCode::PoolBalanceShortfall
+    );
+    
     // Update capital provider balance
-    capital_provider.capital_amount = capital_provider.capital_amount.checked_sub(amount).unwrap();
+    capital_provider.capital_amount = capital_provider.capital_amount.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
     
     // Update the capital pool
-    capital_pool.total_capital = capital_pool.total_capital.checked_sub(amount).unwrap();
-    capital_pool.available_capital = capital_pool.available_capital.checked_sub(amount).unwrap();
+    capital_pool.total_capital = capital_pool.total_capital.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    capital_pool.available_capital = capital_pool.available_capital.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    accounts.global_stats.remove_pool_capital(capital_pool.pool_type, amount);
     
     // Transfer funds from the pool's token account to the provider's token account
     // We need to sign with the PDA
     let seeds = &[
         b"capital-pool", 
-        &[capital_pool.pool_type][..],
+        &[capital_pool.pool_type as u8][..],
         &[capital_pool.bump]
     ];
     let signer = &[&seeds[..]];
     
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.pool_token_account.to_account_info(),
-        to: ctx.accounts.provider_token.to_account_info(),
-        authority: ctx.accounts.capital_pool.to_account_info(),
+    let cpi_accounts = TransferChecked {
+        from: accounts.pool_token_account.to_account_info(),
+        mint: accounts.token_mint.to_account_info(),
+        to: accounts.provider_token.to_account_info(),
+        authority: accounts.capital_pool.to_account_info(),
     };
     
-    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_program = accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
     
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer_checked(cpi_ctx, amount, accounts.capital_pool.token_decimals)?;
     
-    // If the provider has withdrawn all capital, close the account
-    if capital_provider.capital_amount == 0 {
-        // Transfer the rent back to the owner
-        let dest_starting_lamports = ctx.accounts.owner.lamports();
-        let provider_lamports = ctx.accounts.capital_provider.to_account_info().lamports();
-        
-        **ctx.accounts.owner.lamports.borrow_mut() = dest_starting_lamports
-            .checked_add(provider_lamports)
-            .unwrap();
-        **ctx.accounts.capital_provider.to_account_info().lamports.borrow_mut() = 0;
-        
-        // Zero out the data
-        let capital_provider_info = ctx.accounts.capital_provider.to_account_info();
-        let mut data = capital_provider_info.data.borrow_mut();
-        for byte in data.iter_mut() {
-            *byte = 0;
-        }
-    }
+    emit!(CapitalWithdrawn {
+        capital_pool: accounts.capital_pool.key(),
+        provider: accounts.owner.key(),
+        amount,
+        rewards_earned: capital_provider.rewards_earned,
+        total_capital: accounts.capital_pool.total_capital,
+        available_capital: accounts.capital_pool.available_capital,
+    });
+    
+    Ok(())
+}
+
+// Banks accrued rewards without moving capital, so rewards_earned reads current on-chain.
+// Owner-only, since the staker discount depends on the owner passing their stake.
+pub fn checkpoint_rewards(ctx: Context<CheckpointRewards>) -> Result<()> {
+    let capital_provider = &mut ctx.accounts.capital_provider;
+    let performance_fee_bps = lp_performance_fee_bps(
+        &ctx.accounts.config.fee_rates,
+        ctx.accounts.governance.as_deref(),
+        ctx.accounts.governance_stake.as_deref(),
+        capital_provider.accrued_until,
+    )?;
+    let rewards = capital_provider.checkpoint_rewards(
+        ctx.accounts.capital_pool.yield_rate_bps,
+        performance_fee_bps,
+        Clock::get()?.unix_timestamp,
+    )?;
+    
+    emit!(RewardsCheckpointed {
+        capital_pool: ctx.accounts.capital_pool.key(),
+        provider: capital_provider.owner,
+        rewards,
+        rewards_earned: capital_provider.rewards_earned,
+    });
+    
+    Ok(())
+}
+
+// Closes a fully withdrawn position, returning its rent to the owner
+pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    let capital_provider = &ctx.accounts.capital_provider;
+    require!(capital_provider.capital_amount == 0, ErrorCode::PositionNotEmpty);
+    
+    emit!(PositionClosed {
+        capital_pool: capital_provider.pool,
+        provider: capital_provider.owner,
+        rewards_earned: capital_provider.rewards_earned,
+    });
     
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(pool_type: u8, yield_rate_bps: u64)] 
+#[instruction(pool_type: CapitalPoolType, yield_rate_bps: u64)] 
 pub struct InitializeCapitalPool<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -206,12 +557,12 @@ pub struct InitializeCapitalPool<'info> {
         init,
         payer = authority,
         space = CapitalPool::SIZE,
-        seeds = [b"capital-pool", &[pool_type][..]],
+        seeds = [b"capital-pool", &[pool_type as u8][..]],
         bump
     )]
     pub capital_pool: Account<'info, CapitalPool>,
     
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    pub token_mint: Account<'info, Mint>,
     
     #[account(
         constraint = pool_token_account.mint == token_mint.key(),
@@ -228,6 +579,31 @@ pub struct InitializeCapitalPool<'info> {
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub rent: Sysvar<'info, Rent>,
+    
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCapitalPool<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
 }
 
 #[derive(Accounts)]
@@ -235,8 +611,9 @@ pub struct ProvideCapital<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
     
+    // Topped up when the owner already has a position in the pool
     #[account(
-        init,
+        init_if_needed,
         payer = owner,
         space = CapitalProvider::SIZE,
         seeds = [b"capital-provider", owner.key().as_ref(), capital_pool.key().as_ref()],
@@ -244,7 +621,11 @@ pub struct ProvideCapital<'info> {
     )]
     pub capital_provider: Account<'info, CapitalProvider>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
     pub capital_pool: Account<'info, CapitalPool>,
     
     #[account(
@@ -257,13 +638,54 @@ pub struct ProvideCapital<'info> {
     #[account(
         mut,
         constraint = pool_token_account.mint == capital_pool.token_mint,
-        constraint = pool_token_account.key() == capital_pool.token_account
+        constraint = pool_token_account.key() == capital_pool.token_account @ ErrorCode::InvalidPoolTokenAccount
     )]
     pub pool_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    
     pub system_program: Program<'info, System>,
+    #[account(address = capital_pool.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
     pub token_program: Program<'info, Token>,
     pub rent: Sysvar<'info, Rent>,
+    
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+    
+    // Only needed for the governance staker discount on the performance fee
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Option<Account<'info, Governance>>,
+    
+    #[account(
+        seeds = [b"governance-stake", owner.key().as_ref()],
+        bump = governance_stake.bump
+    )]
+    pub governance_stake: Option<Account<'info, GovernanceStake>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
@@ -279,7 +701,11 @@ pub struct WithdrawCapital<'info> {
     )]
     pub capital_provider: Account<'info, CapitalProvider>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
     pub capital_pool: Account<'info, CapitalPool>,
     
     #[account(
@@ -292,10 +718,98 @@ pub struct WithdrawCapital<'info> {
     #[account(
         mut,
         constraint = pool_token_account.mint == capital_pool.token_mint,
-        constraint = pool_token_account.key() == capital_pool.token_account
+        constraint = pool_token_account.key() == capital_pool.token_account @ ErrorCode::InvalidPoolTokenAccount
     )]
     pub pool_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    
     pub system_program: Program<'info, System>,
+    #[account(address = capital_pool.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
     pub token_program: Program<'info, Token>,
-}
\ No newline at end of file
+    
+    // Only needed for the governance staker discount on the performance fee
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Option<Account<'info, Governance>>,
+    
+    #[account(
+        seeds = [b"governance-stake", owner.key().as_ref()],
+        bump = governance_stake.bump
+    )]
+    pub governance_stake: Option<Account<'info, GovernanceStake>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CheckpointRewards<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-provider", owner.key().as_ref(), capital_pool.key().as_ref()],
+        bump = capital_provider.bump,
+        constraint = capital_provider.owner == owner.key()
+    )]
+    pub capital_provider: Account<'info, CapitalProvider>,
+    
+    #[account(
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+    
+    // Only needed for the governance staker discount on the performance fee
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Option<Account<'info, Governance>>,
+    
+    #[account(
+        seeds = [b"governance-stake", owner.key().as_ref()],
+        bump = governance_stake.bump
+    )]
+    pub governance_stake: Option<Account<'info, GovernanceStake>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"capital-provider", owner.key().as_ref(), capital_provider.pool.as_ref()],
+        bump = capital_provider.bump,
+        constraint = capital_provider.owner == owner.key()
+    )]
+    pub capital_provider: Account<'info, CapitalProvider>,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use crate::{ProtocolState, ErrorCode};
+
+pub const MAX_CIRCUIT_BREAKER_THRESHOLD: usize = 5;
+
+// Tracks recently confirmed critical alerts across protocols. Enough of them in a short
+// window suggests a correlated attack, e.g. on a shared dependency, and trips the breaker.
+// Only confirmations by governance count, see `record_confirmed_critical_alert`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CircuitBreaker {
+    pub threshold: u8,    // Distinct protocols with confirmed critical alerts needed to trip
+    pub window_secs: i64,
+    pub recent_protocols: [Pubkey; MAX_CIRCUIT_BREAKER_THRESHOLD],
+    pub recent_confirmed_at: [i64; MAX_CIRCUIT_BREAKER_THRESHOLD],
+    pub is_tripped: bool,
+    pub tripped_at: i64,
+}
+
+impl CircuitBreaker {
+    pub const SIZE: usize = 1 +     // threshold
+                           8 +      // window_secs
+                           32 * MAX_CIRCUIT_BREAKER_THRESHOLD + // recent_protocols
+                           8 * MAX_CIRCUIT_BREAKER_THRESHOLD +  // recent_confirmed_at
+                           1 +      // is_tripped
+                           8;       // tripped_at
+    
+    // Records a confirmed critical alert. Returns true when this trips the breaker.
+    pub fn record_critical_alert(&mut self, protocol: Pubkey, now: i64) -> bool {
+        // Each protocol holds at most one slot, so repeat alerts for it don't count twice;
+        // otherwise the oldest slot is reused
+        let slot = self.recent_protocols.iter().position(|p| *p == protocol).unwrap_or_else(|| {
+            (0..MAX_CIRCUIT_BREAKER_THRESHOLD)
+                .min_by_key(|i| self.recent_confirmed_at[*i])
+                .unwrap()
+        });
+        self.recent_protocols[slot] = protocol;
+        self.recent_confirmed_at[slot] = now;
+        
+        let recent_count = self.recent_confirmed_at
+            .iter()
+            .zip(self.recent_protocols.iter())
+            .filter(|(confirmed_at, p)| **p != Pubkey::default() && now - **confirmed_at <= self.window_secs)
+            .count();
+        
+        if !self.is_tripped && recent_count >= self.threshold as usize {
+            self.is_tripped = true;
+            self.tripped_at = now;
+            return true;
+        }
+        
+        false
+    }
+    
+    pub fn reset(&mut self) {
+        self.recent_protocols = [Pubkey::default(); MAX_CIRCUIT_BREAKER_THRESHOLD];
+        self.recent_confirmed_at = [0; MAX_CIRCUIT_BREAKER_THRESHOLD];
+        self.is_tripped = false;
+        self.tripped_at = 0;
+    }
+}
+
+// Records a critical alert confirmed by the admin or a claim resolver. Confirmations by
+// anyone else are ignored, so whoever controls a few protocols and a watcher can't trip
+// the breaker for the whole system. Returns true when this trips the breaker.
+pub fn record_confirmed_critical_alert(
+    protocol_state: &mut ProtocolState,
+    confirmed_by: &Pubkey,
+    protocol: Pubkey,
+    now: i64,
+) -> bool {
+    let is_governance = *confirmed_by == protocol_state.authority || protocol_state.is_claim_resolver(confirmed_by);
+    is_governance && protocol_state.circuit_breaker.record_critical_alert(protocol, now)
+}
+
+pub const DEFAULT_CIRCUIT_BREAKER: CircuitBreaker = CircuitBreaker {
+    threshold: 3,
+    window_secs: 24 * 60 * 60,
+    recent_protocols: [Pubkey::new_from_array([0; 32]); MAX_CIRCUIT_BREAKER_THRESHOLD],
+    recent_confirmed_at: [0; MAX_CIRCUIT_BREAKER_THRESHOLD],
+    is_tripped: false,
+    tripped_at: 0,
+};
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub critical_alerts: u8,
+    pub tripped_at: i64,
+}
+
+#[event]
+pub struct CircuitBreakerCleared {
+    pub cleared_by: Pubkey,
+    pub tripped_at: i64,
+}
+
+// Governance decides when the correlated incident is over
+pub fn clear_circuit_breaker(ctx: Context<ConfigureCircuitBreaker>) -> Result<()> {
+    let circuit_breaker = &mut ctx.accounts.protocol_state.circuit_breaker;
+    require!(circuit_breaker.is_tripped, ErrorCode::CircuitBreakerNotTripped);
+    
+    let tripped_at = circuit_breaker.tripped_at;
+    circuit_breaker.reset();
+    
+    emit!(CircuitBreakerCleared {
+        cleared_by: ctx.accounts.authority.key(),
+        tripped_at,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCircuitBreaker<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
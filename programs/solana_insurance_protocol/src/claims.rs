@@ -1,6 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{Policy, ProtocolInfo, CapitalPool, ErrorCode};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PARAMETRIC_CLAIMS};
+use crate::global_stats::GlobalStats;
+use crate::incident::record_incident_claim;
+use crate::loss_events::LossEvent;
+use crate::mint_registry::MintRegistry;
+use crate::native_sol::{is_native_mint, unwrap_sol};
+use crate::realms::load_claims_committee;
+use crate::receipts::{payout_receipt, write_receipt};
+use crate::{Policy, ProtocolInfo, ProtocolState, CapitalPool, ErrorCode};
 
 #[account]
 pub struct Claim {
@@ -9,11 +20,15 @@ pub struct Claim {
     pub amount: u64,
     pub evidence: String,
     pub submitted_time: i64,
-    pub status: u8, // 0 = Pending, 1 = Approved, 2 = Rejected
+    pub status: ClaimStatus,
     pub resolution_time: i64,
     pub resolver: Pubkey,
     pub resolution_notes: String,
+    pub loss_event: Pubkey,  // Default when the claim doesn't reference a loss event
+    pub is_fast_track: bool,
+    pub challenge_ends_at: i64, // Approved claims can be vetoed by the emergency council until then
     pub bump: u8,
+    pub version: u8,
 }
 
 impl Claim {
@@ -21,51 +36,158 @@ impl Claim {
                            32 +     // policy
                            32 +     // claimant
                            8 +      // amount
-                           100 +    // evidence (max 96 chars + 4 bytes for string length)
+                           4 + MAX_EVIDENCE_LEN + // evidence
                            8 +      // submitted_time
                            1 +      // status
                            8 +      // resolution_time
                            32 +     // resolver
-                           100 +    // resolution_notes (max 96 chars + 4 bytes for string length)
-                           1;       // bump
+                           4 + MAX_RESOLUTION_NOTES_LEN + // resolution_notes
+                           32 +     // loss_event
+                           1 +      // is_fast_track
+                           8 +      // challenge_ends_at
+                           1 +      // bump
+                           1;       // version
 }
 
-// Status constants
-pub const CLAIM_STATUS_PENDING: u8 = 0;
-pub const CLAIM_STATUS_APPROVED: u8 = 1;
-pub const CLAIM_STATUS_REJECTED: u8 = 2;
+pub const MAX_EVIDENCE_LEN: usize = 96;
+pub const MAX_RESOLUTION_NOTES_LEN: usize = 96;
 
+// Most keys that can be registered to resolve claims
+pub const MAX_CLAIM_RESOLVERS: usize = 5;
+
+#[event]
+pub struct ClaimResolverSetUpdated {
+    pub resolver: Pubkey,
+    pub is_added: bool,
+    pub resolver_count: u8,
+}
+
+// Variants keep the order of the u8 statuses claims used to store, so their Borsh
+// encoding is unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Paid,
+    Vetoed,
+}
+
+// Claims are resolved once, and only an approved claim is then paid out or vetoed:
+//
+//   Pending -> Approved/Rejected  a resolver decides the claim
+//   Approved -> Paid              the payout is made after the challenge window
+//   Approved -> Vetoed            the emergency council cancels it within the window
+impl ClaimStatus {
+    pub fn can_transition_to(self, next: ClaimStatus) -> bool {
+        use ClaimStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Approved | Rejected) |
+            (Approved, Paid | Vetoed)
+        )
+    }
+}
+
+// Every status change goes through here, so a repeated or out-of-order step fails
+// before any accounting is touched
+pub fn transition_claim(claim: &mut Claim, status: ClaimStatus) -> Result<()> {
+    if !claim.status.can_transition_to(status) {
+        return match status {
+            ClaimStatus::Approved | ClaimStatus::Rejected => err!(ErrorCode::ClaimAlreadyResolved),
+            _ => err!(ErrorCode::ClaimNotApproved),
+        };
+    }
+    claim.status = status;
+    
+    Ok(())
+}
+
+// Checks the claim a policy had filed before claims were indexed, whose address is
+// empty when it had none. Until it's settled the policy can't take another claim or
+// be wound down.
+pub(crate) fn check_legacy_claim_settled(legacy_claim: &UncheckedAccount) -> Result<()> {
+    if !legacy_claim.data_is_empty() {
+        let claim = Claim::try_deserialize(&mut &legacy_claim.data.borrow()[..])?;
+        require!(
+            claim.status != ClaimStatus::Pending && claim.status != ClaimStatus::Approved,
+            ErrorCode::ClaimPending
+        );
+    }
+    
+    Ok(())
+}
+
+// A policy can take several partial claims, each bounded by the coverage that earlier
+// claims haven't used
 pub fn submit_claim(
     ctx: Context<SubmitClaim>,
     amount: u64,
     evidence: String,
 ) -> Result<()> {
-    let policy = &ctx.accounts.policy;
+    let policy = &mut ctx.accounts.policy;
     let claim = &mut ctx.accounts.claim;
+    let protocol_info = &mut ctx.accounts.protocol_info;
     let clock = Clock::get()?;
     
-    // Verify the policy is active and hasn't expired
+    require!(!ctx.accounts.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    require!(evidence.len() <= MAX_EVIDENCE_LEN, ErrorCode::EvidenceTooLong);
+    
+    // Verify the policy is active and hasn't expired. Claims against a loss event
+    // only need the policy to have been in force when the incident began.
     require!(policy.is_active, ErrorCode::PolicyNotActive);
-    require!(policy.end_time > clock.unix_timestamp, ErrorCode::PolicyExpired);
-    require!(!policy.is_claimed, ErrorCode::PolicyAlreadyClaimed);
+    match &ctx.accounts.loss_event {
+        Some(loss_event) => {
+            require!(
+                ctx.accounts.config.is_enabled(FEATURE_PARAMETRIC_CLAIMS),
+                ErrorCode::FeatureDisabled
+            );
+            require!(
+                loss_event.covers(policy.start_time, policy.end_time),
+                ErrorCode::PolicyNotCoveringLossEvent
+            );
+        }
+        None => {
+            require!(policy.end_time > clock.unix_timestamp, ErrorCode::PolicyExpired);
+            require!(
+                clock.unix_timestamp >= policy.start_time.checked_add(ctx.accounts.config.params.claim_waiting_period_secs).ok_or(ErrorCode::MathOverflow)?,
+                ErrorCode::ClaimWaitingPeriod
+            );
+        }
+    }
+    check_legacy_claim_settled(&ctx.accounts.legacy_claim)?;
+    
+    // Coverage bought after a known incident started doesn't cover it
+    require!(
+        !protocol_info.incident_state.is_incident() || policy.start_time < protocol_info.incident_started_at,
+        ErrorCode::PolicyPurchasedDuringIncident
+    );
+    record_incident_claim(protocol_info)?;
     
     // Verify the claimant is the insured
     require!(ctx.accounts.claimant.key() == policy.insured, ErrorCode::UnauthorizedClaim);
     
-    // Verify the claim amount is within the coverage limits
-    require!(amount <= policy.coverage_amount, ErrorCode::ExcessClaimAmount);
+    // Verify the claim amount is within the coverage that hasn't been paid or reserved
+    require!(amount <= policy.remaining_coverage()?, ErrorCode::ExcessClaimAmount);
+    
+    policy.claim_count = policy.claim_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    policy.open_claims = policy.open_claims.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
     
     // Initialize the claim
-    claim.policy = ctx.accounts.policy.key();
+    claim.policy = policy.key();
     claim.claimant = ctx.accounts.claimant.key();
     claim.amount = amount;
     claim.evidence = evidence;
     claim.submitted_time = clock.unix_timestamp;
-    claim.status = CLAIM_STATUS_PENDING;
+    claim.status = ClaimStatus::Pending;
     claim.resolution_time = 0;
     claim.resolver = Pubkey::default();
     claim.resolution_notes = String::new();
+    claim.loss_event = ctx.accounts.loss_event.as_ref().map_or(Pubkey::default(), |loss_event| loss_event.key());
+    claim.is_fast_track = ctx.accounts.loss_event.is_some();
+    claim.challenge_ends_at = 0;
     claim.bump = ctx.bumps.claim;
+    claim.version = ACCOUNT_VERSION;
     
     Ok(())
 }
@@ -76,86 +198,342 @@ pub fn resolve_claim(
     resolution_notes: String,
 ) -> Result<()> {
     let claim = &mut ctx.accounts.claim;
-    let policy = &mut ctx.accounts.policy;
     let clock = Clock::get()?;
     
-    // Only protocol authority can resolve claims
-    require!(
-        ctx.accounts.resolver.key() == ctx.accounts.protocol_info.authority,
-        ErrorCode::UnauthorizedResolver
-    );
-    
-    // Verify the claim is pending
-    require!(claim.status == CLAIM_STATUS_PENDING, ErrorCode::ClaimAlreadyResolved);
+    require!(!ctx.accounts.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    require!(resolution_notes.len() <= MAX_RESOLUTION_NOTES_LEN, ErrorCode::ResolutionNotesTooLong);
+    check_resolver(
+        &ctx.accounts.resolver.key(),
+        claim,
+        approve,
+        &ctx.accounts.protocol_info,
+        &ctx.accounts.protocol_state,
+        &ctx.accounts.claims_committee,
+    )?;
     
-    // Update the claim
-    claim.status = if approve { CLAIM_STATUS_APPROVED } else { CLAIM_STATUS_REJECTED };
+    // Update the claim; only a pending claim can be resolved
+    transition_claim(claim, if approve { ClaimStatus::Approved } else { ClaimStatus::Rejected })?;
     claim.resolution_time = clock.unix_timestamp;
     claim.resolver = ctx.accounts.resolver.key();
     claim.resolution_notes = resolution_notes;
     
     if approve {
-        // Mark the policy as claimed
-        policy.is_claimed = true;
-        
-        // If approved, transfer the claim amount from capital pool to the claimant
-        let pool = &mut ctx.accounts.capital_pool;
-        
-        // Check if pool has enough available capital
+        reserve_claim_payout(
+            claim,
+            &mut ctx.accounts.policy,
+            &mut ctx.accounts.capital_pool,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.config.params.claim_challenge_window_secs,
+            clock.unix_timestamp,
+        )?;
+    } else {
+        close_claim(&mut ctx.accounts.policy);
+    }
+    
+    Ok(())
+}
+
+// Claims filed before claims were indexed were never counted as open
+pub(crate) fn close_claim(policy: &mut Policy) {
+    policy.open_claims = policy.open_claims.saturating_sub(1);
+}
+
+// Approves a claim and pays it out in one transaction. Only for tiers without a challenge
+// window, since anywhere else the payout has to wait for the window to pass.
+pub fn resolve_and_pay_claim(
+    ctx: Context<ResolveAndPayClaim>,
+    resolution_notes: String,
+) -> Result<()> {
+    let resolver = ctx.accounts.resolver.key();
+    let default_challenge_window_secs = ctx.accounts.config.params.claim_challenge_window_secs;
+    let pay = &mut ctx.accounts.pay;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!pay.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    require!(resolution_notes.len() <= MAX_RESOLUTION_NOTES_LEN, ErrorCode::ResolutionNotesTooLong);
+    require!(
+        pay.capital_pool.challenge_window_secs(default_challenge_window_secs) == 0,
+        ErrorCode::ChallengeWindowOpen
+    );
+    check_resolver(
+        &resolver,
+        &pay.claim,
+        true,
+        &pay.protocol_info,
+        &pay.protocol_state,
+        &ctx.accounts.claims_committee,
+    )?;
+    
+    let claim = &mut pay.claim;
+    transition_claim(claim, ClaimStatus::Approved)?;
+    claim.resolution_time = now;
+    claim.resolver = resolver;
+    claim.resolution_notes = resolution_notes;
+    reserve_claim_payout(
+        claim,
+        &mut pay.policy,
+        &mut pay.capital_pool,
+        &mut pay.global_stats,
+        default_challenge_window_secs,
+        now,
+    )?;
+    
+    pay_out(pay)
+}
+
+// Claims are decided by the registered resolvers, and never by the protocol being claimed against.
+// With a Realms claims committee, large claims are only approved by a passed proposal.
+fn check_resolver(
+    resolver: &Pubkey,
+    claim: &Claim,
+    approve: bool,
+    protocol_info: &ProtocolInfo,
+    protocol_state: &ProtocolState,
+    claims_committee: &AccountInfo,
+) -> Result<()> {
+    let committee = load_claims_committee(claims_committee)?;
+    let is_committee = committee.as_ref().is_some_and(|committee| committee.is_committee_signer(resolver));
+    require!(
+        (is_committee || protocol_state.is_claim_resolver(resolver)) &&
+        *resolver != protocol_info.authority,
+        ErrorCode::UnauthorizedResolver
+    );
+    if let Some(committee) = &committee {
         require!(
-            pool.available_capital >= claim.amount,
-            ErrorCode::InsufficientPoolCapital
+            !approve || is_committee || claim.amount < committee.large_claim_threshold,
+            ErrorCode::ClaimNeedsCommitteeVote
         );
-        
-        // Update the capital pool
-        pool.available_capital = pool.available_capital.checked_sub(claim.amount).unwrap();
-        pool.reserved_capital = pool.reserved_capital.checked_add(claim.amount).unwrap();
-        
-        // Transfer funds to the claimant
-        let seeds = &[
-            b"capital-pool", 
-            &[pool.pool_type][..],
-            &[pool.bump]
-        ];
-        let signer = &[&seeds[..]];
-        
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.pool_token_account.to_account_info(),
-            to: ctx.accounts.claimant_token.to_account_info(),
-            authority: ctx.accounts.capital_pool.to_account_info(),
-        };
-        
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        
-        token::transfer(cpi_ctx, claim.amount)?;
     }
     
     Ok(())
 }
 
+// Holds an approved claim's amount against the policy's coverage and the pool's capital
+fn reserve_claim_payout(
+    claim: &mut Claim,
+    policy: &mut Policy,
+    pool: &mut CapitalPool,
+    global_stats: &mut GlobalStats,
+    default_challenge_window_secs: i64,
+    now: i64,
+) -> Result<()> {
+    // Mark the policy as claimed and hold the amount against its coverage
+    require!(policy.remaining_coverage()? >= claim.amount, ErrorCode::ExcessClaimAmount);
+    policy.is_claimed = true;
+    policy.pending_reserved = policy.pending_reserved.checked_add(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    
+    // Check if pool has enough available capital and the tier would pay the claim out
+    require!(
+        pool.available_capital >= claim.amount,
+        ErrorCode::InsufficientPoolCapital
+    );
+    pool.check_claim_payout(claim.amount)?;
+    
+    // Reserve the payout; the claimed part of the coverage is no longer outstanding. A
+    // vetoed claim's coverage isn't put back, so a later claim can find less to release.
+    pool.available_capital = pool.available_capital.checked_sub(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    pool.reserved_capital = pool.reserved_capital.checked_add(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    let released_coverage = claim.amount.min(policy.coverage_amount.saturating_sub(policy.coverage_released));
+    policy.coverage_released = policy.coverage_released.checked_add(released_coverage).ok_or(ErrorCode::MathOverflow)?;
+    pool.active_coverage = pool.active_coverage.saturating_sub(released_coverage);
+    global_stats.release_coverage(&pool.token_mint, released_coverage)?;
+    
+    // The payout waits out the challenge window so a compromised resolver can be vetoed;
+    // riskier tiers can hold payouts for longer
+    let challenge_window_secs = pool.challenge_window_secs(default_challenge_window_secs);
+    claim.challenge_ends_at = now
+        .checked_add(challenge_window_secs)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    Ok(())
+}
+
+// Anyone can pay out an approved claim once its challenge window has passed
+pub fn pay_claim(ctx: Context<PayClaim>) -> Result<()> {
+    pay_out(ctx.accounts)
+}
+
+fn pay_out(accounts: &mut PayClaim) -> Result<()> {
+    let claim = &mut accounts.claim;
+    
+    require!(!accounts.protocol_state.is_paused, ErrorCode::ProtocolPaused);
+    transition_claim(claim, ClaimStatus::Paid)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= claim.challenge_ends_at, ErrorCode::ChallengeWindowOpen);
+    require!(!accounts.capital_pool.payouts_frozen, ErrorCode::PoolPayoutsFrozen);
+    // The pool's token has to be listed, though claims are still paid after it's delisted
+    accounts.mint_registry.registered(&accounts.token_mint)?;
+    require!(
+        accounts.pool_token_account.amount >= claim.amount,
+        ErrorCode::PoolBalanceShortfall
+    );
+    
+    // The payout goes either to the insured's token account or, for SOL pools, through
+    // a temporary account that's unwrapped to the insured
+    let destination = match (&accounts.claimant_token, &accounts.sol_payout) {
+        (Some(claimant_token), None) => claimant_token.to_account_info(),
+        (None, Some(sol_payout)) => {
+            require!(is_native_mint(&accounts.token_mint.key()), ErrorCode::NotNativeMint);
+            sol_payout.to_account_info()
+        }
+        _ => return err!(ErrorCode::InvalidPayoutAccount),
+    };
+    
+    // The reserved amount becomes a payout against the policy's coverage. Claims
+    // approved before policies tracked reservations have nothing reserved to release.
+    let policy = &mut accounts.policy;
+    policy.pending_reserved = policy.pending_reserved.saturating_sub(claim.amount);
+    policy.amount_paid = policy.amount_paid.checked_add(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    close_claim(policy);
+    
+    // Track payouts for the protocol's loss experience
+    let protocol_info = &mut accounts.protocol_info;
+    protocol_info.claims_paid = protocol_info.claims_paid.checked_add(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    
+    // A payout over the tier's limits waits for the next day or for the limits to be raised
+    let pool = &mut accounts.capital_pool;
+    pool.record_payout(claim.amount, now)?;
+    pool.reserved_capital = pool.reserved_capital.checked_sub(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    accounts.global_stats.record_claim_paid(pool.pool_type, &pool.token_mint, claim.amount)?;
+    
+    // Transfer funds to the claimant
+    let seeds = &[
+        b"capital-pool", 
+        &[pool.pool_type as u8][..],
+        &[pool.bump]
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_accounts = TransferChecked {
+        from: accounts.pool_token_account.to_account_info(),
+        mint: accounts.token_mint.to_account_info(),
+        to: destination,
+        authority: accounts.capital_pool.to_account_info(),
+    };
+    
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    
+    token::transfer_checked(cpi_ctx, claim.amount, accounts.capital_pool.token_decimals)?;
+    
+    if let Some(sol_payout) = accounts.sol_payout.as_mut() {
+        unwrap_sol(
+            sol_payout,
+            accounts.capital_pool.to_account_info(),
+            signer,
+            &accounts.caller,
+            accounts.insured.to_account_info(),
+            &accounts.token_program,
+            &accounts.system_program,
+        )?;
+    }
+    
+    write_receipt(&accounts.memo_program, payout_receipt(&claim.key(), &claim.policy, claim.amount))
+}
+
+pub fn add_claim_resolver(
+    ctx: Context<ConfigureClaimResolvers>,
+    resolver: Pubkey,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let count = protocol_state.claim_resolver_count as usize;
+    
+    require!(resolver != Pubkey::default(), ErrorCode::UnauthorizedResolver);
+    require!(!protocol_state.is_claim_resolver(&resolver), ErrorCode::ClaimResolverAlreadyRegistered);
+    require!(count < MAX_CLAIM_RESOLVERS, ErrorCode::ClaimResolverSetFull);
+    
+    protocol_state.claim_resolvers[count] = resolver;
+    protocol_state.claim_resolver_count = protocol_state.claim_resolver_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    
+    emit!(ClaimResolverSetUpdated {
+        resolver,
+        is_added: true,
+        resolver_count: protocol_state.claim_resolver_count,
+    });
+    
+    Ok(())
+}
+
+pub fn remove_claim_resolver(
+    ctx: Context<ConfigureClaimResolvers>,
+    resolver: Pubkey,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let count = protocol_state.claim_resolver_count as usize;
+    
+    let index = protocol_state.claim_resolvers[..count]
+        .iter()
+        .position(|key| key == &resolver)
+        .ok_or(ErrorCode::ClaimResolverNotRegistered)?;
+    
+    // Move the last resolver into the freed slot
+    protocol_state.claim_resolvers[index] = protocol_state.claim_resolvers[count - 1];
+    protocol_state.claim_resolvers[count - 1] = Pubkey::default();
+    protocol_state.claim_resolver_count = protocol_state.claim_resolver_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+    
+    emit!(ClaimResolverSetUpdated {
+        resolver,
+        is_added: false,
+        resolver_count: protocol_state.claim_resolver_count,
+    });
+    
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct SubmitClaim<'info> {
     #[account(mut)]
     pub claimant: Signer<'info>,
     
+    // Policies have two address schemes (see PolicyCounter), so the keys a policy stores bind it
     #[account(
-        seeds = [b"policy", claimant.key().as_ref(), policy.protocol.as_ref()],
-        bump = policy.bump,
+        mut,
         constraint = policy.insured == claimant.key()
     )]
     pub policy: Account<'info, Policy>,
     
+    #[account(
+        mut,
+        constraint = protocol_info.key() == policy.protocol
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    // Confirmed incident the claim is for, which fast-tracks its processing
+    #[account(
+        constraint = loss_event.protocol == policy.protocol @ ErrorCode::LossEventProtocolMismatch
+    )]
+    pub loss_event: Option<Account<'info, LossEvent>>,
+    
     #[account(
         init,
         payer = claimant,
         space = Claim::SIZE,
-        seeds = [b"claim", policy.key().as_ref()],
+        seeds = [b"claim", policy.key().as_ref(), &policy.claim_count.to_le_bytes()],
         bump
     )]
     pub claim: Account<'info, Claim>,
     
     pub system_program: Program<'info, System>,
+    
+    /// CHECK: The policy's claim address from before claims were indexed, checked in the
+    /// handler; empty when the policy never had a claim there
+    #[account(
+        seeds = [b"claim", policy.key().as_ref()],
+        bump
+    )]
+    pub legacy_claim: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -163,38 +541,181 @@ pub struct ResolveClaim<'info> {
     #[account(mut)]
     pub resolver: Signer<'info>,
     
+    // Claims and policies are only ever created at their PDAs, so the keys they store
+    // bind them together without paying to re-derive the addresses (which for a claim
+    // would also take its index)
     #[account(
         mut,
-        seeds = [b"claim", policy.key().as_ref()],
-        bump = claim.bump
+        has_one = policy
     )]
     pub claim: Account<'info, Claim>,
     
     #[account(
         mut,
-        seeds = [b"policy", policy.insured.as_ref(), protocol_info.key().as_ref()],
-        bump = policy.bump
+        constraint = policy.protocol == protocol_info.key() @ ErrorCode::PolicyProtocolMismatch
     )]
     pub policy: Account<'info, Policy>,
     
     pub protocol_info: Account<'info, ProtocolInfo>,
     
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+    
+    /// CHECK: The claims committee's address, empty when no committee is configured
+    #[account(
+        seeds = [b"claims-committee"],
+        bump
+    )]
+    pub claims_committee: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PayClaim<'info> {
     #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    // Bound to the policy by the key it stores, like in ResolveClaim
+    #[account(
+        mut,
+        has_one = policy
+    )]
+    pub claim: Account<'info, Claim>,
+    
+    // Policies have two address schemes (see PolicyCounter), so the keys a policy stores bind it
+    #[account(
+        mut,
+        constraint = policy.protocol == protocol_info.key() @ ErrorCode::PolicyProtocolMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
     pub capital_pool: Account<'info, CapitalPool>,
     
     #[account(
         mut,
         constraint = pool_token_account.mint == capital_pool.token_mint,
-        constraint = pool_token_account.key() == capital_pool.token_account
+        constraint = pool_token_account.key() == capital_pool.token_account @ ErrorCode::InvalidPoolTokenAccount
     )]
     pub pool_token_account: Account<'info, TokenAccount>,
     
+    #[account(address = capital_pool.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// CHECK: Owner of the payout account, and the recipient of unwrapped SOL payouts
+    #[account(mut, address = policy.insured)]
+    pub insured: UncheckedAccount<'info>,
+    
+    // Token payouts go to the insured's associated token account, which the caller
+    // pays to create if the insured never made one for the pool's mint
     #[account(
-        mut,
-        constraint = claimant_token.mint == pool_token_account.mint,
-        constraint = claimant_token.owner == policy.insured
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = token_mint,
+        associated_token::authority = insured
+    )]
+    pub claimant_token: Option<Account<'info, TokenAccount>>,
+    
+    // Passed instead of claimant_token to pay a SOL pool's claim out as lamports. It's
+    // closed again in the same instruction, so the caller gets its rent back.
+    #[account(
+        init,
+        payer = caller,
+        seeds = [b"sol-payout", claim.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = capital_pool
     )]
-    pub claimant_token: Account<'info, TokenAccount>,
+    pub sol_payout: Option<Account<'info, TokenAccount>>,
     
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    // Only needed for a memo receipt of the payout
+    pub memo_program: Option<Program<'info, Memo>>,
+    
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+}
+
+// The payout accounts of PayClaim, plus what deciding the claim needs
+#[derive(Accounts)]
+pub struct ResolveAndPayClaim<'info> {
+    pub resolver: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// CHECK: The claims committee's address, empty when no committee is configured
+    #[account(
+        seeds = [b"claims-committee"],
+        bump
+    )]
+    pub claims_committee: UncheckedAccount<'info>,
+    
+    pub pay: PayClaim<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureClaimResolvers<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
 }
\ No newline at end of file
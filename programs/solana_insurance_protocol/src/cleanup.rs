@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use crate::claims::{Claim, ClaimStatus};
+use crate::deregistration::wind_down_policy;
+use crate::global_stats::GlobalStats;
+use crate::keeper::*; // KeeperReward needs its generated account modules in scope
+use crate::treasury::Treasury;
+use crate::{CapitalPool, CapitalProvider, Policy, ProtocolInfo, ProtocolState, ErrorCode};
+
+// How long expired policies and settled claims are kept around before their
+// accounts can be closed. Reclaimed rent collects on the treasury PDA and is
+// taken out with withdraw_treasury_lamports.
+pub const POLICY_RETENTION_SECS: i64 = 180 * 24 * 60 * 60;
+pub const CLAIM_RETENTION_SECS: i64 = 180 * 24 * 60 * 60;
+
+#[event]
+pub struct StaleAccountClosed {
+    pub account: Pubkey,
+    pub rent_reclaimed: u64,
+}
+
+// Anyone can close a policy that expired more than the retention period ago. A claim filed
+// before claims were indexed has to be closed first so it isn't left behind without its
+// policy; indexed claims are closed on their own.
+pub fn close_stale_policy(ctx: Context<CloseStalePolicy>) -> Result<()> {
+    let policy = &ctx.accounts.policy;
+
+    require!(
+        Clock::get()?.unix_timestamp >= policy.end_time.checked_add(POLICY_RETENTION_SECS).ok_or(ErrorCode::MathOverflow)?,
+        ErrorCode::RetentionPeriodNotOver
+    );
+    require!(ctx.accounts.legacy_claim.data_is_empty(), ErrorCode::ClaimPending);
+    // Closing the policy would strand its escrow, which only the policy can sign for
+    require!(policy.is_escrow_settled(), ErrorCode::PremiumStillEscrowed);
+
+    // Policies that were never wound down still count towards the pool and the protocol
+    if policy.is_active {
+        let protocol_info = ctx.accounts.protocol_info.as_mut().ok_or(ErrorCode::CleanupAccountMissing)?;
+        let capital_pool = ctx.accounts.capital_pool.as_mut().ok_or(ErrorCode::CleanupAccountMissing)?;
+        wind_down_policy(
+            &mut ctx.accounts.policy,
+            protocol_info,
+            capital_pool,
+            &mut ctx.accounts.global_stats,
+            &ctx.accounts.legacy_claim,
+            0,
+        )?;
+    }
+
+    emit!(StaleAccountClosed {
+        account: ctx.accounts.policy.key(),
+        rent_reclaimed: ctx.accounts.policy.to_account_info().lamports(),
+    });
+
+    pay_keeper_reward(&mut ctx.accounts.keeper_reward, &mut ctx.accounts.protocol_state)
+}
+
+pub fn close_stale_claim(ctx: Context<CloseStaleClaim>) -> Result<()> {
+    let claim = &ctx.accounts.claim;
+
+    require!(
+        claim.status == ClaimStatus::Rejected ||
+        claim.status == ClaimStatus::Paid ||
+        claim.status == ClaimStatus::Vetoed,
+        ErrorCode::ClaimPending
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= claim.resolution_time.checked_add(CLAIM_RETENTION_SECS).ok_or(ErrorCode::MathOverflow)?,
+        ErrorCode::RetentionPeriodNotOver
+    );
+
+    emit!(StaleAccountClosed {
+        account: claim.key(),
+        rent_reclaimed: claim.to_account_info().lamports(),
+    });
+
+    pay_keeper_reward(&mut ctx.accounts.keeper_reward, &mut ctx.accounts.protocol_state)
+}
+
+// Positions that still have rewards on record are left for their owner to close
+pub fn close_empty_provider(ctx: Context<CloseEmptyProvider>) -> Result<()> {
+    let capital_provider = &ctx.accounts.capital_provider;
+
+    emit!(StaleAccountClosed {
+        account: capital_provider.key(),
+        rent_reclaimed: capital_provider.to_account_info().lamports(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseStalePolicy<'info> {
+    pub caller: Signer<'info>,
+
+    // See PolicyCounter for why the policy's address isn't re-derived
+    #[account(
+        mut,
+        close = treasury
+    )]
+    pub policy: Account<'info, Policy>,
+
+    /// CHECK: The policy's claim address from before claims were indexed, which must be empty
+    #[account(
+        seeds = [b"claim", policy.key().as_ref()],
+        bump
+    )]
+    pub legacy_claim: UncheckedAccount<'info>,
+
+    // Only needed while the policy is still active
+    #[account(
+        mut,
+        constraint = protocol_info.key() == policy.protocol
+    )]
+    pub protocol_info: Option<Account<'info, ProtocolInfo>>,
+
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
+    pub capital_pool: Option<Account<'info, CapitalPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub keeper_reward: KeeperReward<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStaleClaim<'info> {
+    pub caller: Signer<'info>,
+
+    // Claims are only created by the program, and a settled one is closed without its policy
+    #[account(
+        mut,
+        close = treasury
+    )]
+    pub claim: Account<'info, Claim>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub keeper_reward: KeeperReward<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEmptyProvider<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"capital-provider", capital_provider.owner.as_ref(), capital_provider.pool.as_ref()],
+        bump = capital_provider.bump,
+        constraint = capital_provider.capital_amount == 0 @ ErrorCode::ProviderNotEmpty,
+        constraint = capital_provider.rewards_earned == 0 @ ErrorCode::ProviderNotEmpty
+    )]
+    pub capital_provider: Account<'info, CapitalProvider>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
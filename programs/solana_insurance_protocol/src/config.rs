@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolState, ErrorCode};
+
+// Tunables read by the instructions they apply to. They're changed through
+// the timelocked `ParameterChange::Config` like any other configuration.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ConfigParams {
+    pub min_policy_duration_days: u16,
+    pub max_policy_duration_days: u16,
+    pub claim_waiting_period_secs: i64,   // Time after purchase before a policy can be claimed on
+    pub claim_challenge_window_secs: i64, // Time an approved claim waits before it can be paid
+    pub price_deviation_cooldown_secs: i64, // Minimum time between price deviation alerts
+}
+
+impl ConfigParams {
+    pub const SIZE: usize = 2 +     // min_policy_duration_days
+                           2 +      // max_policy_duration_days
+                           8 +      // claim_waiting_period_secs
+                           8 +      // claim_challenge_window_secs
+                           8;       // price_deviation_cooldown_secs
+
+    pub fn is_valid(&self) -> bool {
+        self.min_policy_duration_days > 0 &&
+        self.min_policy_duration_days <= self.max_policy_duration_days &&
+        self.claim_waiting_period_secs >= 0 &&
+        self.claim_challenge_window_secs >= 0 &&
+        self.price_deviation_cooldown_secs >= 0
+    }
+}
+
+// Rates charged on top of the protocol fee, which lives on the protocol state. Changed
+// alongside the other tunables through `ParameterChange::Config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeRates {
+    pub lp_performance_fee_bps: u64, // Cut of LP rewards, before any staker discount
+}
+
+impl FeeRates {
+    pub const SIZE: usize = 8;      // lp_performance_fee_bps
+
+    pub fn is_valid(&self) -> bool {
+        self.lp_performance_fee_bps <= MAX_LP_PERFORMANCE_FEE_BPS
+    }
+}
+
+pub const MAX_LP_PERFORMANCE_FEE_BPS: u64 = 3000; // 30% of LP rewards
+
+pub const DEFAULT_FEE_RATES: FeeRates = FeeRates {
+    lp_performance_fee_bps: 1000,
+};
+
+// Subsystems that can be switched off without a redeploy
+pub const FEATURE_PARAMETRIC_CLAIMS: u8 = 1 << 0;     // Loss events and the claims filed against them
+pub const FEATURE_PERMISSIONLESS_ALERTS: u8 = 1 << 1; // Bonded alerts from unregistered reporters
+pub const ALL_FEATURES: u8 = FEATURE_PARAMETRIC_CLAIMS | FEATURE_PERMISSIONLESS_ALERTS;
+
+pub const DEFAULT_CONFIG_PARAMS: ConfigParams = ConfigParams {
+    min_policy_duration_days: 1,
+    max_policy_duration_days: 365,
+    claim_waiting_period_secs: 0,
+    claim_challenge_window_secs: 24 * 60 * 60,
+    price_deviation_cooldown_secs: 60 * 60,
+};
+
+#[account]
+pub struct Config {
+    pub params: ConfigParams,
+    pub bump: u8,
+    pub version: u8,
+    // Version 4
+    pub features: u8, // Enabled FEATURE_* flags
+    // Version 16
+    pub fee_rates: FeeRates,
+}
+
+impl Config {
+    pub const SIZE: usize = 8 +     // discriminator
+                           ConfigParams::SIZE + // params
+                           1 +      // bump
+                           1 +      // version
+                           1 +      // features
+                           FeeRates::SIZE; // fee_rates
+
+    // Size before fee rates were added, for migrating older configs
+    pub const SIZE_BEFORE_FEE_RATES: usize = Self::SIZE - FeeRates::SIZE;
+
+    pub fn is_enabled(&self, feature: u8) -> bool {
+        self.features & feature == feature
+    }
+}
+
+#[event]
+pub struct FeaturesUpdated {
+    pub previous_features: u8,
+    pub features: u8,
+}
+
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.params = DEFAULT_CONFIG_PARAMS;
+    config.bump = ctx.bumps.config;
+    config.version = ACCOUNT_VERSION;
+    config.features = ALL_FEATURES;
+    config.fee_rates = DEFAULT_FEE_RATES;
+
+    Ok(())
+}
+
+// Only switches features off, which takes effect immediately so a misbehaving subsystem
+// can be shut off right away. Switching them back on goes through `ParameterChange::Features`.
+pub fn set_features(
+    ctx: Context<SetFeatures>,
+    features: u8,
+) -> Result<()> {
+    require!(features & !ALL_FEATURES == 0, ErrorCode::InvalidConfig);
+
+    let config = &mut ctx.accounts.config;
+    require!(features & !config.features == 0, ErrorCode::ChangeRequiresTimelock);
+    let previous_features = config.features;
+    config.features = features;
+
+    emit!(FeaturesUpdated {
+        previous_features,
+        features,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeatures<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
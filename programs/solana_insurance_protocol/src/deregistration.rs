@@ -0,0 +1,496 @@
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::claims::check_legacy_claim_settled;
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::global_stats::GlobalStats;
+use crate::math::mul_div_floor;
+use crate::keeper::*; // KeeperReward needs its generated account modules in scope
+use crate::native_sol::{is_native_mint, unwrap_sol};
+use crate::premium_escrow::{close_escrow, release_to_treasury, transfer_from_escrow};
+use crate::receipts::{refund_receipt, write_receipt};
+use crate::treasury::{transfer_from_treasury, Treasury, TreasuryVault};
+use crate::{
+    CapitalPool, DeactivationReason, Policy, ProtocolEntry, ProtocolInfo, ProtocolRegistry, ProtocolState,
+    ErrorCode,
+};
+
+#[event]
+pub struct ProtocolDeregistrationStarted {
+    pub protocol: Pubkey,
+    pub started_by: Pubkey,
+    pub active_policies: u32,
+}
+
+#[event]
+pub struct PolicyWoundDown {
+    pub policy: Pubkey,
+    pub protocol: Pubkey,
+    pub refund_amount: u64, // 0 when the policy ran to expiry or was claimed
+}
+
+#[event]
+pub struct ProtocolDeregistered {
+    pub protocol: Pubkey,
+    pub authority: Pubkey,
+}
+
+// Unused share of the premium for a policy cancelled at `now`. Escrowed policies refund
+// from what's in escrow, so the protocol fee isn't refunded; older ones refund from the
+// treasury on the whole premium.
+pub fn unexpired_premium(policy: &Policy, now: i64) -> u64 {
+    let duration = policy.end_time.saturating_sub(policy.start_time);
+    let remaining = policy.end_time.saturating_sub(now);
+    if duration <= 0 || remaining <= 0 {
+        return 0;
+    }
+
+    // Rounded down, so it never exceeds the premium
+    let premium = if policy.premium_escrowed > 0 { policy.premium_escrowed } else { policy.premium_amount };
+    mul_div_floor(premium, remaining as u128, duration as u128).unwrap_or(0)
+}
+
+// Stops new sales for good. Existing policies then have to be wound down one by one
+// before the protocol can be closed.
+pub fn begin_protocol_deregistration(ctx: Context<BeginProtocolDeregistration>) -> Result<()> {
+    let protocol_info = &mut ctx.accounts.protocol_info;
+
+    require!(!protocol_info.is_deregistering, ErrorCode::ProtocolDeregistering);
+    // Closing policies early would cut off claims for a confirmed incident
+    require!(!protocol_info.incident_state.is_incident(), ErrorCode::ProtocolHasOpenIncident);
+
+    protocol_info.is_deregistering = true;
+    protocol_info.is_active = false;
+    protocol_info.deactivation_reason = DeactivationReason::Sunset;
+    protocol_info.deactivated_by = Pubkey::default();
+    ctx.accounts.registry.sync_active(protocol_info)?;
+    update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+        directory.record(protocol_info)
+    })?;
+
+    emit!(ProtocolDeregistrationStarted {
+        protocol: protocol_info.key(),
+        started_by: ctx.accounts.authority.key(),
+        active_policies: protocol_info.active_policies,
+    });
+
+    Ok(())
+}
+
+// Anyone can close out a policy that has expired or paid out its whole coverage,
+// releasing what's left of its coverage from the pool
+pub fn close_expired_policy(ctx: Context<ClosePolicy>) -> Result<()> {
+    let policy = &ctx.accounts.policy;
+
+    require!(
+        policy.remaining_coverage()? == 0 || policy.end_time <= Clock::get()?.unix_timestamp,
+        ErrorCode::PolicyStillInForce
+    );
+
+    wind_down_policy(
+        &mut ctx.accounts.policy,
+        &mut ctx.accounts.protocol_info,
+        &mut ctx.accounts.capital_pool,
+        &mut ctx.accounts.global_stats,
+        &ctx.accounts.legacy_claim,
+        0,
+    )?;
+
+    pay_keeper_reward(&mut ctx.accounts.keeper_reward, &mut ctx.accounts.protocol_state)
+}
+
+// Cancels a policy that's still in force and refunds the unused premium. Escrowed
+// policies are refunded from their escrow, so the insured can cancel without the admin;
+// older policies are refunded from the treasury at the admin's request.
+pub fn cancel_policy(ctx: Context<CancelPolicy>) -> Result<()> {
+    let policy = &ctx.accounts.policy;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!policy.is_claimed && policy.end_time > now, ErrorCode::PolicyNotActive);
+    let is_escrowed = policy.premium_escrowed > 0;
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority ||
+            (is_escrowed && ctx.accounts.authority.key() == policy.insured),
+        ErrorCode::UnauthorizedAccess
+    );
+
+    let refund_amount = unexpired_premium(policy, now);
+    // SOL premiums can be refunded as lamports through a temporary account
+    let destination = match (&ctx.accounts.insured_token, &ctx.accounts.sol_refund) {
+        (Some(insured_token), None) => Some(insured_token),
+        (None, Some(sol_refund)) => {
+            require!(is_native_mint(&ctx.accounts.token_mint.key()), ErrorCode::NotNativeMint);
+            Some(sol_refund)
+        }
+        _ if refund_amount == 0 => None,
+        _ => return err!(ErrorCode::InvalidPayoutAccount),
+    };
+
+    if is_escrowed {
+        let premium_escrow = ctx.accounts.premium_escrow.as_ref().ok_or(ErrorCode::PremiumEscrowRequired)?;
+        // What's been earned so far still goes to the treasury
+        release_to_treasury(
+            &mut ctx.accounts.policy,
+            premium_escrow,
+            &mut ctx.accounts.treasury_vault,
+            &ctx.accounts.treasury_token,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            now,
+        )?;
+        if let Some(destination) = destination {
+            transfer_from_escrow(
+                &ctx.accounts.policy,
+                premium_escrow,
+                destination.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                refund_amount,
+            )?;
+        }
+        ctx.accounts.policy.premium_refunded = refund_amount;
+        close_escrow(
+            &ctx.accounts.policy,
+            ctx.accounts.premium_escrow.as_mut().ok_or(ErrorCode::PremiumEscrowRequired)?,
+            &ctx.accounts.treasury_token,
+            &ctx.accounts.token_mint,
+            ctx.accounts.insured.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    } else if let Some(destination) = destination.filter(|_| refund_amount > 0) {
+        transfer_from_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.treasury_token,
+            destination,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        let treasury_vault = &mut ctx.accounts.treasury_vault;
+        treasury_vault.total_refunded = treasury_vault.total_refunded.checked_add(refund_amount).ok_or(ErrorCode::MathOverflow)?;
+    }
+    // Closed even without a refund so its rent goes back to the authority
+    if let Some(sol_refund) = ctx.accounts.sol_refund.as_mut() {
+        let seeds = &[
+            b"treasury".as_ref(),
+            &[ctx.accounts.treasury.bump]
+        ];
+        unwrap_sol(
+            sol_refund,
+            ctx.accounts.treasury.to_account_info(),
+            &[&seeds[..]],
+            &ctx.accounts.authority,
+            ctx.accounts.insured.to_account_info(),
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+        )?;
+    }
+    if refund_amount > 0 {
+        write_receipt(&ctx.accounts.memo_program, refund_receipt(&ctx.accounts.policy.key(), refund_amount))?;
+    }
+
+    // Refunded premium was never earned
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    protocol_info.premiums_earned = protocol_info.premiums_earned.saturating_sub(refund_amount);
+
+    wind_down_policy(
+        &mut ctx.accounts.policy,
+        protocol_info,
+        &mut ctx.accounts.capital_pool,
+        &mut ctx.accounts.global_stats,
+        &ctx.accounts.legacy_claim,
+        refund_amount,
+    )
+}
+
+pub(crate) fn wind_down_policy(
+    policy: &mut Account<Policy>,
+    protocol_info: &mut Account<ProtocolInfo>,
+    capital_pool: &mut Account<CapitalPool>,
+    global_stats: &mut Account<GlobalStats>,
+    legacy_claim: &UncheckedAccount,
+    refund_amount: u64,
+) -> Result<()> {
+    require!(policy.is_active, ErrorCode::PolicyNotActive);
+
+    // A claim still waiting for a decision or its payout keeps the policy open
+    require!(policy.open_claims == 0, ErrorCode::ClaimPending);
+    check_legacy_claim_settled(legacy_claim)?;
+
+    // Approved claims already released their part of the coverage
+    let released_coverage = policy.coverage_amount.saturating_sub(policy.coverage_released);
+    capital_pool.active_coverage = capital_pool.active_coverage.saturating_sub(released_coverage);
+    global_stats.record_policy_closed(&capital_pool.token_mint, released_coverage, &policy.premium_mint, refund_amount)?;
+    policy.is_active = false;
+    protocol_info.active_policies = protocol_info.active_policies.saturating_sub(1);
+
+    emit!(PolicyWoundDown {
+        policy: policy.key(),
+        protocol: protocol_info.key(),
+        refund_amount,
+    });
+
+    Ok(())
+}
+
+// Closes the protocol once every policy has been wound down, returning the rent to its authority
+pub fn deregister_protocol(ctx: Context<DeregisterProtocol>) -> Result<()> {
+    let protocol_info = &mut ctx.accounts.protocol_info;
+
+    require!(protocol_info.is_deregistering, ErrorCode::ProtocolNotDeregistering);
+    require!(protocol_info.active_policies == 0, ErrorCode::ProtocolHasActivePolicies);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.registered_count = registry.registered_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+    // Deregistering protocols are inactive, but an automatic reactivation may not have been synced
+    registry.sync_active(protocol_info)?;
+    // The entry stays behind so later indexes remain in place
+    ctx.accounts.protocol_entry.is_registered = false;
+    update_directory(registry, &ctx.accounts.directory, |directory| {
+        directory.remove(protocol_info.registry_index)
+    })?;
+
+    emit!(ProtocolDeregistered {
+        protocol: protocol_info.key(),
+        authority: protocol_info.authority,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BeginProtocolDeregistration<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = protocol_info.authority == authority.key() ||
+            protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePolicy<'info> {
+    pub caller: Signer<'info>,
+
+    // Policies have two address schemes (see PolicyCounter), so the keys a policy stores bind it
+    #[account(
+        mut,
+        constraint = policy.protocol == protocol_info.key() @ ErrorCode::PolicyProtocolMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    /// CHECK: The policy's claim address from before claims were indexed, checked for a
+    /// pending claim in the handler; empty when the policy never had a claim there
+    #[account(
+        seeds = [b"claim", policy.key().as_ref()],
+        bump
+    )]
+    pub legacy_claim: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub keeper_reward: KeeperReward<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // Policies have two address schemes (see PolicyCounter), so the keys a policy stores bind it
+    #[account(
+        mut,
+        constraint = policy.protocol == protocol_info.key() @ ErrorCode::PolicyProtocolMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+
+    #[account(
+        mut,
+        constraint = protocol_info.is_deregistering @ ErrorCode::ProtocolNotDeregistering
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    // The admin or, for escrowed policies, the insured is checked in the handler
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    /// CHECK: The policy's claim address from before claims were indexed, checked for a
+    /// pending claim in the handler; empty when the policy never had a claim there
+    #[account(
+        seeds = [b"claim", policy.key().as_ref()],
+        bump
+    )]
+    pub legacy_claim: UncheckedAccount<'info>,
+
+    // Required for policies that escrowed their premium
+    #[account(
+        mut,
+        seeds = [b"premium-escrow", policy.key().as_ref()],
+        bump
+    )]
+    pub premium_escrow: Option<Account<'info, TokenAccount>>,
+
+    // Refunds of older policies come out of the treasury vault the premium was paid into
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump = treasury_vault.bump,
+        constraint = treasury_vault.mint == policy.premium_mint @ ErrorCode::InvalidTreasuryVault
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(
+        mut,
+        constraint = treasury_token.key() == treasury_vault.token_account @ ErrorCode::InvalidTreasuryVault
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
+
+    #[account(address = treasury_vault.mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Receives unwrapped SOL refunds
+    #[account(mut, address = policy.insured)]
+    pub insured: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = insured_token.owner == policy.insured,
+        constraint = insured_token.mint == treasury_token.mint
+    )]
+    pub insured_token: Option<Account<'info, TokenAccount>>,
+
+    // Passed instead of insured_token to refund a SOL premium as lamports; closed again
+    // in the same instruction
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"sol-refund", policy.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = treasury
+    )]
+    pub sol_refund: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    // Only needed for a memo receipt of the refund
+    pub memo_program: Option<Program<'info, Memo>>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterProtocol<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = rent_receiver,
+        constraint = protocol_info.authority == authority.key() ||
+            protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    /// CHECK: Receives the rent of the closed account; must be the protocol authority that paid it
+    #[account(
+        mut,
+        address = protocol_info.authority
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-entry".as_ref(), &protocol_info.registry_index.to_le_bytes()],
+        bump = protocol_entry.bump
+    )]
+    pub protocol_entry: Account<'info, ProtocolEntry>,
+
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
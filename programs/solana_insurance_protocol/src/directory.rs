@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::{ProtocolEntry, ProtocolInfo, ProtocolRegistry, ProtocolState, ErrorCode};
+
+// Protocols the directory lists, by registry index. Ones registered past it are
+// still enumerable through their ProtocolEntry accounts.
+pub const MAX_DIRECTORY_ENTRIES: usize = 512;
+
+#[zero_copy]
+pub struct DirectoryEntry {
+    pub name_hash: [u8; 32], // Hash of the protocol name; the name itself is on ProtocolInfo
+    pub protocol: Pubkey,
+    pub risk_score: u8,
+    pub is_active: u8,       // zero-copy fields can't be bools
+    pub is_registered: u8,
+    pub padding: [u8; 5],
+}
+
+// Every covered protocol in one account, so the UI can list them with a single read.
+// Too large to be created by the program, so it's a keypair account the admin
+// allocates and hands to initialize_protocol_directory.
+#[account(zero_copy)]
+pub struct ProtocolDirectory {
+    pub entries: [DirectoryEntry; MAX_DIRECTORY_ENTRIES],
+    pub entry_count: u64, // Slots in use, i.e. the highest listed registry index plus one
+}
+
+impl ProtocolDirectory {
+    pub const SIZE: usize = 8 + std::mem::size_of::<ProtocolDirectory>();
+
+    // Writes the protocol's slot; protocols past the capacity are left out
+    pub fn record(&mut self, protocol_info: &Account<ProtocolInfo>) {
+        let index = protocol_info.registry_index as usize;
+        if index >= MAX_DIRECTORY_ENTRIES {
+            return;
+        }
+
+        self.entries[index] = DirectoryEntry {
+            name_hash: hash(protocol_info.protocol_name.as_bytes()).to_bytes(),
+            protocol: protocol_info.key(),
+            risk_score: protocol_info.risk_score,
+            is_active: protocol_info.is_active as u8,
+            is_registered: 1,
+            padding: [0; 5],
+        };
+        self.entry_count = std::cmp::max(self.entry_count, index as u64 + 1);
+    }
+
+    pub fn remove(&mut self, index: u64) {
+        if let Some(entry) = self.entries.get_mut(index as usize) {
+            entry.is_active = 0;
+            entry.is_registered = 0;
+        }
+    }
+}
+
+#[event]
+pub struct ProtocolDirectoryInitialized {
+    pub directory: Pubkey,
+}
+
+// Once set, every instruction that changes a protocol's listed risk score or active
+// status has to keep the directory current
+pub fn initialize_protocol_directory(ctx: Context<InitializeProtocolDirectory>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.directory == Pubkey::default(), ErrorCode::ProtocolDirectoryAlreadySet);
+
+    ctx.accounts.directory.load_init()?.entry_count = 0;
+    registry.directory = ctx.accounts.directory.key();
+
+    emit!(ProtocolDirectoryInitialized {
+        directory: registry.directory,
+    });
+
+    Ok(())
+}
+
+// Updates the directory when it's in use. Its address is pinned by the account
+// constraints, so only a missing directory has to be checked here.
+pub fn update_directory(
+    registry: &ProtocolRegistry,
+    directory: &Option<AccountLoader<ProtocolDirectory>>,
+    update: impl FnOnce(&mut ProtocolDirectory),
+) -> Result<()> {
+    match directory {
+        Some(directory) => update(&mut *directory.load_mut()?),
+        None => require!(registry.directory == Pubkey::default(), ErrorCode::ProtocolDirectoryRequired),
+    }
+
+    Ok(())
+}
+
+// Anyone can bring a protocol's slot up to date, e.g. for protocols registered before
+// the directory existed. A deregistered protocol's
+// slot is cleared.
+pub fn sync_directory_entry(ctx: Context<SyncDirectoryEntry>) -> Result<()> {
+    let protocol_entry = &ctx.accounts.protocol_entry;
+    let mut directory = ctx.accounts.directory.load_mut()?;
+
+    match &ctx.accounts.protocol_info {
+        Some(protocol_info) if protocol_entry.is_registered => {
+            require!(protocol_info.key() == protocol_entry.protocol, ErrorCode::DirectoryEntryMismatch);
+            directory.record(protocol_info);
+        }
+        _ => {
+            require!(!protocol_entry.is_registered, ErrorCode::DirectoryEntryMismatch);
+            directory.remove(protocol_entry.index);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolDirectory<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(zero)]
+    pub directory: AccountLoader<'info, ProtocolDirectory>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct SyncDirectoryEntry<'info> {
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: AccountLoader<'info, ProtocolDirectory>,
+
+    #[account(
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    #[account(
+        seeds = [b"protocol-entry".as_ref(), &protocol_entry.index.to_le_bytes()],
+        bump = protocol_entry.bump
+    )]
+    pub protocol_entry: Account<'info, ProtocolEntry>,
+
+    // Omitted once the protocol is deregistered and its info account closed
+    pub protocol_info: Option<Account<'info, ProtocolInfo>>,
+}
@@ -0,0 +1,359 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::claims::{close_claim, transition_claim, Claim, ClaimStatus};
+use crate::{CapitalPool, Policy, ProtocolState, ErrorCode};
+
+pub const MAX_COUNCIL_MEMBERS: usize = 5;
+pub const MAX_COUNCIL_REASON_LEN: usize = 200;
+
+// Small set of keys, independent of the admin and the claim resolvers, that can
+// stop payouts while a governance or adjuster compromise is investigated
+#[account]
+pub struct EmergencyCouncil {
+    pub members: [Pubkey; MAX_COUNCIL_MEMBERS],
+    pub member_count: u8,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl EmergencyCouncil {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 * MAX_COUNCIL_MEMBERS + // members
+                           1 +      // member_count
+                           1 +      // bump
+                           1;       // version
+
+    pub fn is_member(&self, member: &Pubkey) -> bool {
+        self.members[..self.member_count as usize].contains(member)
+    }
+
+    // A majority of the council has to sign, so a single compromised member can't veto
+    // claims or lift another member's freeze
+    pub fn required_signers(&self) -> usize {
+        self.member_count as usize / 2 + 1
+    }
+
+    // The distinct members signing with `member`, who co-sign as remaining accounts
+    pub fn quorum(&self, member: &Pubkey, co_signers: &[AccountInfo]) -> Result<Vec<Pubkey>> {
+        let mut signers = vec![*member];
+        for co_signer in co_signers {
+            require!(
+                co_signer.is_signer && self.is_member(co_signer.key),
+                ErrorCode::NotCouncilMember
+            );
+            if !signers.contains(co_signer.key) {
+                signers.push(*co_signer.key);
+            }
+        }
+        require!(signers.len() >= self.required_signers(), ErrorCode::CouncilQuorumNotMet);
+
+        Ok(signers)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CouncilActionKind {
+    VetoClaim,
+    FreezePayouts,
+    UnfreezePayouts,
+}
+
+// The council's latest action on a claim or pool, so the reason and the members
+// behind it stay readable on-chain
+#[account]
+pub struct CouncilAction {
+    pub target: Pubkey,         // Vetoed claim or frozen pool
+    pub kind: CouncilActionKind,
+    pub reason: String,
+    pub signers: [Pubkey; MAX_COUNCIL_MEMBERS],
+    pub signer_count: u8,
+    pub acted_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl CouncilAction {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // target
+                           1 +      // kind
+                           4 + MAX_COUNCIL_REASON_LEN + // reason
+                           32 * MAX_COUNCIL_MEMBERS + // signers
+                           1 +      // signer_count
+                           8 +      // acted_at
+                           1 +      // bump
+                           1;       // version
+
+    fn record(
+        &mut self,
+        target: Pubkey,
+        kind: CouncilActionKind,
+        reason: &str,
+        signers: &[Pubkey],
+        bump: u8,
+    ) -> Result<()> {
+        self.target = target;
+        self.kind = kind;
+        self.reason = reason.to_string();
+        self.signers = [Pubkey::default(); MAX_COUNCIL_MEMBERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.acted_at = Clock::get()?.unix_timestamp;
+        self.bump = bump;
+        self.version = ACCOUNT_VERSION;
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ClaimVetoed {
+    pub claim: Pubkey,
+    pub member: Pubkey,
+    pub amount: u64,
+    pub reason: String,
+}
+
+#[event]
+pub struct PoolPayoutsFrozen {
+    pub capital_pool: Pubkey,
+    pub member: Pubkey,
+    pub frozen: bool,
+    pub reason: String,
+}
+
+pub fn initialize_emergency_council(
+    ctx: Context<InitializeEmergencyCouncil>,
+    members: Vec<Pubkey>,
+) -> Result<()> {
+    let emergency_council = &mut ctx.accounts.emergency_council;
+    set_council_members(emergency_council, &members)?;
+    emergency_council.bump = ctx.bumps.emergency_council;
+    emergency_council.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn update_emergency_council(
+    ctx: Context<UpdateEmergencyCouncil>,
+    members: Vec<Pubkey>,
+) -> Result<()> {
+    set_council_members(&mut ctx.accounts.emergency_council, &members)
+}
+
+fn set_council_members(
+    emergency_council: &mut EmergencyCouncil,
+    members: &[Pubkey],
+) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_COUNCIL_MEMBERS,
+        ErrorCode::InvalidCouncilSize
+    );
+
+    emergency_council.members = [Pubkey::default(); MAX_COUNCIL_MEMBERS];
+    emergency_council.members[..members.len()].copy_from_slice(members);
+    emergency_council.member_count = members.len() as u8;
+
+    Ok(())
+}
+
+fn validate_reason(reason: &str) -> Result<()> {
+    require!(
+        !reason.is_empty() && reason.len() <= MAX_COUNCIL_REASON_LEN,
+        ErrorCode::InvalidCouncilReason
+    );
+
+    Ok(())
+}
+
+// Cancels an approved claim before it is paid and releases its reserved payout
+pub fn veto_claim(
+    ctx: Context<VetoClaim>,
+    reason: String,
+) -> Result<()> {
+    validate_reason(&reason)?;
+    let signers = ctx.accounts.emergency_council.quorum(&ctx.accounts.member.key(), ctx.remaining_accounts)?;
+
+    let claim = &mut ctx.accounts.claim;
+    transition_claim(claim, ClaimStatus::Vetoed)?;
+    require!(
+        Clock::get()?.unix_timestamp < claim.challenge_ends_at,
+        ErrorCode::ChallengeWindowClosed
+    );
+
+    let policy = &mut ctx.accounts.policy;
+    // Saturating for claims approved before policies tracked reservations
+    policy.pending_reserved = policy.pending_reserved.saturating_sub(claim.amount);
+    close_claim(policy);
+
+    let pool = &mut ctx.accounts.capital_pool;
+    pool.reserved_capital = pool.reserved_capital.checked_sub(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+    pool.available_capital = pool.available_capital.checked_add(claim.amount).ok_or(ErrorCode::MathOverflow)?;
+
+    ctx.accounts.council_action.record(
+        claim.key(),
+        CouncilActionKind::VetoClaim,
+        &reason,
+        &signers,
+        ctx.bumps.council_action,
+    )?;
+
+    emit!(ClaimVetoed {
+        claim: claim.key(),
+        member: ctx.accounts.member.key(),
+        amount: claim.amount,
+        reason,
+    });
+
+    Ok(())
+}
+
+// Stops every payout from a pool until the council lifts the freeze
+pub fn set_pool_payouts_frozen(
+    ctx: Context<SetPoolPayoutsFrozen>,
+    frozen: bool,
+    reason: String,
+) -> Result<()> {
+    validate_reason(&reason)?;
+    let signers = ctx.accounts.emergency_council.quorum(&ctx.accounts.member.key(), ctx.remaining_accounts)?;
+
+    let capital_pool = &mut ctx.accounts.capital_pool;
+    capital_pool.payouts_frozen = frozen;
+
+    let kind = if frozen { CouncilActionKind::FreezePayouts } else { CouncilActionKind::UnfreezePayouts };
+    ctx.accounts.council_action.record(
+        capital_pool.key(),
+        kind,
+        &reason,
+        &signers,
+        ctx.bumps.council_action,
+    )?;
+
+    emit!(PoolPayoutsFrozen {
+        capital_pool: capital_pool.key(),
+        member: ctx.accounts.member.key(),
+        frozen,
+        reason,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeEmergencyCouncil<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = EmergencyCouncil::SIZE,
+        seeds = [b"emergency-council"],
+        bump
+    )]
+    pub emergency_council: Account<'info, EmergencyCouncil>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEmergencyCouncil<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"emergency-council"],
+        bump = emergency_council.bump
+    )]
+    pub emergency_council: Account<'info, EmergencyCouncil>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+// The other signing members are passed as remaining accounts
+#[derive(Accounts)]
+pub struct VetoClaim<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(
+        seeds = [b"emergency-council"],
+        bump = emergency_council.bump,
+        constraint = emergency_council.is_member(&member.key()) @ ErrorCode::NotCouncilMember
+    )]
+    pub emergency_council: Account<'info, EmergencyCouncil>,
+
+    #[account(
+        mut,
+        has_one = policy
+    )]
+    pub claim: Account<'info, Claim>,
+
+    // Bound to the claim by the key it stores; see PolicyCounter for why the policy's
+    // own address isn't re-derived
+    #[account(mut)]
+    pub policy: Account<'info, Policy>,
+
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump,
+        constraint = capital_pool.key() == policy.pool @ ErrorCode::PolicyPoolMismatch
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    #[account(
+        init,
+        payer = member,
+        space = CouncilAction::SIZE,
+        seeds = [b"council-action", claim.key().as_ref()],
+        bump
+    )]
+    pub council_action: Account<'info, CouncilAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// The other signing members are passed as remaining accounts
+#[derive(Accounts)]
+pub struct SetPoolPayoutsFrozen<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(
+        seeds = [b"emergency-council"],
+        bump = emergency_council.bump,
+        constraint = emergency_council.is_member(&member.key()) @ ErrorCode::NotCouncilMember
+    )]
+    pub emergency_council: Account<'info, EmergencyCouncil>,
+
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    // Overwritten by each freeze or unfreeze of the pool
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = CouncilAction::SIZE,
+        seeds = [b"council-action", capital_pool.key().as_ref()],
+        bump
+    )]
+    pub council_action: Account<'info, CouncilAction>,
+
+    pub system_program: Program<'info, System>,
+}
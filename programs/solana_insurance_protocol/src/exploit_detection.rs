@@ -1,72 +1,566 @@
 use anchor_lang::prelude::*;
-use crate::ProtocolState;
-use crate::{ProtocolInfo, ErrorCode};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolRegistry, ProtocolState};
+use crate::bonded_alerts::forfeit_alert_bond;
+use crate::alert_subscriptions::{notify_subscribers, AlertNotification};
+use crate::circuit_breaker::{record_confirmed_critical_alert, CircuitBreakerTripped};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::watchers::{slash_watcher, Watcher, WatcherRegistry, WatcherSlashed, MAX_ALERT_CONFIRMATIONS};
+use crate::incident::{record_alert_confirmed, record_alert_dismissed, record_alert_raised};
+use crate::session_keys::{authorize_with_session, SessionKey, SESSION_PERMISSION_ACKNOWLEDGE_ALERT};
+use crate::claims::MAX_RESOLUTION_NOTES_LEN;
+use crate::keeper::*; // KeeperReward needs its generated account modules in scope
+use crate::{CapitalPool, CapitalPoolType, DeactivationReason, ProtocolInfo, ErrorCode};
 
 #[account]
 pub struct ExploitAlert {
     pub protocol: Pubkey,
+    pub reporter: Pubkey,
     pub alert_time: i64,
-    pub anomaly_type: u8,  // 1 = TVL drop, 2 = Price anomaly, 3 = Transaction volume
-    pub severity: u8,      // 1-100 scale
-    pub details: String,
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub details_hash: [u8; 32], // Hash of the off-chain incident write-up
+    pub details_uri: String,
     pub is_confirmed: bool,
+    pub is_resolved: bool,
     pub resolution_notes: String,
+    pub response_tier: u8,
+    pub affected_pool: Pubkey,
+    pub bounty_claimed: bool,
+    pub is_malicious: bool,
+    pub expires_at: i64,   // After this an unresolved alert can be expired by anyone
+    pub confirmers: [Pubkey; MAX_ALERT_CONFIRMATIONS],
+    pub confirmation_count: u8,
+    pub required_confirmations: u8, // Lowered when a trusted watcher reports or confirms
+    pub is_active: bool,   // Set once enough watchers confirm; only active alerts trigger responses
+    pub ack_deadline: i64, // 0 when no acknowledgement from the protocol team is required
+    pub is_acknowledged: bool,
+    pub is_surcharging: bool, // Counted in the protocol's surcharging_alerts
+    pub bond_amount: u64,     // Posted by a reporter who isn't a watcher
+    pub bond_settled: bool,
+    pub suspicious_txs: [SuspiciousTransaction; MAX_SUSPICIOUS_TXS],
+    pub suspicious_tx_count: u8,
+    pub affected_accounts: [Pubkey; MAX_AFFECTED_ACCOUNTS],
+    pub affected_account_count: u8,
     pub bump: u8,
+    pub version: u8,
 }
 
 impl ExploitAlert {
     pub const SIZE: usize = 8 +     // discriminator
                            32 +     // protocol
+                           32 +     // reporter
                            8 +      // alert_time
                            1 +      // anomaly_type
                            1 +      // severity
-                           100 +    // details (max 96 chars + 4 bytes for string length)
+                           32 +     // details_hash
+                           4 + MAX_DETAILS_URI_LEN + // details_uri
                            1 +      // is_confirmed
-                           100 +    // resolution_notes (max 96 chars + 4 bytes for string length)
-                           1;       // bump
+                           1 +      // is_resolved
+                           4 + MAX_RESOLUTION_NOTES_LEN + // resolution_notes
+                           1 +      // response_tier
+                           32 +     // affected_pool
+                           1 +      // bounty_claimed
+                           1 +      // is_malicious
+                           8 +      // expires_at
+                           32 * MAX_ALERT_CONFIRMATIONS + // confirmers
+                           1 +      // confirmation_count
+                           1 +      // required_confirmations
+                           1 +      // is_active
+                           8 +      // ack_deadline
+                           1 +      // is_acknowledged
+                           1 +      // is_surcharging
+                           8 +      // bond_amount
+                           1 +      // bond_settled
+                           (64 + 8) * MAX_SUSPICIOUS_TXS + // suspicious_txs
+                           1 +      // suspicious_tx_count
+                           32 * MAX_AFFECTED_ACCOUNTS + // affected_accounts
+                           1 +      // affected_account_count
+                           1 +      // bump
+                           1;       // version
 }
 
-// Anomaly types
-pub const ANOMALY_TVL_DROP: u8 = 1;
-pub const ANOMALY_PRICE: u8 = 2;
-pub const ANOMALY_TX_VOLUME: u8 = 3;
+pub const MAX_DETAILS_URI_LEN: usize = 64;
+pub const MAX_SEVERITY_REASON_LEN: usize = 200;
+pub const MAX_SUSPICIOUS_TXS: usize = 3;
+pub const MAX_AFFECTED_ACCOUNTS: usize = 4;
+
+// Transaction an alert points to as evidence, so it can be looked up directly
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SuspiciousTransaction {
+    pub signature: [u8; 64],
+    pub slot: u64,
+}
+
+impl SuspiciousTransaction {
+    pub const EMPTY: SuspiciousTransaction = SuspiciousTransaction {
+        signature: [0; 64],
+        slot: 0,
+    };
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnomalyType {
+    TvlDrop,
+    PriceAnomaly,
+    TransactionVolume,
+    OracleManipulation,
+    FlashLoanAttack,
+    AdminKeyCompromise,
+    Depeg,
+    MissedHeartbeat,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum AlertSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl AlertSeverity {
+    // Share of the maximum bounty paid for a confirmed alert of this severity
+    pub fn bounty_pct(&self) -> u64 {
+        match self {
+            AlertSeverity::Low => 25,
+            AlertSeverity::Medium => 50,
+            AlertSeverity::High => 75,
+            AlertSeverity::Critical => 100,
+        }
+    }
+    
+    pub fn escalated(&self) -> AlertSeverity {
+        match self {
+            AlertSeverity::Low => AlertSeverity::Medium,
+            AlertSeverity::Medium => AlertSeverity::High,
+            AlertSeverity::High | AlertSeverity::Critical => AlertSeverity::Critical,
+        }
+    }
+}
+
+// Automated response tiers, each including the responses of the tiers below it
+pub const RESPONSE_EVENT_ONLY: u8 = 0;
+pub const RESPONSE_PREMIUM_SURCHARGE: u8 = 1;
+pub const RESPONSE_PAUSE_POLICIES: u8 = 2;
+pub const RESPONSE_PAUSE_WITHDRAWALS: u8 = 3;
+
+// Response tier applied for each alert severity
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AlertResponseConfig {
+    pub low_response: u8,
+    pub medium_response: u8,
+    pub high_response: u8,
+    pub critical_response: u8,
+    pub surcharge_bps: u64,
+    pub alert_ttl_secs: i64,
+    pub ack_deadline_secs: i64,  // Time the protocol team has to acknowledge a high-severity alert
+}
+
+impl AlertResponseConfig {
+    pub const SIZE: usize = 1 +     // low_response
+                           1 +      // medium_response
+                           1 +      // high_response
+                           1 +      // critical_response
+                           8 +      // surcharge_bps
+                           8 +      // alert_ttl_secs
+                           8;       // ack_deadline_secs
+    
+    pub fn is_valid(&self) -> bool {
+        self.low_response <= self.medium_response &&
+        self.medium_response <= self.high_response &&
+        self.high_response <= self.critical_response &&
+        self.critical_response <= RESPONSE_PAUSE_WITHDRAWALS &&
+        self.surcharge_bps <= 10000 &&
+        self.alert_ttl_secs > 0 &&
+        self.ack_deadline_secs > 0
+    }
+    
+    pub fn response_tier(&self, severity: AlertSeverity) -> u8 {
+        match severity {
+            AlertSeverity::Low => self.low_response,
+            AlertSeverity::Medium => self.medium_response,
+            AlertSeverity::High => self.high_response,
+            AlertSeverity::Critical => self.critical_response,
+        }
+    }
+    
+    // Acknowledgement deadline for an alert activated at `now`; only alerts that can
+    // still escalate need one
+    pub fn ack_deadline(&self, severity: AlertSeverity, now: i64) -> Result<i64> {
+        if severity == AlertSeverity::High {
+            Ok(now.checked_add(self.ack_deadline_secs).ok_or(ErrorCode::MathOverflow)?)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+pub const DEFAULT_ALERT_RESPONSE_CONFIG: AlertResponseConfig = AlertResponseConfig {
+    low_response: RESPONSE_EVENT_ONLY,
+    medium_response: RESPONSE_PREMIUM_SURCHARGE,
+    high_response: RESPONSE_PAUSE_POLICIES,
+    critical_response: RESPONSE_PAUSE_WITHDRAWALS,
+    surcharge_bps: 2500, // +25% on new premiums
+    alert_ttl_secs: 3 * 24 * 60 * 60,
+    ack_deadline_secs: 2 * 60 * 60,
+};
+
+// Lifecycle events carry enough context for off-chain responders to act on the log alone
+#[event]
+pub struct AlertCreated {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub reporter: Pubkey,
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub response_tier: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlertResolved {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub reporter: Pubkey,
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub resolver: Pubkey,
+    pub is_confirmed: bool,
+    pub is_malicious: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlertResponseTriggered {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub severity: AlertSeverity,
+    pub response_tier: u8,
+}
+
+#[event]
+pub struct AlertResponseLifted {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub response_tier: u8,
+}
+
+#[event]
+pub struct AlertExpired {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct AlertAcknowledged {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub acknowledged_by: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlertEscalated {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub response_tier: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlertSeverityUpdated {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub anomaly_type: AnomalyType,
+    pub previous_severity: AlertSeverity,
+    pub severity: AlertSeverity,
+    pub response_tier: u8,
+    pub updated_by: Pubkey,
+    pub reason: String,
+    pub slot: u64,
+}
+
+#[event]
+pub struct AlertEvidenceAdded {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub added_by: Pubkey,
+    pub suspicious_tx_count: u8,
+    pub affected_account_count: u8,
+}
+
+#[event]
+pub struct AlertConfirmedByWatcher {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub reporter: Pubkey,
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub watcher: Pubkey,
+    pub confirmation_count: u8,
+    pub slot: u64,
+}
+
+// Marks the alert active and applies its automated responses
+pub fn activate_alert(
+    exploit_alert: &mut Account<ExploitAlert>,
+    protocol_info: &mut ProtocolInfo,
+    affected_pool: Option<&mut CapitalPool>,
+    config: &AlertResponseConfig,
+) -> Result<()> {
+    exploit_alert.is_active = true;
+    exploit_alert.ack_deadline = config.ack_deadline(exploit_alert.severity, Clock::get()?.unix_timestamp)?;
+    apply_alert_response(exploit_alert, protocol_info, affected_pool, config)?;
+    
+    emit!(AlertResponseTriggered {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        severity: exploit_alert.severity,
+        response_tier: exploit_alert.response_tier,
+    });
+    
+    Ok(())
+}
+
+// The pool an alert acts on: the one it recorded, or before it has one, the pool of the
+// protocol's risk tier
+pub fn is_alert_pool(
+    exploit_alert: &ExploitAlert,
+    protocol_info: &ProtocolInfo,
+    pool: &Account<CapitalPool>,
+) -> bool {
+    if exploit_alert.affected_pool == Pubkey::default() {
+        pool.pool_type == CapitalPoolType::for_risk_score(protocol_info.risk_score)
+    } else {
+        pool.key() == exploit_alert.affected_pool
+    }
+}
+
+// Applies the automated responses for the alert's tier
+pub fn apply_alert_response(
+    exploit_alert: &mut ExploitAlert,
+    protocol_info: &mut ProtocolInfo,
+    affected_pool: Option<&mut CapitalPool>,
+    config: &AlertResponseConfig,
+) -> Result<()> {
+    let response_tier = exploit_alert.response_tier;
+    
+    if response_tier >= RESPONSE_PREMIUM_SURCHARGE {
+        protocol_info.alert_surcharge_bps = std::cmp::max(protocol_info.alert_surcharge_bps, config.surcharge_bps);
+        if !exploit_alert.is_surcharging {
+            exploit_alert.is_surcharging = true;
+            protocol_info.surcharging_alerts = protocol_info.surcharging_alerts.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+    if response_tier >= RESPONSE_PAUSE_POLICIES {
+        protocol_info.suspend();
+    }
+    if response_tier >= RESPONSE_PAUSE_WITHDRAWALS {
+        let pool = affected_pool.ok_or(ErrorCode::AffectedPoolRequired)?;
+        pool.withdrawals_paused = true;
+    }
+    
+    Ok(())
+}
+
+// Drops the alert's share of the premium surcharge once it is resolved.
+// The surcharge itself stays until the last surcharging alert is gone.
+pub fn release_alert_surcharge(
+    exploit_alert: &mut ExploitAlert,
+    protocol_info: &mut ProtocolInfo,
+) {
+    if !exploit_alert.is_surcharging {
+        return;
+    }
+    exploit_alert.is_surcharging = false;
+    protocol_info.surcharging_alerts = protocol_info.surcharging_alerts.saturating_sub(1);
+    if protocol_info.surcharging_alerts == 0 {
+        protocol_info.alert_surcharge_bps = 0;
+    }
+}
+
+// Undoes the automated responses of an alert that turned out to be false
+pub fn lift_alert_response(
+    exploit_alert: &mut ExploitAlert,
+    protocol_info: &mut ProtocolInfo,
+    affected_pool: Option<&mut CapitalPool>,
+    max_insurable_risk_score: u8,
+) -> Result<()> {
+    let response_tier = exploit_alert.response_tier;
+    
+    release_alert_surcharge(exploit_alert, protocol_info);
+    // Don't resume sales that are paused for another reason
+    if response_tier >= RESPONSE_PAUSE_POLICIES &&
+        !protocol_info.incident_state.is_incident() &&
+        protocol_info.deactivation_reason == DeactivationReason::None &&
+        protocol_info.risk_score <= max_insurable_risk_score
+    {
+        protocol_info.is_active = true;
+    }
+    if response_tier >= RESPONSE_PAUSE_WITHDRAWALS {
+        let pool = affected_pool.ok_or(ErrorCode::AffectedPoolRequired)?;
+        pool.withdrawals_paused = false;
+    }
+    
+    Ok(())
+}
 
 pub fn create_exploit_alert(
     ctx: Context<CreateExploitAlert>,
-    anomaly_type: u8,
-    severity: u8,
-    details: String,
+    anomaly_type: AnomalyType,
+    severity: AlertSeverity,
+    details_hash: [u8; 32],
+    details_uri: String,
 ) -> Result<()> {
     let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let config = ctx.accounts.protocol_state.alert_response;
     let clock = Clock::get()?;
     
-    // Verify anomaly type is valid
-    require!(
-        anomaly_type == ANOMALY_TVL_DROP || 
-        anomaly_type == ANOMALY_PRICE || 
-        anomaly_type == ANOMALY_TX_VOLUME,
-        ErrorCode::InvalidAnomalyType
-    );
-    
-    // Verify severity is in range 1-100
-    require!(severity > 0 && severity <= 100, ErrorCode::InvalidSeverity);
+    require!(details_uri.len() <= MAX_DETAILS_URI_LEN, ErrorCode::DetailsUriTooLong);
     
-    // Only protocol authority or protocol monitoring oracles can create alerts
+    // Only watchers with at least the minimum stake can create alerts, and only so many per window
     require!(
-        ctx.accounts.authority.key() == ctx.accounts.protocol_info.authority || 
-        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
-        ErrorCode::UnauthorizedAccess
+        ctx.accounts.watcher.is_staked(&ctx.accounts.watcher_registry),
+        ErrorCode::WatcherNotStaked
     );
+    ctx.accounts.watcher.record_alert(&ctx.accounts.watcher_registry, clock.unix_timestamp)?;
     
     // Initialize the alert
-    exploit_alert.protocol = ctx.accounts.protocol_info.key();
+    exploit_alert.protocol = protocol_info.key();
+    exploit_alert.reporter = ctx.accounts.authority.key();
     exploit_alert.alert_time = clock.unix_timestamp;
     exploit_alert.anomaly_type = anomaly_type;
     exploit_alert.severity = severity;
-    exploit_alert.details = details;
+    exploit_alert.details_hash = details_hash;
+    exploit_alert.details_uri = details_uri;
     exploit_alert.is_confirmed = false;
+    exploit_alert.is_resolved = false;
     exploit_alert.resolution_notes = String::new();
+    exploit_alert.response_tier = config.response_tier(severity);
+    exploit_alert.affected_pool = ctx.accounts.affected_pool.as_ref().map_or(Pubkey::default(), |pool| pool.key());
+    exploit_alert.bounty_claimed = false;
+    exploit_alert.is_malicious = false;
+    exploit_alert.expires_at = clock.unix_timestamp.checked_add(config.alert_ttl_secs).ok_or(ErrorCode::MathOverflow)?;
+    exploit_alert.confirmers = [Pubkey::default(); MAX_ALERT_CONFIRMATIONS];
+    exploit_alert.confirmers[0] = ctx.accounts.authority.key();
+    exploit_alert.confirmation_count = 1;
+    exploit_alert.required_confirmations = ctx.accounts.watcher_registry.confirmation_threshold_for(&ctx.accounts.watcher);
+    exploit_alert.is_active = false;
+    exploit_alert.ack_deadline = 0;
+    exploit_alert.is_acknowledged = false;
+    exploit_alert.is_surcharging = false;
+    exploit_alert.bond_amount = 0;
+    exploit_alert.bond_settled = false;
+    exploit_alert.suspicious_txs = [SuspiciousTransaction::EMPTY; MAX_SUSPICIOUS_TXS];
+    exploit_alert.suspicious_tx_count = 0;
+    exploit_alert.affected_accounts = [Pubkey::default(); MAX_AFFECTED_ACCOUNTS];
+    exploit_alert.affected_account_count = 0;
     exploit_alert.bump = ctx.bumps.exploit_alert;
+    exploit_alert.version = ACCOUNT_VERSION;
+    
+    protocol_info.alerts_raised = protocol_info.alerts_raised.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    record_alert_raised(protocol_info)?;
+    
+    emit!(AlertCreated {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        reporter: exploit_alert.reporter,
+        anomaly_type,
+        severity,
+        response_tier: exploit_alert.response_tier,
+        slot: clock.slot,
+    });
+    
+    // The reporter counts as the first confirmation
+    if exploit_alert.confirmation_count >= exploit_alert.required_confirmations {
+        activate_alert(
+            exploit_alert,
+            protocol_info,
+            ctx.accounts.affected_pool.as_deref_mut(),
+            &config,
+        )?;
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        notify_subscribers(
+            ctx.remaining_accounts,
+            exploit_alert.protocol,
+            exploit_alert.key(),
+            AlertNotification::Activated,
+        )?;
+    }
+    
+    Ok(())
+}
+
+pub fn confirm_alert(ctx: Context<ConfirmAlert>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let watcher_key = ctx.accounts.authority.key();
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(!exploit_alert.is_active, ErrorCode::AlertAlreadyActive);
+    require!(
+        ctx.accounts.watcher.is_staked(&ctx.accounts.watcher_registry),
+        ErrorCode::WatcherNotStaked
+    );
+    
+    // Bonded reporters aren't listed as confirmers but can't confirm their own alert either
+    let confirmation_count = exploit_alert.confirmation_count as usize;
+    require!(
+        watcher_key != exploit_alert.reporter &&
+        !exploit_alert.confirmers[..confirmation_count].contains(&watcher_key),
+        ErrorCode::AlertAlreadyConfirmedByWatcher
+    );
+    require!(confirmation_count < MAX_ALERT_CONFIRMATIONS, ErrorCode::AlertAlreadyActive);
+    
+    exploit_alert.confirmers[confirmation_count] = watcher_key;
+    exploit_alert.confirmation_count = exploit_alert.confirmation_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    exploit_alert.required_confirmations = std::cmp::min(
+        exploit_alert.required_confirmations,
+        ctx.accounts.watcher_registry.confirmation_threshold_for(&ctx.accounts.watcher),
+    );
+    
+    let clock = Clock::get()?;
+    ctx.accounts.watcher.record_confirmation(clock.unix_timestamp - exploit_alert.alert_time)?;
+    
+    emit!(AlertConfirmedByWatcher {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        reporter: exploit_alert.reporter,
+        anomaly_type: exploit_alert.anomaly_type,
+        severity: exploit_alert.severity,
+        watcher: watcher_key,
+        confirmation_count: exploit_alert.confirmation_count,
+        slot: clock.slot,
+    });
+    
+    if exploit_alert.confirmation_count >= exploit_alert.required_confirmations {
+        activate_alert(
+            exploit_alert,
+            &mut ctx.accounts.protocol_info,
+            ctx.accounts.affected_pool.as_deref_mut(),
+            &ctx.accounts.protocol_state.alert_response,
+        )?;
+        ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(&ctx.accounts.protocol_info)
+        })?;
+        notify_subscribers(
+            ctx.remaining_accounts,
+            exploit_alert.protocol,
+            exploit_alert.key(),
+            AlertNotification::Activated,
+        )?;
+    }
     
     Ok(())
 }
@@ -74,21 +568,543 @@ pub fn create_exploit_alert(
 pub fn resolve_exploit_alert(
     ctx: Context<ResolveExploitAlert>,
     is_confirmed: bool,
+    is_malicious: bool,
     resolution_notes: String,
 ) -> Result<()> {
     let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let protocol_info = &mut ctx.accounts.protocol_info;
     
-    // Only protocol authority can resolve alerts
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(!(is_confirmed && is_malicious), ErrorCode::InvalidAlertResolution);
+    require!(resolution_notes.len() <= MAX_RESOLUTION_NOTES_LEN, ErrorCode::ResolutionNotesTooLong);
+    
+    // Alerts are resolved by the admin or the claim resolvers, never by the protocol
+    // they accuse, since resolving one can slash the reporter
+    let resolver = ctx.accounts.authority.key();
     require!(
-        ctx.accounts.authority.key() == ctx.accounts.protocol_info.authority || 
-        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        resolver == ctx.accounts.protocol_state.authority ||
+        ctx.accounts.protocol_state.is_claim_resolver(&resolver),
         ErrorCode::UnauthorizedAccess
     );
     
     // Update the alert
     exploit_alert.is_confirmed = is_confirmed;
+    exploit_alert.is_resolved = true;
+    exploit_alert.is_malicious = is_malicious;
     exploit_alert.resolution_notes = resolution_notes;
     
+    emit!(AlertResolved {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        reporter: exploit_alert.reporter,
+        anomaly_type: exploit_alert.anomaly_type,
+        severity: exploit_alert.severity,
+        resolver: ctx.accounts.authority.key(),
+        is_confirmed,
+        is_malicious,
+        slot: Clock::get()?.slot,
+    });
+    notify_subscribers(
+        ctx.remaining_accounts,
+        exploit_alert.protocol,
+        exploit_alert.key(),
+        AlertNotification::Resolved,
+    )?;
+    
+    // A confirmed incident stops coverage sales and marks when the incident began,
+    // so claims can be limited to policies bought before it
+    if is_confirmed {
+        release_alert_surcharge(exploit_alert, protocol_info);
+        protocol_info.alerts_confirmed = protocol_info.alerts_confirmed.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        protocol_info.suspend();
+        record_alert_confirmed(protocol_info, exploit_alert.alert_time)?;
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        if exploit_alert.severity == AlertSeverity::Critical {
+            let now = Clock::get()?.unix_timestamp;
+            let protocol_state = &mut ctx.accounts.protocol_state;
+            if record_confirmed_critical_alert(protocol_state, &resolver, exploit_alert.protocol, now) {
+                emit!(CircuitBreakerTripped {
+                    critical_alerts: protocol_state.circuit_breaker.threshold,
+                    tripped_at: now,
+                });
+            }
+        }
+    } else {
+        protocol_info.alerts_false = protocol_info.alerts_false.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        record_alert_dismissed(protocol_info)?;
+        
+        if exploit_alert.is_active {
+            lift_alert_response(
+                exploit_alert,
+                protocol_info,
+                ctx.accounts.affected_pool.as_deref_mut(),
+                ctx.accounts.protocol_state.max_insurable_risk_score,
+            )?;
+            ctx.accounts.registry.sync_active(protocol_info)?;
+            update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+                directory.record(protocol_info)
+            })?;
+            
+            emit!(AlertResponseLifted {
+                alert: exploit_alert.key(),
+                protocol: exploit_alert.protocol,
+                response_tier: exploit_alert.response_tier,
+            });
+        }
+    }
+    
+    // Bonded reporters stake only their bond, which is forfeited for any false alert.
+    // Alerts raised automatically have no watcher to credit or slash.
+    if exploit_alert.bond_amount > 0 {
+        if !is_confirmed {
+            forfeit_alert_bond(
+                &mut ctx.accounts.exploit_alert,
+                &mut ctx.accounts.watcher_registry,
+                &ctx.accounts.stake_vault,
+                &ctx.accounts.treasury_token,
+                &ctx.accounts.stake_mint,
+                &ctx.accounts.token_program,
+            )?;
+        }
+        return Ok(());
+    }
+    if exploit_alert.reporter != Pubkey::default() {
+        let watcher = ctx.accounts.watcher.as_mut().ok_or(ErrorCode::ReporterWatcherRequired)?;
+        watcher.record_resolution(is_confirmed)?;
+    }
+    if is_malicious {
+        let watcher = ctx.accounts.watcher.as_mut().ok_or(ErrorCode::ReporterWatcherRequired)?;
+        let amount = slash_watcher(
+            watcher,
+            &mut ctx.accounts.watcher_registry,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.treasury_token,
+            &ctx.accounts.stake_mint,
+            &ctx.accounts.token_program,
+        )?;
+        
+        emit!(WatcherSlashed {
+            watcher: watcher.key(),
+            alert: ctx.accounts.exploit_alert.key(),
+            amount,
+            strikes: watcher.strikes,
+            is_revoked: watcher.is_revoked,
+        });
+    }
+    
+    Ok(())
+}
+
+pub fn acknowledge_alert(ctx: Context<AcknowledgeAlert>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let clock = Clock::get()?;
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(exploit_alert.ack_deadline != 0, ErrorCode::AcknowledgementNotRequired);
+    require!(!exploit_alert.is_acknowledged, ErrorCode::AlertAlreadyAcknowledged);
+    require!(clock.unix_timestamp <= exploit_alert.ack_deadline, ErrorCode::AcknowledgementDeadlinePassed);
+    authorize_with_session(
+        ctx.accounts.authority.key(),
+        &[ctx.accounts.protocol_info.authority],
+        &ctx.accounts.session_key,
+        SESSION_PERMISSION_ACKNOWLEDGE_ALERT,
+    )?;
+    
+    exploit_alert.is_acknowledged = true;
+    
+    emit!(AlertAcknowledged {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        acknowledged_by: ctx.accounts.authority.key(),
+        slot: clock.slot,
+    });
+    
+    Ok(())
+}
+
+// Raises an alert the protocol team didn't acknowledge in time to the next severity
+// and applies the extra responses. Anyone can call it once the deadline has passed.
+pub fn escalate_alert(ctx: Context<EscalateAlert>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let config = ctx.accounts.protocol_state.alert_response;
+    let clock = Clock::get()?;
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(
+        exploit_alert.ack_deadline != 0 && !exploit_alert.is_acknowledged,
+        ErrorCode::AcknowledgementNotRequired
+    );
+    require!(clock.unix_timestamp > exploit_alert.ack_deadline, ErrorCode::AcknowledgementDeadlineNotPassed);
+    
+    // An escalation to withdrawal pauses needs a pool; the caller supplies the protocol's
+    // tier pool if the reporter didn't
+    if exploit_alert.affected_pool == Pubkey::default() {
+        if let Some(pool) = &ctx.accounts.affected_pool {
+            exploit_alert.affected_pool = pool.key();
+        }
+    }
+    
+    exploit_alert.severity = exploit_alert.severity.escalated();
+    exploit_alert.response_tier = std::cmp::max(
+        exploit_alert.response_tier,
+        config.response_tier(exploit_alert.severity),
+    );
+    exploit_alert.ack_deadline = config.ack_deadline(exploit_alert.severity, clock.unix_timestamp)?;
+    
+    apply_alert_response(
+        exploit_alert,
+        &mut ctx.accounts.protocol_info,
+        ctx.accounts.affected_pool.as_deref_mut(),
+        &config,
+    )?;
+    ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+    update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+        directory.record(&ctx.accounts.protocol_info)
+    })?;
+    
+    emit!(AlertEscalated {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        anomaly_type: exploit_alert.anomaly_type,
+        severity: exploit_alert.severity,
+        response_tier: exploit_alert.response_tier,
+        slot: clock.slot,
+    });
+    notify_subscribers(
+        ctx.remaining_accounts,
+        exploit_alert.protocol,
+        exploit_alert.key(),
+        AlertNotification::Escalated,
+    )?;
+    
+    Ok(())
+}
+
+// Appends suspicious transactions and affected accounts to an open alert. The reporter,
+// confirming watchers and the admin can add evidence as the incident unfolds.
+pub fn add_alert_evidence(
+    ctx: Context<AddAlertEvidence>,
+    suspicious_txs: Vec<SuspiciousTransaction>,
+    affected_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let authority = ctx.accounts.authority.key();
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(
+        authority == exploit_alert.reporter ||
+        exploit_alert.confirmers[..exploit_alert.confirmation_count as usize].contains(&authority) ||
+        authority == ctx.accounts.protocol_state.authority,
+        ErrorCode::UnauthorizedAccess
+    );
+    
+    let tx_count = exploit_alert.suspicious_tx_count as usize;
+    let account_count = exploit_alert.affected_account_count as usize;
+    require!(
+        tx_count + suspicious_txs.len() <= MAX_SUSPICIOUS_TXS &&
+        account_count + affected_accounts.len() <= MAX_AFFECTED_ACCOUNTS,
+        ErrorCode::TooMuchAlertEvidence
+    );
+    
+    for tx in suspicious_txs.iter() {
+        let count = exploit_alert.suspicious_tx_count as usize;
+        require!(
+            !exploit_alert.suspicious_txs[..count].iter().any(|t| t.signature == tx.signature),
+            ErrorCode::DuplicateAlertEvidence
+        );
+        exploit_alert.suspicious_txs[count] = *tx;
+        exploit_alert.suspicious_tx_count = exploit_alert.suspicious_tx_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    }
+    for account in affected_accounts.iter() {
+        let count = exploit_alert.affected_account_count as usize;
+        require!(
+            !exploit_alert.affected_accounts[..count].contains(account),
+            ErrorCode::DuplicateAlertEvidence
+        );
+        exploit_alert.affected_accounts[count] = *account;
+        exploit_alert.affected_account_count = exploit_alert.affected_account_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    }
+    
+    emit!(AlertEvidenceAdded {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        added_by: authority,
+        suspicious_tx_count: exploit_alert.suspicious_tx_count,
+        affected_account_count: exploit_alert.affected_account_count,
+    });
+    
+    Ok(())
+}
+
+// Upgrades the severity of an unfolding incident. Responses only ever ratchet up here;
+// a report that turns out to be overstated is resolved as false instead.
+pub fn update_alert_severity(
+    ctx: Context<UpdateAlertSeverity>,
+    severity: AlertSeverity,
+    reason: String,
+) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let config = ctx.accounts.protocol_state.alert_response;
+    let clock = Clock::get()?;
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(severity > exploit_alert.severity, ErrorCode::SeverityNotUpgraded);
+    require!(
+        !reason.is_empty() && reason.len() <= MAX_SEVERITY_REASON_LEN,
+        ErrorCode::InvalidSeverityReason
+    );
+    
+    if exploit_alert.affected_pool == Pubkey::default() {
+        if let Some(pool) = &ctx.accounts.affected_pool {
+            exploit_alert.affected_pool = pool.key();
+        }
+    }
+    
+    let previous_severity = exploit_alert.severity;
+    exploit_alert.severity = severity;
+    exploit_alert.response_tier = std::cmp::max(
+        exploit_alert.response_tier,
+        config.response_tier(severity),
+    );
+    
+    // Alerts still waiting on confirmations pick up the new tier when they activate
+    if exploit_alert.is_active {
+        if !exploit_alert.is_acknowledged {
+            exploit_alert.ack_deadline = config.ack_deadline(severity, clock.unix_timestamp)?;
+        }
+        apply_alert_response(
+            exploit_alert,
+            &mut ctx.accounts.protocol_info,
+            ctx.accounts.affected_pool.as_deref_mut(),
+            &config,
+        )?;
+        ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(&ctx.accounts.protocol_info)
+        })?;
+    }
+    
+    emit!(AlertSeverityUpdated {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        anomaly_type: exploit_alert.anomaly_type,
+        previous_severity,
+        severity,
+        response_tier: exploit_alert.response_tier,
+        updated_by: ctx.accounts.authority.key(),
+        reason,
+        slot: clock.slot,
+    });
+    notify_subscribers(
+        ctx.remaining_accounts,
+        exploit_alert.protocol,
+        exploit_alert.key(),
+        AlertNotification::Escalated,
+    )?;
+    
+    Ok(())
+}
+
+// Closes out an alert nobody resolved in time. It's treated like a false alert,
+// except the reporter isn't slashed.
+pub fn expire_alert(ctx: Context<ExpireAlert>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let clock = Clock::get()?;
+    
+    require!(!exploit_alert.is_resolved, ErrorCode::AlertAlreadyResolved);
+    require!(clock.unix_timestamp >= exploit_alert.expires_at, ErrorCode::AlertNotExpired);
+    
+    exploit_alert.is_confirmed = false;
+    exploit_alert.is_resolved = true;
+    exploit_alert.resolution_notes = String::from("Expired without resolution");
+    record_alert_dismissed(protocol_info)?;
+    if exploit_alert.reporter != Pubkey::default() && exploit_alert.bond_amount == 0 {
+        ctx.accounts.watcher.as_mut().ok_or(ErrorCode::ReporterWatcherRequired)?.release_alert();
+    }
+    
+    emit!(AlertResolved {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        reporter: exploit_alert.reporter,
+        anomaly_type: exploit_alert.anomaly_type,
+        severity: exploit_alert.severity,
+        resolver: ctx.accounts.caller.key(),
+        is_confirmed: false,
+        is_malicious: false,
+        slot: clock.slot,
+    });
+    
+    if exploit_alert.is_active {
+        lift_alert_response(
+            exploit_alert,
+            protocol_info,
+            ctx.accounts.affected_pool.as_deref_mut(),
+            ctx.accounts.protocol_state.max_insurable_risk_score,
+        )?;
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        emit!(AlertResponseLifted {
+            alert: exploit_alert.key(),
+            protocol: exploit_alert.protocol,
+            response_tier: exploit_alert.response_tier,
+        });
+    }
+    emit!(AlertExpired {
+        alert: exploit_alert.key(),
+        protocol: exploit_alert.protocol,
+        expires_at: exploit_alert.expires_at,
+    });
+    notify_subscribers(
+        ctx.remaining_accounts,
+        exploit_alert.protocol,
+        exploit_alert.key(),
+        AlertNotification::Resolved,
+    )?;
+    
+    pay_keeper_reward(&mut ctx.accounts.keeper_reward, &mut ctx.accounts.protocol_state)
+}
+
+// Creates a program account at a PDA the way Anchor's `init` does. The alert address is
+// predictable, so a plain create_account could be blocked by sending lamports to it first.
+fn create_pda_account<'info>(
+    payer: &Signer<'info>,
+    account: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+    signer: &[&[&[u8]]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+    
+    if current_lamports == 0 {
+        let cpi_accounts = CreateAccount {
+            from: payer.to_account_info(),
+            to: account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer);
+        return system_program::create_account(cpi_ctx, rent, space as u64, &crate::ID);
+    }
+    
+    let top_up = rent.saturating_sub(current_lamports);
+    if top_up > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.to_account_info(),
+            to: account.to_account_info(),
+        };
+        system_program::transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), top_up)?;
+    }
+    let cpi_accounts = Allocate {
+        account_to_allocate: account.to_account_info(),
+    };
+    system_program::allocate(
+        CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer),
+        space as u64,
+    )?;
+    let cpi_accounts = Assign {
+        account_to_assign: account.to_account_info(),
+    };
+    system_program::assign(
+        CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer),
+        &crate::ID,
+    )
+}
+
+// Alert raised by an on-chain check rather than a watcher
+pub struct SystemAlert {
+    pub anomaly_type: AnomalyType,
+    pub severity: AlertSeverity,
+    pub details: String, // Only its hash is stored on the alert
+}
+
+// Creates an alert with no reporting watcher at the `exploit_alert_info` PDA and applies
+// its responses straight away, since there are no watchers to confirm it
+pub fn raise_system_alert<'info>(
+    payer: &Signer<'info>,
+    exploit_alert_info: &UncheckedAccount<'info>,
+    protocol_info: &mut Account<'info, ProtocolInfo>,
+    protocol_state: &Account<'info, ProtocolState>,
+    system_program: &Program<'info, System>,
+    exploit_alert_bump: u8,
+    alert: SystemAlert,
+) -> Result<()> {
+    let config = protocol_state.alert_response;
+    let clock = Clock::get()?;
+    let protocol_key = protocol_info.key();
+    let alert_time_bytes = clock.unix_timestamp.to_le_bytes();
+    
+    let seeds = &[
+        b"exploit-alert".as_ref(),
+        protocol_key.as_ref(),
+        alert_time_bytes.as_ref(),
+        &[exploit_alert_bump]
+    ];
+    let signer = &[&seeds[..]];
+    create_pda_account(payer, exploit_alert_info, system_program, signer, ExploitAlert::SIZE)?;
+    
+    let severity = alert.severity;
+    let mut exploit_alert = ExploitAlert {
+        protocol: protocol_key,
+        reporter: Pubkey::default(),
+        alert_time: clock.unix_timestamp,
+        anomaly_type: alert.anomaly_type,
+        severity,
+        details_hash: hash(alert.details.as_bytes()).to_bytes(),
+        details_uri: String::new(),
+        is_confirmed: false,
+        is_resolved: false,
+        resolution_notes: String::new(),
+        response_tier: config.response_tier(severity),
+        affected_pool: Pubkey::default(),
+        bounty_claimed: false,
+        is_malicious: false,
+        expires_at: clock.unix_timestamp.checked_add(config.alert_ttl_secs).ok_or(ErrorCode::MathOverflow)?,
+        confirmers: [Pubkey::default(); MAX_ALERT_CONFIRMATIONS],
+        confirmation_count: 0,
+        required_confirmations: 0,
+        is_active: true,
+        ack_deadline: config.ack_deadline(severity, clock.unix_timestamp)?,
+        is_acknowledged: false,
+        is_surcharging: false,
+        bond_amount: 0,
+        bond_settled: false,
+        suspicious_txs: [SuspiciousTransaction::EMPTY; MAX_SUSPICIOUS_TXS],
+        suspicious_tx_count: 0,
+        affected_accounts: [Pubkey::default(); MAX_AFFECTED_ACCOUNTS],
+        affected_account_count: 0,
+        bump: exploit_alert_bump,
+        version: ACCOUNT_VERSION,
+    };
+    
+    protocol_info.alerts_raised = protocol_info.alerts_raised.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    record_alert_raised(protocol_info)?;
+    apply_alert_response(&mut exploit_alert, protocol_info, None, &config)?;
+    
+    exploit_alert.try_serialize(&mut &mut exploit_alert_info.try_borrow_mut_data()?[..])?;
+    
+    emit!(AlertCreated {
+        alert: exploit_alert_info.key(),
+        protocol: protocol_key,
+        reporter: exploit_alert.reporter,
+        anomaly_type: exploit_alert.anomaly_type,
+        severity,
+        response_tier: exploit_alert.response_tier,
+        slot: clock.slot,
+    });
+    emit!(AlertResponseTriggered {
+        alert: exploit_alert_info.key(),
+        protocol: protocol_key,
+        severity,
+        response_tier: exploit_alert.response_tier,
+    });
+    
     Ok(())
 }
 
@@ -106,15 +1122,110 @@ pub struct CreateExploitAlert<'info> {
     )]
     pub exploit_alert: Account<'info, ExploitAlert>,
     
+    #[account(mut)]
     pub protocol_info: Account<'info, ProtocolInfo>,
     
     #[account(
         seeds = [b"protocol-state"],
-        bump
+        bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    // Pool of the protocol's risk tier, whose withdrawals are paused by a critical alert
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = affected_pool.pool_type == CapitalPoolType::for_risk_score(protocol_info.risk_score) @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    #[account(
+        mut,
+        seeds = [b"watcher", authority.key().as_ref()],
+        bump = watcher.bump,
+        constraint = watcher.owner == authority.key() @ ErrorCode::WatcherNotStaked
+    )]
+    pub watcher: Account<'info, Watcher>,
+    
+    #[account(
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+    
     pub system_program: Program<'info, System>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmAlert<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = affected_pool.key() == exploit_alert.affected_pool @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    #[account(
+        mut,
+        seeds = [b"watcher", authority.key().as_ref()],
+        bump = watcher.bump,
+        constraint = watcher.owner == authority.key() @ ErrorCode::WatcherNotStaked
+    )]
+    pub watcher: Account<'info, Watcher>,
+    
+    #[account(
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
 }
 
 #[derive(Accounts)]
@@ -129,11 +1240,249 @@ pub struct ResolveExploitAlert<'info> {
     )]
     pub exploit_alert: Account<'info, ExploitAlert>,
     
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    // Mutable to record confirmed critical alerts for the circuit breaker
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = affected_pool.key() == exploit_alert.affected_pool @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    // Reporting watcher, credited with the outcome and slashed if the alert was malicious
+    #[account(
+        mut,
+        seeds = [b"watcher", exploit_alert.reporter.as_ref()],
+        bump = watcher.bump
+    )]
+    pub watcher: Option<Account<'info, Watcher>>,
+    
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+    
+    #[account(
+        mut,
+        constraint = stake_vault.key() == watcher_registry.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token.mint == watcher_registry.stake_mint,
+        constraint = treasury_token.owner == protocol_state.authority
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
+    
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct AcknowledgeAlert<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    // Only needed when the signer is a delegate of the protocol authority
+    #[account(
+        seeds = [b"session-key", session_key.owner.as_ref(), authority.key().as_ref()],
+        bump = session_key.bump
+    )]
+    pub session_key: Option<Account<'info, SessionKey>>,
+}
+
+#[derive(Accounts)]
+pub struct EscalateAlert<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = is_alert_pool(&exploit_alert, &protocol_info, affected_pool) @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct AddAlertEvidence<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", exploit_alert.protocol.as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAlertSeverity<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(mut)]
     pub protocol_info: Account<'info, ProtocolInfo>,
     
     #[account(
         seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = is_alert_pool(&exploit_alert, &protocol_info, affected_pool) @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
         bump
     )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireAlert<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
     pub protocol_state: Account<'info, ProtocolState>,
-}
\ No newline at end of file
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[affected_pool.pool_type as u8][..]],
+        bump = affected_pool.bump,
+        constraint = affected_pool.key() == exploit_alert.affected_pool @ ErrorCode::AffectedPoolMismatch
+    )]
+    pub affected_pool: Option<Account<'info, CapitalPool>>,
+    
+    pub keeper_reward: KeeperReward<'info>,
+    
+    // Reporting watcher, whose stake the alert no longer locks
+    #[account(
+        mut,
+        seeds = [b"watcher", exploit_alert.reporter.as_ref()],
+        bump = watcher.bump
+    )]
+    pub watcher: Option<Account<'info, Watcher>>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
@@ -0,0 +1,256 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::math::{mul_div_ceil, mul_div_floor};
+use crate::{ProtocolInfo, ProtocolState, ErrorCode};
+
+pub const MAX_PROTOCOL_FEE_BPS: u64 = 1000; // 10% of each premium
+pub const PROTOCOL_FEE_TIMELOCK_SECS: i64 = 2 * 24 * 60 * 60;
+
+#[event]
+pub struct ProtocolFeeChangeScheduled {
+    pub current_fee: u64,
+    pub new_fee: u64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ProtocolFeeUpdated {
+    pub previous_fee: u64,
+    pub new_fee: u64,
+}
+
+#[event]
+pub struct ProtocolFeeTermsUpdated {
+    pub protocol: Pubkey,
+    pub fee_override_bps: Option<u64>,
+    pub revenue_share_bps: u64,
+    pub revenue_share_account: Pubkey,
+}
+
+#[event]
+pub struct ProtocolFeesWithdrawn {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+// Protocol's cut of a premium, rounded up; `protocol_fee` is in basis points
+pub fn protocol_fee_amount(premium_amount: u64, protocol_fee: u64) -> Result<u64> {
+    mul_div_ceil(premium_amount, protocol_fee as u128, 10000).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+// Fee charged on a protocol's policies: its override if it has one, otherwise the global fee
+pub fn effective_protocol_fee(protocol_state: &ProtocolState, protocol_info: &ProtocolInfo) -> u64 {
+    protocol_info.fee_override_bps.unwrap_or(protocol_state.protocol_fee)
+}
+
+// Part of a fee paid to the covered protocol, rounded down; `revenue_share_bps` is a share of the fee
+pub fn revenue_share_amount(fee_amount: u64, revenue_share_bps: u64) -> Result<u64> {
+    mul_div_floor(fee_amount, revenue_share_bps as u128, 10000).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+// Schedules a fee change that can only be applied once the timelock has passed,
+// so policy buyers see it coming. A new schedule replaces any pending one.
+pub fn set_protocol_fee(
+    ctx: Context<SetProtocolFee>,
+    new_fee_bps: u64,
+) -> Result<()> {
+    schedule_protocol_fee(&mut ctx.accounts.protocol_state, new_fee_bps)
+}
+
+pub(crate) fn schedule_protocol_fee(
+    protocol_state: &mut ProtocolState,
+    new_fee_bps: u64,
+) -> Result<()> {
+    require!(new_fee_bps <= MAX_PROTOCOL_FEE_BPS, ErrorCode::ProtocolFeeTooHigh);
+
+    let effective_at = Clock::get()?.unix_timestamp.checked_add(PROTOCOL_FEE_TIMELOCK_SECS).ok_or(ErrorCode::MathOverflow)?;
+    protocol_state.pending_protocol_fee = new_fee_bps;
+    protocol_state.protocol_fee_effective_at = effective_at;
+
+    emit!(ProtocolFeeChangeScheduled {
+        current_fee: protocol_state.protocol_fee,
+        new_fee: new_fee_bps,
+        effective_at,
+    });
+
+    Ok(())
+}
+
+// Anyone can apply a scheduled fee change once it's due
+pub fn execute_protocol_fee_change(ctx: Context<ExecuteProtocolFeeChange>) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+
+    require!(protocol_state.protocol_fee_effective_at != 0, ErrorCode::NoPendingProtocolFee);
+    require!(
+        Clock::get()?.unix_timestamp >= protocol_state.protocol_fee_effective_at,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let previous_fee = protocol_state.protocol_fee;
+    protocol_state.protocol_fee = protocol_state.pending_protocol_fee;
+    protocol_state.pending_protocol_fee = 0;
+    protocol_state.protocol_fee_effective_at = 0;
+
+    emit!(ProtocolFeeUpdated {
+        previous_fee,
+        new_fee: protocol_state.protocol_fee,
+    });
+
+    Ok(())
+}
+
+// Takes effect immediately: the vault has to be owned by the protocol state, so moving
+// it changes neither what anyone pays nor who can take the fees out
+pub fn set_fee_vault(ctx: Context<SetFeeVault>) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    protocol_state.fee_vault = ctx.accounts.fee_vault.key();
+
+    Ok(())
+}
+
+// A protocol's fee override and the share of its fees paid to its own treasury, e.g.
+// for protocols that subsidize their members' coverage. Changed through the timelocked
+// `ParameterChange::ProtocolFeeTerms`.
+pub(crate) fn validate_protocol_fee_terms(
+    fee_override_bps: Option<u64>,
+    revenue_share_bps: u64,
+    revenue_share_account: &Pubkey,
+) -> Result<()> {
+    if let Some(fee_override_bps) = fee_override_bps {
+        require!(fee_override_bps <= MAX_PROTOCOL_FEE_BPS, ErrorCode::ProtocolFeeTooHigh);
+    }
+    require!(revenue_share_bps <= 10000, ErrorCode::InvalidRevenueShare);
+    require!(
+        revenue_share_bps == 0 || *revenue_share_account != Pubkey::default(),
+        ErrorCode::RevenueShareAccountRequired
+    );
+    Ok(())
+}
+
+pub(crate) fn set_protocol_fee_terms(
+    protocol_info: &mut Account<ProtocolInfo>,
+    fee_override_bps: Option<u64>,
+    revenue_share_bps: u64,
+    revenue_share_account: Pubkey,
+) {
+    protocol_info.fee_override_bps = fee_override_bps;
+    protocol_info.revenue_share_bps = revenue_share_bps;
+    protocol_info.revenue_share_account = revenue_share_account;
+
+    emit!(ProtocolFeeTermsUpdated {
+        protocol: protocol_info.key(),
+        fee_override_bps,
+        revenue_share_bps,
+        revenue_share_account,
+    });
+}
+
+pub fn withdraw_protocol_fees(
+    ctx: Context<WithdrawProtocolFees>,
+    amount: u64,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+
+    let withdrawable = protocol_state.fees_collected.checked_sub(protocol_state.fees_withdrawn).ok_or(ErrorCode::MathOverflow)?;
+    require!(amount <= withdrawable, ErrorCode::InsufficientProtocolFees);
+    protocol_state.fees_withdrawn = protocol_state.fees_withdrawn.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"protocol-state".as_ref(),
+        &[protocol_state.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.fee_vault.to_account_info(),
+        mint: ctx.accounts.fee_mint.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: protocol_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.fee_mint.decimals)?;
+
+    emit!(ProtocolFeesWithdrawn {
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolFee<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProtocolFeeChange<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeVault<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    // Owned by the protocol state PDA so fees can only leave through withdraw_protocol_fees
+    #[account(
+        constraint = fee_vault.owner == protocol_state.key() @ ErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == protocol_state.fee_vault @ ErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == fee_vault.mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    #[account(address = fee_vault.mint)]
+    pub fee_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
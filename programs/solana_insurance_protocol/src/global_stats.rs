@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::capital_management::CapitalPoolType;
+use crate::mint_registry::MAX_REGISTERED_MINTS;
+use crate::{ProtocolState, ErrorCode};
+
+pub const POOL_TYPE_COUNT: usize = 3;
+
+// Totals for one token, in its base units; amounts in different tokens don't add up
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MintTotals {
+    pub mint: Pubkey,
+    pub active_coverage: u64,
+    pub premiums_collected: u64, // Net of refunds on cancelled policies
+    pub claims_paid: u64,
+}
+
+impl MintTotals {
+    pub const SIZE: usize = 32 +    // mint
+                           8 +      // active_coverage
+                           8 +      // premiums_collected
+                           8;       // claims_paid
+}
+
+const EMPTY_MINT_TOTALS: MintTotals = MintTotals {
+    mint: Pubkey::new_from_array([0; 32]),
+    active_coverage: 0,
+    premiums_collected: 0,
+    claims_paid: 0,
+};
+
+// Protocol-wide totals kept up to date by the instructions that change them, so
+// dashboards can read one account instead of scanning every pool and policy.
+// Counting starts when the account is created.
+#[account]
+pub struct GlobalStats {
+    // Summed across tokens, so no longer kept since Version 16; see mint_totals
+    pub total_active_coverage: u64,
+    pub total_premiums_collected: u64,
+    pub total_claims_paid: u64,
+    pub pool_tvl: [u64; POOL_TYPE_COUNT], // Capital held by each pool tier, indexed by pool type - 1
+    pub active_policies: u64,
+    pub bump: u8,
+    pub version: u8,
+    // Version 16
+    pub mint_totals: [MintTotals; MAX_REGISTERED_MINTS], // Coverage is in the pool's token, premiums in the policy's
+}
+
+impl GlobalStats {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // total_active_coverage
+                           8 +      // total_premiums_collected
+                           8 +      // total_claims_paid
+                           8 * POOL_TYPE_COUNT + // pool_tvl
+                           8 +      // active_policies
+                           1 +      // bump
+                           1 +      // version
+                           MintTotals::SIZE * MAX_REGISTERED_MINTS; // mint_totals
+
+    pub fn mint_totals(&self, mint: &Pubkey) -> Option<&MintTotals> {
+        self.mint_totals.iter().find(|totals| totals.mint == *mint)
+    }
+
+    // The token's totals, taking a free entry the first time the token is counted.
+    // There's an entry for every token the mint registry can list.
+    pub fn mint_totals_mut(&mut self, mint: &Pubkey) -> Result<&mut MintTotals> {
+        let index = self.mint_totals.iter()
+            .position(|totals| totals.mint == *mint)
+            .or_else(|| self.mint_totals.iter().position(|totals| totals.mint == Pubkey::default()))
+            .ok_or(ErrorCode::MintRegistryFull)?;
+        let totals = &mut self.mint_totals[index];
+        totals.mint = *mint;
+        Ok(totals)
+    }
+
+    pub fn record_policy_created(
+        &mut self,
+        coverage_mint: &Pubkey,
+        coverage_amount: u64,
+        premium_mint: &Pubkey,
+        premium_amount: u64,
+    ) -> Result<()> {
+        let coverage_totals = self.mint_totals_mut(coverage_mint)?;
+        coverage_totals.active_coverage = coverage_totals.active_coverage.checked_add(coverage_amount).ok_or(ErrorCode::MathOverflow)?;
+        let premium_totals = self.mint_totals_mut(premium_mint)?;
+        premium_totals.premiums_collected = premium_totals.premiums_collected.checked_add(premium_amount).ok_or(ErrorCode::MathOverflow)?;
+        self.active_policies = self.active_policies.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // `released_coverage` is 0 when the coverage was already released by an approved claim
+    pub fn record_policy_closed(
+        &mut self,
+        coverage_mint: &Pubkey,
+        released_coverage: u64,
+        premium_mint: &Pubkey,
+        refund_amount: u64,
+    ) -> Result<()> {
+        self.release_coverage(coverage_mint, released_coverage)?;
+        let premium_totals = self.mint_totals_mut(premium_mint)?;
+        premium_totals.premiums_collected = premium_totals.premiums_collected.saturating_sub(refund_amount);
+        self.active_policies = self.active_policies.saturating_sub(1);
+        Ok(())
+    }
+
+    pub fn release_coverage(&mut self, coverage_mint: &Pubkey, coverage_amount: u64) -> Result<()> {
+        let totals = self.mint_totals_mut(coverage_mint)?;
+        totals.active_coverage = totals.active_coverage.saturating_sub(coverage_amount);
+        Ok(())
+    }
+
+    pub fn record_claim_paid(&mut self, pool_type: CapitalPoolType, mint: &Pubkey, amount: u64) -> Result<()> {
+        let totals = self.mint_totals_mut(mint)?;
+        totals.claims_paid = totals.claims_paid.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        self.remove_pool_capital(pool_type, amount);
+        Ok(())
+    }
+
+    pub fn add_pool_capital(&mut self, pool_type: CapitalPoolType, amount: u64) -> Result<()> {
+        let tvl = &mut self.pool_tvl[pool_type as usize - 1];
+        *tvl = tvl.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn remove_pool_capital(&mut self, pool_type: CapitalPoolType, amount: u64) {
+        let tvl = &mut self.pool_tvl[pool_type as usize - 1];
+        *tvl = tvl.saturating_sub(amount);
+    }
+}
+
+pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.total_active_coverage = 0;
+    global_stats.total_premiums_collected = 0;
+    global_stats.total_claims_paid = 0;
+    global_stats.pool_tvl = [0; POOL_TYPE_COUNT];
+    global_stats.active_policies = 0;
+    global_stats.mint_totals = [EMPTY_MINT_TOTALS; MAX_REGISTERED_MINTS];
+    global_stats.bump = ctx.bumps.global_stats;
+    global_stats.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalStats<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GlobalStats::SIZE,
+        seeds = [b"global-stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,627 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::capital_management::{CapitalPool, CapitalPoolType};
+use crate::config::FeeRates;
+use crate::fees::schedule_protocol_fee;
+use crate::math::mul_div_ceil;
+use crate::risk_committee::{set_members, RiskCommittee, MAX_COMMITTEE_MEMBERS};
+use crate::{ProtocolState, RiskWeights, ErrorCode};
+
+#[account]
+pub struct Governance {
+    pub governance_mint: Pubkey,
+    pub governance_vault: Pubkey,
+    pub total_staked: u64,
+    pub proposal_count: u64,
+    pub min_proposal_stake: u64,  // Stake needed to open a proposal
+    pub quorum_votes: u64,        // Votes in favour needed for a proposal to pass
+    pub voting_period_secs: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Governance {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // governance_mint
+                           32 +     // governance_vault
+                           8 +      // total_staked
+                           8 +      // proposal_count
+                           8 +      // min_proposal_stake
+                           8 +      // quorum_votes
+                           8 +      // voting_period_secs
+                           1 +      // bump
+                           1;       // version
+}
+
+#[account]
+pub struct GovernanceStake {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub locked_until: i64, // End of the latest vote or discounted policy the stake was used for
+    pub bump: u8,
+    pub version: u8,
+    // Version 15
+    pub staked_at: i64,    // Last time stake was added
+}
+
+impl GovernanceStake {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // owner
+                           8 +      // amount
+                           8 +      // locked_until
+                           1 +      // bump
+                           1 +      // version
+                           8;       // staked_at
+}
+
+// Premium discount (bps) for insureds staking at least the given multiple of the
+// proposal stake, highest tier first
+pub const STAKER_DISCOUNT_TIERS: [(u64, u64); 3] = [
+    (20, 1500),
+    (5, 1000),
+    (1, 500),
+];
+
+pub fn staker_discount_bps(stake_amount: u64, min_proposal_stake: u64) -> u64 {
+    if stake_amount == 0 {
+        return 0;
+    }
+
+    STAKER_DISCOUNT_TIERS
+        .iter()
+        .find(|(multiple, _)| stake_amount >= min_proposal_stake.saturating_mul(*multiple))
+        .map(|(_, discount_bps)| *discount_bps)
+        .unwrap_or(0)
+}
+
+// Discounted rates round up, like the premiums and fees they're charged at
+pub fn apply_staker_discount(premium_rate_bps: u64, discount_bps: u64) -> Result<u64> {
+    let remaining_bps = 10000u64.checked_sub(discount_bps).ok_or(ErrorCode::MathOverflow)?;
+    mul_div_ceil(premium_rate_bps, remaining_bps as u128, 10000).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+// Config's performance fee on an LP's rewards, discounted if the owner's stake was in place for the
+// whole stretch being banked. Stake added since then doesn't count, so it can't be staked
+// just for the checkpoint and pulled straight after.
+pub fn lp_performance_fee_bps(
+    fee_rates: &FeeRates,
+    governance: Option<&Governance>,
+    stake: Option<&GovernanceStake>,
+    accrued_until: i64,
+) -> Result<u64> {
+    let discount_bps = match (governance, stake) {
+        (Some(governance), Some(stake)) if stake.staked_at <= accrued_until => {
+            staker_discount_bps(stake.amount, governance.min_proposal_stake)
+        }
+        _ => 0,
+    };
+    apply_staker_discount(fee_rates.lp_performance_fee_bps, discount_bps)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ProposalAction {
+    SetPoolRiskProfile {
+        pool_type: CapitalPoolType,
+        risk_weights: RiskWeights,
+        max_risk_score: u8,
+    },
+    SetProtocolFee {
+        fee_bps: u64,
+    },
+    SetRiskCommittee {
+        members: Vec<Pubkey>,
+        quorum: u8,
+    },
+}
+
+impl ProposalAction {
+    // Largest variant is SetRiskCommittee with a full committee
+    pub const SIZE: usize = 1 + 4 + 32 * MAX_COMMITTEE_MEMBERS + 1;
+}
+
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub is_executed: bool,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Proposal {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // id
+                           32 +     // proposer
+                           ProposalAction::SIZE + // action
+                           8 +      // votes_for
+                           8 +      // votes_against
+                           8 +      // created_at
+                           8 +      // voting_ends_at
+                           1 +      // is_executed
+                           1 +      // bump
+                           1;       // version
+
+    pub fn has_passed(&self, quorum_votes: u64) -> bool {
+        self.votes_for > self.votes_against && self.votes_for >= quorum_votes
+    }
+}
+
+// Marks that a staker has voted on a proposal
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub in_favor: bool,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl VoteRecord {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // proposal
+                           32 +     // voter
+                           8 +      // weight
+                           1 +      // in_favor
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub in_favor: bool,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+}
+
+pub fn initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    min_proposal_stake: u64,
+    quorum_votes: u64,
+    voting_period_secs: i64,
+) -> Result<()> {
+    require!(quorum_votes > 0 && voting_period_secs > 0, ErrorCode::InvalidGovernanceConfig);
+
+    let governance = &mut ctx.accounts.governance;
+    governance.governance_mint = ctx.accounts.governance_mint.key();
+    governance.governance_vault = ctx.accounts.governance_vault.key();
+    governance.total_staked = 0;
+    governance.proposal_count = 0;
+    governance.min_proposal_stake = min_proposal_stake;
+    governance.quorum_votes = quorum_votes;
+    governance.voting_period_secs = voting_period_secs;
+    governance.bump = ctx.bumps.governance;
+    governance.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn stake_governance(
+    ctx: Context<StakeGovernance>,
+    amount: u64,
+) -> Result<()> {
+    let stake = &mut ctx.accounts.governance_stake;
+    let governance = &mut ctx.accounts.governance;
+
+    if stake.owner == Pubkey::default() {
+        stake.owner = ctx.accounts.owner.key();
+        stake.locked_until = 0;
+        stake.bump = ctx.bumps.governance_stake;
+        stake.version = ACCOUNT_VERSION;
+    }
+    stake.amount = stake.amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    stake.staked_at = Clock::get()?.unix_timestamp;
+    governance.total_staked = governance.total_staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.owner_token.to_account_info(),
+        mint: ctx.accounts.governance_mint.to_account_info(),
+        to: ctx.accounts.governance_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.governance_mint.decimals)?;
+
+    Ok(())
+}
+
+pub fn unstake_governance(
+    ctx: Context<UnstakeGovernance>,
+    amount: u64,
+) -> Result<()> {
+    let stake = &mut ctx.accounts.governance_stake;
+    let governance = &mut ctx.accounts.governance;
+
+    // Stake stays locked until the votes it was counted in have closed, so the same
+    // tokens can't be moved to another wallet and vote again, and until the policies
+    // it discounted have ended
+    require!(Clock::get()?.unix_timestamp >= stake.locked_until, ErrorCode::GovernanceStakeLocked);
+    require!(stake.amount >= amount, ErrorCode::InsufficientGovernanceStake);
+
+    stake.amount = stake.amount.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    governance.total_staked = governance.total_staked.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"governance".as_ref(),
+        &[governance.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.governance_vault.to_account_info(),
+        mint: ctx.accounts.governance_mint.to_account_info(),
+        to: ctx.accounts.owner_token.to_account_info(),
+        authority: governance.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.governance_mint.decimals)?;
+
+    Ok(())
+}
+
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    action: ProposalAction,
+) -> Result<()> {
+    let governance = &mut ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.governance_stake.amount >= governance.min_proposal_stake,
+        ErrorCode::InsufficientGovernanceStake
+    );
+    validate_action(&action)?;
+
+    proposal.id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    proposal.votes_for = 0;
+    proposal.votes_against = 0;
+    proposal.created_at = clock.unix_timestamp;
+    proposal.voting_ends_at = clock.unix_timestamp.checked_add(governance.voting_period_secs).ok_or(ErrorCode::MathOverflow)?;
+    proposal.is_executed = false;
+    proposal.bump = ctx.bumps.proposal;
+    proposal.version = ACCOUNT_VERSION;
+
+    governance.proposal_count = governance.proposal_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        id: proposal.id,
+        proposer: proposal.proposer,
+        voting_ends_at: proposal.voting_ends_at,
+    });
+
+    Ok(())
+}
+
+// Actions are checked up front so a passed proposal can't fail on execution
+fn validate_action(action: &ProposalAction) -> Result<()> {
+    match action {
+        ProposalAction::SetPoolRiskProfile { risk_weights, max_risk_score, .. } => {
+            require!(risk_weights.is_valid(), ErrorCode::InvalidRiskWeights);
+            require!(*max_risk_score <= 100, ErrorCode::InvalidRiskScore);
+        }
+        ProposalAction::SetProtocolFee { fee_bps } => {
+            require!(*fee_bps <= crate::fees::MAX_PROTOCOL_FEE_BPS, ErrorCode::ProtocolFeeTooHigh);
+        }
+        ProposalAction::SetRiskCommittee { members, .. } => {
+            require!(members.len() <= MAX_COMMITTEE_MEMBERS, ErrorCode::InvalidCommitteeSize);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn vote(
+    ctx: Context<Vote>,
+    in_favor: bool,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let stake = &mut ctx.accounts.governance_stake;
+    let vote_record = &mut ctx.accounts.vote_record;
+
+    require!(Clock::get()?.unix_timestamp < proposal.voting_ends_at, ErrorCode::VotingClosed);
+    require!(stake.amount > 0, ErrorCode::InsufficientGovernanceStake);
+
+    let weight = stake.amount;
+    if in_favor {
+        proposal.votes_for = proposal.votes_for.checked_add(weight).ok_or(ErrorCode::MathOverflow)?;
+    } else {
+        proposal.votes_against = proposal.votes_against.checked_add(weight).ok_or(ErrorCode::MathOverflow)?;
+    }
+    stake.locked_until = std::cmp::max(stake.locked_until, proposal.voting_ends_at);
+
+    vote_record.proposal = proposal.key();
+    vote_record.voter = ctx.accounts.voter.key();
+    vote_record.weight = weight;
+    vote_record.in_favor = in_favor;
+    vote_record.bump = ctx.bumps.vote_record;
+    vote_record.version = ACCOUNT_VERSION;
+
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        voter: vote_record.voter,
+        weight,
+        in_favor,
+    });
+
+    Ok(())
+}
+
+// Anyone can execute a proposal that passed once voting has closed
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(!proposal.is_executed, ErrorCode::ProposalAlreadyExecuted);
+    require!(Clock::get()?.unix_timestamp >= proposal.voting_ends_at, ErrorCode::VotingNotClosed);
+    require!(proposal.has_passed(ctx.accounts.governance.quorum_votes), ErrorCode::ProposalNotPassed);
+
+    match &proposal.action {
+        ProposalAction::SetPoolRiskProfile { pool_type, risk_weights, max_risk_score } => {
+            let capital_pool = ctx.accounts.capital_pool.as_mut().ok_or(ErrorCode::ProposalAccountMissing)?;
+            require!(capital_pool.pool_type == *pool_type, ErrorCode::ProposalAccountMissing);
+            capital_pool.risk_weights = *risk_weights;
+            capital_pool.max_risk_score = *max_risk_score;
+        }
+        ProposalAction::SetProtocolFee { fee_bps } => {
+            // Goes through the same timelock as an admin fee change
+            schedule_protocol_fee(&mut ctx.accounts.protocol_state, *fee_bps)?;
+        }
+        ProposalAction::SetRiskCommittee { members, quorum } => {
+            let risk_committee = ctx.accounts.risk_committee.as_mut().ok_or(ErrorCode::ProposalAccountMissing)?;
+            set_members(risk_committee, members, *quorum)?;
+            risk_committee.epoch = risk_committee.epoch.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+
+    proposal.is_executed = true;
+
+    emit!(ProposalExecuted {
+        proposal: proposal.key(),
+        id: proposal.id,
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Governance::SIZE,
+        seeds = [b"governance"],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    pub governance_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = governance_vault.mint == governance_mint.key(),
+        constraint = governance_vault.owner == governance.key()
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeGovernance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = GovernanceStake::SIZE,
+        seeds = [b"governance-stake", owner.key().as_ref()],
+        bump
+    )]
+    pub governance_stake: Account<'info, GovernanceStake>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        constraint = owner_token.mint == governance.governance_mint,
+        constraint = owner_token.owner == owner.key()
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = governance_vault.key() == governance.governance_vault
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+
+    #[account(address = governance.governance_mint)]
+    pub governance_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeGovernance<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"governance-stake", owner.key().as_ref()],
+        bump = governance_stake.bump,
+        constraint = governance_stake.owner == owner.key()
+    )]
+    pub governance_stake: Account<'info, GovernanceStake>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        constraint = owner_token.mint == governance.governance_mint,
+        constraint = owner_token.owner == owner.key()
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = governance_vault.key() == governance.governance_vault
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+
+    #[account(address = governance.governance_mint)]
+    pub governance_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        seeds = [b"governance-stake", proposer.key().as_ref()],
+        bump = governance_stake.bump
+    )]
+    pub governance_stake: Account<'info, GovernanceStake>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::SIZE,
+        seeds = [b"proposal", &governance.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"governance-stake", voter.key().as_ref()],
+        bump = governance_stake.bump
+    )]
+    pub governance_stake: Account<'info, GovernanceStake>,
+
+    // Can only be created once per voter and proposal
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::SIZE,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    // Accounts changed by the proposal's action, when it targets one
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Option<Account<'info, CapitalPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"risk-committee"],
+        bump = risk_committee.bump
+    )]
+    pub risk_committee: Option<Account<'info, RiskCommittee>>,
+}
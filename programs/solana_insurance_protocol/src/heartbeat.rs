@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::alert_subscriptions::{notify_subscribers, AlertNotification};
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::exploit_detection::{raise_system_alert, AlertSeverity, AnomalyType, SystemAlert};
+use crate::session_keys::{authorize_with_session, SessionKey, SESSION_PERMISSION_SUBMIT_HEARTBEAT};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ErrorCode};
+
+#[account]
+pub struct HeartbeatMonitor {
+    pub protocol: Pubkey,
+    pub keeper: Pubkey,           // May submit heartbeats alongside the protocol authority
+    pub max_missed_epochs: u64,
+    pub last_heartbeat_epoch: u64,
+    pub last_heartbeat_at: i64,
+    pub last_alert_epoch: u64,    // Epoch of the last missed-heartbeat alert
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl HeartbeatMonitor {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // keeper
+                           8 +      // max_missed_epochs
+                           8 +      // last_heartbeat_epoch
+                           8 +      // last_heartbeat_at
+                           8 +      // last_alert_epoch
+                           1 +      // bump
+                           1;       // version
+}
+
+pub fn configure_heartbeat(
+    ctx: Context<ConfigureHeartbeat>,
+    keeper: Pubkey,
+    max_missed_epochs: u64,
+) -> Result<()> {
+    require!(max_missed_epochs > 0, ErrorCode::InvalidHeartbeatConfig);
+    
+    let heartbeat_monitor = &mut ctx.accounts.heartbeat_monitor;
+    let clock = Clock::get()?;
+    
+    // Configuring counts as a heartbeat so the monitor doesn't start out lapsed
+    if heartbeat_monitor.protocol == Pubkey::default() {
+        heartbeat_monitor.protocol = ctx.accounts.protocol_info.key();
+        heartbeat_monitor.last_heartbeat_epoch = clock.epoch;
+        heartbeat_monitor.last_heartbeat_at = clock.unix_timestamp;
+        heartbeat_monitor.last_alert_epoch = 0;
+        heartbeat_monitor.bump = ctx.bumps.heartbeat_monitor;
+        heartbeat_monitor.version = ACCOUNT_VERSION;
+    }
+    heartbeat_monitor.keeper = keeper;
+    heartbeat_monitor.max_missed_epochs = max_missed_epochs;
+    
+    Ok(())
+}
+
+pub fn submit_heartbeat(ctx: Context<SubmitHeartbeat>) -> Result<()> {
+    let heartbeat_monitor = &mut ctx.accounts.heartbeat_monitor;
+    let clock = Clock::get()?;
+    
+    authorize_with_session(
+        ctx.accounts.submitter.key(),
+        &[ctx.accounts.protocol_info.authority, heartbeat_monitor.keeper],
+        &ctx.accounts.session_key,
+        SESSION_PERMISSION_SUBMIT_HEARTBEAT,
+    )?;
+    
+    heartbeat_monitor.last_heartbeat_epoch = clock.epoch;
+    heartbeat_monitor.last_heartbeat_at = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+// Raises a medium-severity alert once per lapse when heartbeats stop
+pub fn check_heartbeat(ctx: Context<CheckHeartbeat>) -> Result<()> {
+    let heartbeat_monitor = &mut ctx.accounts.heartbeat_monitor;
+    let clock = Clock::get()?;
+    
+    let missed_epochs = clock.epoch.saturating_sub(heartbeat_monitor.last_heartbeat_epoch);
+    require!(
+        missed_epochs > heartbeat_monitor.max_missed_epochs,
+        ErrorCode::HeartbeatNotMissed
+    );
+    require!(
+        heartbeat_monitor.last_alert_epoch <= heartbeat_monitor.last_heartbeat_epoch,
+        ErrorCode::HeartbeatAlertAlreadyRaised
+    );
+    
+    raise_system_alert(
+        &ctx.accounts.payer,
+        &ctx.accounts.exploit_alert,
+        &mut ctx.accounts.protocol_info,
+        &ctx.accounts.protocol_state,
+        &ctx.accounts.system_program,
+        ctx.bumps.exploit_alert,
+        SystemAlert {
+            anomaly_type: AnomalyType::MissedHeartbeat,
+            severity: AlertSeverity::Medium,
+            details: format!("No heartbeat for {} epochs", missed_epochs),
+        },
+    )?;
+    ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+    update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+        directory.record(&ctx.accounts.protocol_info)
+    })?;
+    notify_subscribers(
+        ctx.remaining_accounts,
+        ctx.accounts.protocol_info.key(),
+        ctx.accounts.exploit_alert.key(),
+        AlertNotification::Activated,
+    )?;
+    heartbeat_monitor.last_alert_epoch = clock.epoch;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureHeartbeat<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = HeartbeatMonitor::SIZE,
+        seeds = [b"heartbeat", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub heartbeat_monitor: Account<'info, HeartbeatMonitor>,
+    
+    #[account(
+        constraint = protocol_info.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitHeartbeat<'info> {
+    pub submitter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"heartbeat", protocol_info.key().as_ref()],
+        bump = heartbeat_monitor.bump
+    )]
+    pub heartbeat_monitor: Account<'info, HeartbeatMonitor>,
+    
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    // Only needed when the submitter is a delegate of the authority or keeper
+    #[account(
+        seeds = [b"session-key", session_key.owner.as_ref(), submitter.key().as_ref()],
+        bump = session_key.bump
+    )]
+    pub session_key: Option<Account<'info, SessionKey>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckHeartbeat<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"heartbeat", protocol_info.key().as_ref()],
+        bump = heartbeat_monitor.bump
+    )]
+    pub heartbeat_monitor: Account<'info, HeartbeatMonitor>,
+    
+    /// CHECK: created at this PDA by the check when it raises an alert
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        bump
+    )]
+    pub exploit_alert: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::{ProtocolInfo, ErrorCode};
+
+// Where a protocol is in incident response. Alert and claim instructions move it
+// along and only the transitions below are allowed:
+//
+//   Normal/Resolved -> Alerted    an alert is raised
+//   Alerted -> Normal             its last open alert is dismissed or expires
+//   Alerted -> Confirmed          an alert is confirmed
+//   Confirmed -> Settling         the first claim is filed against the incident
+//   Confirmed/Settling -> Resolved  the protocol is reactivated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IncidentState {
+    Normal,
+    Alerted,
+    Confirmed,
+    Settling,
+    Resolved,
+}
+
+impl IncidentState {
+    pub fn can_transition_to(self, next: IncidentState) -> bool {
+        use IncidentState::*;
+        matches!(
+            (self, next),
+            (Normal | Resolved, Alerted) |
+            (Alerted, Normal) |
+            (Alerted, Confirmed) |
+            (Confirmed, Settling) |
+            (Confirmed | Settling, Resolved)
+        )
+    }
+
+    // A confirmed incident that hasn't been closed out yet
+    pub fn is_incident(self) -> bool {
+        matches!(self, IncidentState::Confirmed | IncidentState::Settling)
+    }
+}
+
+#[event]
+pub struct IncidentStateChanged {
+    pub protocol: Pubkey,
+    pub previous_state: IncidentState,
+    pub state: IncidentState,
+}
+
+pub fn transition_incident(
+    protocol_info: &mut Account<ProtocolInfo>,
+    state: IncidentState,
+) -> Result<()> {
+    let previous_state = protocol_info.incident_state;
+    require!(previous_state.can_transition_to(state), ErrorCode::InvalidIncidentTransition);
+    protocol_info.incident_state = state;
+
+    emit!(IncidentStateChanged {
+        protocol: protocol_info.key(),
+        previous_state,
+        state,
+    });
+
+    Ok(())
+}
+
+// Alerts raised during an incident are tracked but don't change the state
+pub fn record_alert_raised(protocol_info: &mut Account<ProtocolInfo>) -> Result<()> {
+    protocol_info.open_alerts = protocol_info.open_alerts.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    if matches!(protocol_info.incident_state, IncidentState::Normal | IncidentState::Resolved) {
+        transition_incident(protocol_info, IncidentState::Alerted)?;
+    }
+
+    Ok(())
+}
+
+// For alerts resolved as false or left to expire
+pub fn record_alert_dismissed(protocol_info: &mut Account<ProtocolInfo>) -> Result<()> {
+    protocol_info.open_alerts = protocol_info.open_alerts.saturating_sub(1);
+    if protocol_info.incident_state == IncidentState::Alerted && protocol_info.open_alerts == 0 {
+        transition_incident(protocol_info, IncidentState::Normal)?;
+    }
+
+    Ok(())
+}
+
+// Marks when the incident began, so claims can be limited to policies bought before it
+pub fn record_alert_confirmed(
+    protocol_info: &mut Account<ProtocolInfo>,
+    alert_time: i64,
+) -> Result<()> {
+    protocol_info.open_alerts = protocol_info.open_alerts.saturating_sub(1);
+    if protocol_info.incident_state.is_incident() {
+        return Ok(());
+    }
+
+    // Alerts raised before incident tracking was added never moved the protocol to Alerted
+    if protocol_info.incident_state != IncidentState::Alerted {
+        transition_incident(protocol_info, IncidentState::Alerted)?;
+    }
+    transition_incident(protocol_info, IncidentState::Confirmed)?;
+    protocol_info.incident_started_at = alert_time;
+
+    Ok(())
+}
+
+pub fn record_incident_claim(protocol_info: &mut Account<ProtocolInfo>) -> Result<()> {
+    if protocol_info.incident_state == IncidentState::Confirmed {
+        transition_incident(protocol_info, IncidentState::Settling)?;
+    }
+
+    Ok(())
+}
+
+pub fn resolve_incident(protocol_info: &mut Account<ProtocolInfo>) -> Result<()> {
+    transition_incident(protocol_info, IncidentState::Resolved)?;
+    protocol_info.incident_started_at = 0;
+
+    Ok(())
+}
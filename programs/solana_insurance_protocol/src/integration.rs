@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use crate::capital_management::CapitalPoolType;
+use crate::governance::apply_staker_discount;
+use crate::risk_assessment::{
+    apply_premium_surcharge, apply_safeguard_discounts, calculate_premium_amount, calculate_premium_rate,
+};
+use crate::{ProtocolInfo, ProtocolState};
+
+// Helpers for programs that buy coverage, file claims or provide capital through CPI.
+// Depending on this crate with the `cpi` feature exposes `cpi::create_policy`,
+// `cpi::submit_claim` and `cpi::provide_capital` with their `cpi::accounts` structs;
+// the functions below derive the addresses those structs need and price a policy
+// exactly like create_policy does. A program buying coverage for one of its PDAs
+// signs with `CpiContext::new_with_signer`, and the PDA pays the policy's rent.
+
+pub fn protocol_state_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"protocol-state"], &crate::ID)
+}
+
+pub fn config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &crate::ID)
+}
+
+pub fn global_stats_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global-stats"], &crate::ID)
+}
+
+// Seeded by the authority the protocol registered under, which update_protocol_info
+// never changes. After an authority rotation the current authority derives a different
+// address; read the protocol's ProtocolEntry for it instead.
+pub fn protocol_info_address(registering_authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"protocol-info", registering_authority.as_ref()], &crate::ID)
+}
+
+pub fn protocol_entry_address(registry_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"protocol-entry", &registry_index.to_le_bytes()], &crate::ID)
+}
+
+pub fn capital_pool_address(pool_type: CapitalPoolType) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"capital-pool", &[pool_type as u8]], &crate::ID)
+}
+
+pub fn capital_provider_address(owner: &Pubkey, capital_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"capital-provider", owner.as_ref(), capital_pool.as_ref()], &crate::ID)
+}
+
+pub fn policy_counter_address(insured: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"policy-counter", insured.as_ref()], &crate::ID)
+}
+
+// `index` is the policy counter's next_index before the purchase, 0 for a first one
+pub fn policy_address(insured: &Pubkey, protocol_info: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"policy", insured.as_ref(), protocol_info.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+// `index` is the policy's claim_count before the claim is filed, 0 for a first one
+pub fn claim_address(policy: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claim", policy.as_ref(), &index.to_le_bytes()], &crate::ID)
+}
+
+// Where a policy's single claim was filed before claims were indexed
+pub fn legacy_claim_address(policy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claim", policy.as_ref()], &crate::ID)
+}
+
+pub fn treasury_vault_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury-vault", mint.as_ref()], &crate::ID)
+}
+
+pub fn mint_registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mint-registry"], &crate::ID)
+}
+
+pub fn governance_stake_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"governance-stake", owner.as_ref()], &crate::ID)
+}
+
+// Annual rate for the protocol's risk, net of safeguard and staker discounts and
+// including any surcharge from open exploit alerts
+pub fn premium_rate_bps(
+    protocol_info: &ProtocolInfo,
+    protocol_state: &ProtocolState,
+    staker_discount_bps: u64,
+) -> Result<u64> {
+    let premium_rate_bps = apply_safeguard_discounts(
+        calculate_premium_rate(protocol_info.risk_score, &protocol_state.premium_curve)?,
+        protocol_info.safeguard_flags,
+        &protocol_state.safeguard_discount_bps,
+    )?;
+    let premium_rate_bps = apply_premium_surcharge(premium_rate_bps, protocol_info.alert_surcharge_bps)?;
+    apply_staker_discount(premium_rate_bps, staker_discount_bps)
+}
+
+// Smallest premium create_policy accepts for the coverage
+pub fn required_premium(
+    protocol_info: &ProtocolInfo,
+    protocol_state: &ProtocolState,
+    staker_discount_bps: u64,
+    coverage_amount: u64,
+    duration_days: u16,
+) -> Result<u64> {
+    calculate_premium_amount(
+        coverage_amount,
+        premium_rate_bps(protocol_info, protocol_state, staker_discount_bps)?,
+        duration_days,
+    )
+}
@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::capital_management::CapitalPool;
+use crate::global_stats::GlobalStats;
+use crate::{ProtocolInfo, ProtocolRegistry, ErrorCode};
+
+// Accounting a capital pool must always satisfy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolInvariant {
+    TokenBalance, // The pool holds at least its available and reserved capital
+    Capital,      // Available and reserved capital don't exceed total capital
+    Coverage,     // The pool's coverage is counted in the global active coverage
+}
+
+#[event]
+pub struct PoolInvariantViolated {
+    pub capital_pool: Pubkey,
+    pub invariant: PoolInvariant,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+#[event]
+pub struct PoolBalanceSynced {
+    pub capital_pool: Pubkey,
+    pub balance: u64,
+    pub surplus: u64,   // Donations and dust added to available capital
+    pub shortfall: u64, // Written off available capital
+    pub available_capital: u64,
+}
+
+#[event]
+pub struct RegistryInvariantViolated {
+    pub registered_count: u64,
+    pub active_count: u64,
+    pub next_index: u64,
+}
+
+#[event]
+pub struct RegistryCountsReconciled {
+    pub protocol: Pubkey,
+    pub registered_count: u64,
+    pub active_count: u64,
+}
+
+fn check(capital_pool: Pubkey, invariant: PoolInvariant, holds: bool, expected: u64, actual: u64) -> Result<()> {
+    if !holds {
+        // Logs survive the failed transaction, so monitoring still sees the event
+        emit!(PoolInvariantViolated {
+            capital_pool,
+            invariant,
+            expected,
+            actual,
+        });
+        return err!(ErrorCode::PoolInvariantViolated);
+    }
+    Ok(())
+}
+
+// Permissionless tripwire against accounting bugs. Paid claims leave total capital
+// as it was, so what the pool must still hold is its available and reserved capital.
+pub fn assert_invariants(ctx: Context<AssertInvariants>) -> Result<()> {
+    let pool = &ctx.accounts.capital_pool;
+    let key = pool.key();
+    let held = pool.available_capital.saturating_add(pool.reserved_capital);
+    let balance = ctx.accounts.pool_token_account.amount;
+
+    check(key, PoolInvariant::TokenBalance, balance >= held, held, balance)?;
+    check(key, PoolInvariant::Capital, held <= pool.total_capital, pool.total_capital, held)?;
+    let mint_coverage = ctx.accounts.global_stats
+        .mint_totals(&pool.token_mint)
+        .map_or(0, |totals| totals.active_coverage);
+    check(
+        key,
+        PoolInvariant::Coverage,
+        pool.active_coverage <= mint_coverage,
+        mint_coverage,
+        pool.active_coverage,
+    )
+}
+
+// Permissionless. Brings the pool's accounting back in line with what its vault
+// actually holds. Tokens sent straight to the vault (donations, fee dust) become
+// available capital backing new coverage; a shortfall is written off available
+// capital so withdrawals stop promising tokens that aren't there. A shortfall eating
+// into reserved payouts can't be absorbed and fails the tripwire instead.
+pub fn sync_pool_balance(ctx: Context<SyncPoolBalance>) -> Result<()> {
+    let pool = &mut ctx.accounts.capital_pool;
+    let key = pool.key();
+    let balance = ctx.accounts.pool_token_account.amount;
+    let held = pool.available_capital.checked_add(pool.reserved_capital).ok_or(ErrorCode::MathOverflow)?;
+
+    let surplus = balance.saturating_sub(held);
+    let shortfall = held.saturating_sub(balance);
+    if surplus == 0 && shortfall == 0 {
+        return Ok(());
+    }
+    check(key, PoolInvariant::TokenBalance, shortfall <= pool.available_capital, held, balance)?;
+
+    if surplus > 0 {
+        pool.available_capital = pool.available_capital.checked_add(surplus).ok_or(ErrorCode::MathOverflow)?;
+        pool.total_capital = pool.total_capital.checked_add(surplus).ok_or(ErrorCode::MathOverflow)?;
+        ctx.accounts.global_stats.add_pool_capital(pool.pool_type, surplus)?;
+    } else {
+        pool.available_capital = pool.available_capital.checked_sub(shortfall).ok_or(ErrorCode::MathOverflow)?;
+        pool.total_capital = pool.total_capital.saturating_sub(shortfall);
+        ctx.accounts.global_stats.remove_pool_capital(pool.pool_type, shortfall);
+    }
+
+    emit!(PoolBalanceSynced {
+        capital_pool: key,
+        balance,
+        surplus,
+        shortfall,
+        available_capital: pool.available_capital,
+    });
+
+    Ok(())
+}
+
+// Active protocols are a subset of the registered ones, which are a subset of
+// every index ever handed out
+pub fn assert_registry_invariants(ctx: Context<AssertRegistryInvariants>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    if registry.active_count > registry.registered_count || registry.registered_count > registry.next_index {
+        emit!(RegistryInvariantViolated {
+            registered_count: registry.registered_count,
+            active_count: registry.active_count,
+            next_index: registry.next_index,
+        });
+        return err!(ErrorCode::RegistryInvariantViolated);
+    }
+    Ok(())
+}
+
+// Permissionless backfill of active_count for protocols registered before the count
+// was kept, and for ones suspended automatically before those paths synced it
+pub fn reconcile_registry_counts(ctx: Context<ReconcileRegistryCounts>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    if protocol_info.counted_active == protocol_info.is_active {
+        return Ok(());
+    }
+    registry.sync_active(protocol_info)?;
+
+    emit!(RegistryCountsReconciled {
+        protocol: protocol_info.key(),
+        registered_count: registry.registered_count,
+        active_count: registry.active_count,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AssertInvariants<'info> {
+    #[account(
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    #[account(address = capital_pool.token_account)]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPoolBalance<'info> {
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+
+    #[account(address = capital_pool.token_account @ ErrorCode::InvalidPoolTokenAccount)]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct AssertRegistryInvariants<'info> {
+    #[account(
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileRegistryCounts<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+}
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolState, ErrorCode};
+
+// Time-driven instructions (expiring policies and alerts, closing stale policies and
+// claims) are permissionless cranks. Whoever runs one can claim a small reward from
+// the protocol fees, so keeper networks or independent bots have a reason to.
+#[account]
+pub struct KeeperConfig {
+    pub reward_per_crank: u64, // In the fee vault's mint; 0 turns rewards off
+    pub cranks_rewarded: u64,
+    pub total_rewards_paid: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl KeeperConfig {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // reward_per_crank
+                           8 +      // cranks_rewarded
+                           8 +      // total_rewards_paid
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct KeeperRewardUpdated {
+    pub previous_reward: u64,
+    pub reward_per_crank: u64,
+}
+
+#[event]
+pub struct KeeperRewarded {
+    pub keeper_token: Pubkey,
+    pub amount: u64,
+}
+
+// Accounts a crank takes to pay its caller. Leaving out the keeper config runs the
+// crank without a reward.
+#[derive(Accounts)]
+pub struct KeeperReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"keeper-config"],
+        bump = keeper_config.bump
+    )]
+    pub keeper_config: Option<Account<'info, KeeperConfig>>,
+
+    #[account(mut)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub keeper_token: Option<Account<'info, TokenAccount>>,
+
+    pub fee_mint: Option<Account<'info, Mint>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+// Pays the reward out of fees not yet withdrawn, or whatever is left of them
+pub fn pay_keeper_reward<'info>(
+    reward: &mut KeeperReward<'info>,
+    protocol_state: &mut Account<'info, ProtocolState>,
+) -> Result<()> {
+    let Some(keeper_config) = reward.keeper_config.as_mut() else {
+        return Ok(());
+    };
+    let fee_vault = reward.fee_vault.as_ref().ok_or(ErrorCode::KeeperAccountMissing)?;
+    let keeper_token = reward.keeper_token.as_ref().ok_or(ErrorCode::KeeperAccountMissing)?;
+    let fee_mint = reward.fee_mint.as_ref().ok_or(ErrorCode::KeeperAccountMissing)?;
+    let token_program = reward.token_program.as_ref().ok_or(ErrorCode::KeeperAccountMissing)?;
+
+    require_keys_eq!(fee_vault.key(), protocol_state.fee_vault, ErrorCode::InvalidFeeVault);
+    require_keys_eq!(fee_mint.key(), fee_vault.mint, ErrorCode::InvalidFeeVault);
+    require_keys_eq!(keeper_token.mint, fee_vault.mint, ErrorCode::InvalidFeeVault);
+
+    let withdrawable = protocol_state.fees_collected.saturating_sub(protocol_state.fees_withdrawn);
+    let amount = std::cmp::min(
+        keeper_config.reward_per_crank,
+        std::cmp::min(withdrawable, fee_vault.amount)
+    );
+    if amount == 0 {
+        return Ok(());
+    }
+    protocol_state.fees_withdrawn = protocol_state.fees_withdrawn.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"protocol-state".as_ref(),
+        &[protocol_state.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: fee_vault.to_account_info(),
+        mint: fee_mint.to_account_info(),
+        to: keeper_token.to_account_info(),
+        authority: protocol_state.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, fee_mint.decimals)?;
+
+    keeper_config.cranks_rewarded = keeper_config.cranks_rewarded.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    keeper_config.total_rewards_paid = keeper_config.total_rewards_paid.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(KeeperRewarded {
+        keeper_token: keeper_token.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+// Takes effect immediately: rewards are paid out of protocol fees the admin can already
+// withdraw, never out of premiums held for policies or LP capital
+pub fn set_keeper_reward(ctx: Context<SetKeeperReward>, reward_per_crank: u64) -> Result<()> {
+    let keeper_config = &mut ctx.accounts.keeper_config;
+    let previous_reward = keeper_config.reward_per_crank;
+    if keeper_config.version == 0 {
+        keeper_config.cranks_rewarded = 0;
+        keeper_config.total_rewards_paid = 0;
+        keeper_config.bump = ctx.bumps.keeper_config;
+        keeper_config.version = ACCOUNT_VERSION;
+    }
+    keeper_config.reward_per_crank = reward_per_crank;
+
+    emit!(KeeperRewardUpdated {
+        previous_reward,
+        reward_per_crank,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetKeeperReward<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = KeeperConfig::SIZE,
+        seeds = [b"keeper-config"],
+        bump
+    )]
+    pub keeper_config: Account<'info, KeeperConfig>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
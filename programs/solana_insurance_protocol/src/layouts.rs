@@ -0,0 +1,41 @@
+use anchor_lang::Discriminator;
+use solana_insurance_protocol_types::{self as types, ProgramAccount};
+use crate::capital_management::{CapitalPool, CapitalProvider};
+use crate::claims::Claim;
+use crate::governance::GovernanceStake;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::treasury::TreasuryVault;
+use crate::Policy;
+
+// Keeps the solana-insurance-protocol-types crate in step with the accounts here: the
+// build fails if an account's size or discriminator, or the layout version, drifts
+// from its off-chain copy.
+
+const fn same_discriminator(a: [u8; 8], b: [u8; 8]) -> bool {
+    let mut i = 0;
+    while i < 8 {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+macro_rules! check_layout {
+    ($account:ty, $copy:ty) => {
+        const _: () = assert!(
+            <$account>::SIZE == <$copy as ProgramAccount>::SIZE &&
+            same_discriminator(<$account as Discriminator>::DISCRIMINATOR, <$copy as ProgramAccount>::DISCRIMINATOR)
+        );
+    };
+}
+
+const _: () = assert!(ACCOUNT_VERSION == types::ACCOUNT_VERSION);
+
+check_layout!(Policy, types::Policy);
+check_layout!(Claim, types::Claim);
+check_layout!(CapitalPool, types::CapitalPool);
+check_layout!(CapitalProvider, types::CapitalProvider);
+check_layout!(TreasuryVault, types::TreasuryVault);
+check_layout!(GovernanceStake, types::GovernanceStake);
@@ -1,20 +1,118 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::memo::Memo;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
 
-mod risk_assessment;
-mod capital_management;
-mod claims;
-mod exploit_detection;
+pub mod risk_assessment;
+pub mod capital_management;
+pub mod claims;
+pub mod exploit_detection;
+pub mod risk_attestation;
+pub mod audit_registry;
+pub mod risk_committee;
+pub mod safeguards;
+pub mod solvency;
+pub mod watchers;
+pub mod tvl_monitor;
+pub mod loss_events;
+pub mod heartbeat;
+pub mod oracle_deviation;
+pub mod circuit_breaker;
+pub mod postmortems;
+pub mod alert_subscriptions;
+pub mod bonded_alerts;
+pub mod fees;
+pub mod governance;
+pub mod timelock;
+pub mod authority;
+pub mod deregistration;
+pub mod treasury;
+pub mod emergency_council;
+pub mod config;
+pub mod migrations;
+pub mod global_stats;
+pub mod cleanup;
+pub mod allowlist;
+pub mod session_keys;
+pub mod incident;
+pub mod native_sol;
+pub mod integration;
+pub mod pyth;
+pub mod wormhole;
+pub mod swap;
+pub mod lst_depeg;
+pub mod keeper;
+pub mod tvl_adapters;
+pub mod realms;
+pub mod receipts;
+pub mod snapshots;
+pub mod validator_cover;
+pub mod views;
+pub mod invariants;
+pub mod directory;
+pub mod oracle_guards;
+pub mod premium_escrow;
+pub mod mint_registry;
+pub mod math;
+mod layouts;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 use risk_assessment::*;
 use capital_management::*;
 use claims::*;
 use exploit_detection::*;
+use risk_attestation::*;
+use audit_registry::*;
+use risk_committee::*;
+use safeguards::*;
+use solvency::*;
+use watchers::*;
+use tvl_monitor::*;
+use loss_events::*;
+use heartbeat::*;
+use oracle_deviation::*;
+use circuit_breaker::*;
+use postmortems::*;
+use alert_subscriptions::*;
+use bonded_alerts::*;
+use fees::*;
+use governance::*;
+use timelock::*;
+use authority::*;
+use deregistration::*;
+use treasury::*;
+use emergency_council::*;
+use config::*;
+use migrations::*;
+use global_stats::*;
+use cleanup::*;
+use allowlist::*;
+use session_keys::*;
+use incident::*;
+use native_sol::*;
+use integration::*;
+use wormhole::*;
+use lst_depeg::*;
+use keeper::*;
+use tvl_adapters::*;
+use realms::*;
+use receipts::*;
+use snapshots::*;
+use validator_cover::*;
+use views::*;
+use invariants::*;
+use directory::*;
+use oracle_guards::*;
+use premium_escrow::*;
+use mint_registry::*;
 
 
 
 declare_id!("4LLgpV6Hu42KLg8W2GzdxjRxXmVoybSwb897WEdmXWQE"); 
 
+pub const MAX_PROTOCOL_NAME_LEN: usize = 32;
+pub const MAX_METADATA_URI_LEN: usize = 96;
+
 #[program]
 pub mod solana_insurance_protocol {
     use super::*;
@@ -22,12 +120,43 @@ pub mod solana_insurance_protocol {
     // === Core Insurance Functions ===
     
     pub fn initialize(ctx: Context<Initialize>, protocol_fee: u64) -> Result<()> {
+        require!(protocol_fee <= MAX_PROTOCOL_FEE_BPS, ErrorCode::ProtocolFeeTooHigh);
+        
         let protocol_state = &mut ctx.accounts.protocol_state;
         protocol_state.authority = ctx.accounts.authority.key();
         protocol_state.protocol_fee = protocol_fee;
+        protocol_state.risk_oracle = Pubkey::default();
+        protocol_state.max_insurable_risk_score = DEFAULT_MAX_INSURABLE_RISK_SCORE;
+        protocol_state.premium_curve = DEFAULT_PREMIUM_CURVE;
+        protocol_state.safeguard_discount_bps = [0; SAFEGUARD_COUNT];
+        protocol_state.alert_response = DEFAULT_ALERT_RESPONSE_CONFIG;
+        protocol_state.tvl_anomaly = DEFAULT_TVL_ANOMALY_CONFIG;
+        protocol_state.circuit_breaker = DEFAULT_CIRCUIT_BREAKER;
+        protocol_state.is_paused = false;
+        protocol_state.fee_vault = Pubkey::default();
+        protocol_state.fees_collected = 0;
+        protocol_state.fees_withdrawn = 0;
+        protocol_state.pending_protocol_fee = 0;
+        protocol_state.protocol_fee_effective_at = 0;
+        protocol_state.change_count = 0;
+        protocol_state.pending_authority = Pubkey::default();
+        protocol_state.authority_multisig = Pubkey::default();
         protocol_state.bump = ctx.bumps.protocol_state;        
+        protocol_state.version = ACCOUNT_VERSION;
+        protocol_state.claim_resolvers = [Pubkey::default(); MAX_CLAIM_RESOLVERS];
+        protocol_state.claim_resolver_count = 0;
         let registry = &mut ctx.accounts.registry;
-        registry.protocol_count = 0;
+        registry.registered_count = 0;
+        registry.active_count = 0;
+        registry.next_index = 0;
+        registry.version = ACCOUNT_VERSION;
+        registry.allowlist_enabled = false;
+        registry.directory = Pubkey::default();
+        
+        emit!(ProtocolInitialized {
+            authority: protocol_state.authority,
+            protocol_fee,
+        });
         
         Ok(())
     }
@@ -37,16 +166,167 @@ pub mod solana_insurance_protocol {
         protocol_name: String,
         tvl_usd: u64,
     ) -> Result<()> {
+        require!(protocol_name.len() <= MAX_PROTOCOL_NAME_LEN, ErrorCode::ProtocolNameTooLong);
+        require!(
+            !ctx.accounts.registry.allowlist_enabled || ctx.accounts.registration_approval.is_some(),
+            ErrorCode::RegistrationNotApproved
+        );
+        
         let protocol_info = &mut ctx.accounts.protocol_info;
         protocol_info.authority = ctx.accounts.authority.key();
         protocol_info.protocol_name = protocol_name;
+        protocol_info.metadata_uri = String::new();
         protocol_info.tvl_usd = tvl_usd;
         protocol_info.risk_score = 50; // Default medium risk score
+        protocol_info.set_risk_components(50, 50, 50);
         protocol_info.is_active = true;
+        protocol_info.last_risk_update = 0;
+        protocol_info.premiums_earned = 0;
+        protocol_info.claims_paid = 0;
+        protocol_info.verified_audit_count = 0;
+        protocol_info.last_audit_date = 0;
+        protocol_info.safeguard_flags = 0;
+        protocol_info.safeguard_assessor = Pubkey::default();
+        protocol_info.incident_started_at = 0;
+        protocol_info.alert_surcharge_bps = 0;
+        protocol_info.surcharging_alerts = 0;
+        protocol_info.alerts_raised = 0;
+        protocol_info.alerts_confirmed = 0;
+        protocol_info.alerts_false = 0;
+        protocol_info.oracle_dependency = false;
+        protocol_info.deactivation_reason = DeactivationReason::None;
+        protocol_info.deactivated_by = Pubkey::default();
+        protocol_info.active_policies = 0;
+        protocol_info.is_deregistering = false;
+        protocol_info.registry_index = ctx.accounts.registry.next_index;
         protocol_info.bump = ctx.bumps.protocol_info;        
+        protocol_info.version = ACCOUNT_VERSION;
+        protocol_info.fee_override_bps = None;
+        protocol_info.revenue_share_bps = 0;
+        protocol_info.revenue_share_account = Pubkey::default();
+        protocol_info.fees_generated = 0;
+        protocol_info.revenue_shared = 0;
+        protocol_info.incident_state = IncidentState::Normal;
+        protocol_info.open_alerts = 0;
+        protocol_info.counted_active = false;
+        protocol_info.pending_authority = Pubkey::default();
+        // Record the protocol under the next registry index so it can be enumerated
+        let protocol_entry = &mut ctx.accounts.protocol_entry;
+        protocol_entry.index = protocol_info.registry_index;
+        protocol_entry.protocol = protocol_info.key();
+        protocol_entry.authority = protocol_info.authority;
+        protocol_entry.registered_at = Clock::get()?.unix_timestamp;
+        protocol_entry.is_registered = true;
+        protocol_entry.bump = ctx.bumps.protocol_entry;
+        protocol_entry.version = ACCOUNT_VERSION;
+        
         // Update the registry
         let registry = &mut ctx.accounts.registry;
-        registry.protocol_count = registry.protocol_count.checked_add(1).unwrap();
+        registry.registered_count = registry.registered_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        registry.sync_active(protocol_info)?;
+        registry.next_index = registry.next_index.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        update_directory(registry, &ctx.accounts.directory, |directory| directory.record(protocol_info))?;
+        
+        emit!(ProtocolRegistered {
+            protocol: protocol_info.key(),
+            authority: protocol_info.authority,
+            registry_index: protocol_info.registry_index,
+            protocol_name: protocol_info.protocol_name.clone(),
+            tvl_usd,
+        });
+        
+        Ok(())
+    }
+
+    // While the allowlist is on, only protocols the admin approved can register
+    pub fn set_registration_allowlist(
+        ctx: Context<SetRegistrationAllowlist>,
+        enabled: bool,
+    ) -> Result<()> {
+        allowlist::set_registration_allowlist(ctx, enabled)
+    }
+    
+    pub fn approve_protocol_registration(
+        ctx: Context<ApproveProtocolRegistration>,
+        authority: Pubkey,
+    ) -> Result<()> {
+        allowlist::approve_protocol_registration(ctx, authority)
+    }
+    
+    pub fn revoke_registration_approval(ctx: Context<RevokeRegistrationApproval>) -> Result<()> {
+        allowlist::revoke_registration_approval(ctx)
+    }
+    
+    // Lists every covered protocol in one zero-copy account the UI can read at once
+    pub fn initialize_protocol_directory(ctx: Context<InitializeProtocolDirectory>) -> Result<()> {
+        directory::initialize_protocol_directory(ctx)
+    }
+    
+    pub fn sync_directory_entry(ctx: Context<SyncDirectoryEntry>) -> Result<()> {
+        directory::sync_directory_entry(ctx)
+    }
+    
+    // A new authority only proposes the rotation, which the new key has to accept with
+    // accept_protocol_authority. The protocol info address stays derived from the
+    // original authority after a rotation.
+    pub fn update_protocol_info(
+        ctx: Context<UpdateProtocolInfo>,
+        protocol_name: Option<String>,
+        new_authority: Option<Pubkey>,
+        metadata_uri: Option<String>,
+    ) -> Result<()> {
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        
+        if let Some(protocol_name) = protocol_name {
+            require!(protocol_name.len() <= MAX_PROTOCOL_NAME_LEN, ErrorCode::ProtocolNameTooLong);
+            protocol_info.protocol_name = protocol_name;
+        }
+        if let Some(metadata_uri) = metadata_uri {
+            require!(metadata_uri.len() <= MAX_METADATA_URI_LEN, ErrorCode::MetadataUriTooLong);
+            protocol_info.metadata_uri = metadata_uri;
+        }
+        if let Some(new_authority) = new_authority {
+            require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+            protocol_info.pending_authority = new_authority;
+            
+            emit!(ProtocolAuthorityTransferProposed {
+                protocol: protocol_info.key(),
+                current_authority: protocol_info.authority,
+                pending_authority: new_authority,
+            });
+        }
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        emit!(ProtocolInfoUpdated {
+            protocol: protocol_info.key(),
+            authority: protocol_info.authority,
+            protocol_name: protocol_info.protocol_name.clone(),
+            metadata_uri: protocol_info.metadata_uri.clone(),
+        });
+        
+        Ok(())
+    }
+    
+    // Second step of a protocol authority rotation, signed by the proposed key
+    pub fn accept_protocol_authority(ctx: Context<AcceptProtocolAuthority>) -> Result<()> {
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        let new_authority = ctx.accounts.new_authority.key();
+        
+        require!(protocol_info.pending_authority != Pubkey::default(), ErrorCode::NoPendingAuthority);
+        require!(protocol_info.pending_authority == new_authority, ErrorCode::UnauthorizedAccess);
+        
+        let previous_authority = protocol_info.authority;
+        protocol_info.authority = new_authority;
+        protocol_info.pending_authority = Pubkey::default();
+        ctx.accounts.protocol_entry.authority = new_authority;
+        
+        emit!(ProtocolAuthorityTransferred {
+            protocol: protocol_info.key(),
+            previous_authority,
+            new_authority,
+        });
         
         Ok(())
     }
@@ -58,32 +338,271 @@ pub mod solana_insurance_protocol {
         duration_days: u16,
     ) -> Result<()> {
         let policy = &mut ctx.accounts.policy;
-        let _protocol_info = &ctx.accounts.protocol_info;  // Underscore prefix
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        let protocol_state = &mut ctx.accounts.protocol_state;
+        let config = &ctx.accounts.config.params;
         let clock = Clock::get()?;
         
+        require!(!protocol_state.is_paused, ErrorCode::ProtocolPaused);
+        require!(!protocol_state.circuit_breaker.is_tripped, ErrorCode::CircuitBreakerTripped);
+        require!(coverage_amount > 0, ErrorCode::ZeroCoverageAmount);
+        require!(premium_amount > 0, ErrorCode::ZeroPremiumAmount);
+        // The config keeps the minimum above zero
+        require!(
+            duration_days >= config.min_policy_duration_days && duration_days <= config.max_policy_duration_days,
+            ErrorCode::InvalidPolicyDuration
+        );
+        
+        // Policies are only sold from a pool tier whose risk profile accepts the protocol
+        require!(
+            ctx.accounts.capital_pool.accepts(protocol_info),
+            ErrorCode::ProtocolNotAcceptedByPool
+        );
+        
+        // The premium and the pool's token both have to be listed, and the pool's token caps the coverage
+        let mint_registry = &ctx.accounts.mint_registry;
+        let min_premium = mint_registry.accepted(&ctx.accounts.premium_mint)?.min_premium;
+        mint_registry
+            .find(&ctx.accounts.capital_pool.token_mint)
+            .filter(|pool_mint| pool_mint.is_accepted)
+            .ok_or(ErrorCode::MintNotAccepted)?
+            .check_policy_coverage(coverage_amount)?;
+        
+        // Insureds staking the governance token get a tiered discount
+        let staker_discount_bps = match (&ctx.accounts.governance, &ctx.accounts.governance_stake) {
+            (Some(governance), Some(stake)) => staker_discount_bps(stake.amount, governance.min_proposal_stake),
+            _ => 0,
+        };
+        
+        // The premium paid must cover the protocol's risk-based price, net of discounts
+        // and including any surcharge from open exploit alerts, and the token's minimum
+        let risk_premium = required_premium(protocol_info, protocol_state, staker_discount_bps, coverage_amount, duration_days)?;
+        require!(
+            premium_amount >= risk_premium.max(min_premium),
+            ErrorCode::InsufficientPremium
+        );
+        
         policy.insured = ctx.accounts.insured.key();
-        policy.protocol = ctx.accounts.protocol_info.key();
+        policy.protocol = protocol_info.key();
+        policy.pool = ctx.accounts.capital_pool.key();
+        
+        let capital_pool = &mut ctx.accounts.capital_pool;
+        capital_pool.active_coverage = capital_pool.active_coverage.checked_add(coverage_amount).ok_or(ErrorCode::MathOverflow)?;
         policy.coverage_amount = coverage_amount;
         policy.premium_amount = premium_amount;
         policy.start_time = clock.unix_timestamp;
-        policy.end_time = clock.unix_timestamp + (duration_days as i64 * 86400);
+        policy.end_time = clock.unix_timestamp
+            .checked_add(duration_days as i64 * 86400)
+            .ok_or(ErrorCode::MathOverflow)?;
         policy.is_active = true;
         policy.is_claimed = false;
         policy.bump = ctx.bumps.policy;
         
-        // Transfer premium from the insured's token account to the protocol's treasury
-        let cpi_accounts = Transfer {
+        // Stake that earned a discount stays locked until the policy ends, so it can't be
+        // staked for the purchase and pulled in the same slot
+        if staker_discount_bps > 0 {
+            if let Some(stake) = ctx.accounts.governance_stake.as_mut() {
+                stake.locked_until = stake.locked_until.max(policy.end_time);
+            }
+        }
+        policy.version = ACCOUNT_VERSION;
+        policy.premium_mint = ctx.accounts.premium_mint.key();
+        policy.premium_decimals = ctx.accounts.premium_mint.decimals;
+        policy.amount_paid = 0;
+        policy.pending_reserved = 0;
+        policy.premium_escrowed = 0;
+        policy.premium_released = 0;
+        policy.premium_refunded = 0;
+        policy.claim_count = 0;
+        policy.open_claims = 0;
+        policy.coverage_released = 0;
+        
+        // Created on the insured's first purchase
+        let policy_counter = &mut ctx.accounts.policy_counter;
+        if policy_counter.insured == Pubkey::default() {
+            policy_counter.insured = ctx.accounts.insured.key();
+            policy_counter.bump = ctx.bumps.policy_counter;
+            policy_counter.version = ACCOUNT_VERSION;
+        }
+        policy.index = policy_counter.next_index;
+        policy_counter.next_index = policy_counter.next_index.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        
+        // Track premiums for the protocol's loss experience
+        protocol_info.premiums_earned = protocol_info.premiums_earned.checked_add(premium_amount).ok_or(ErrorCode::MathOverflow)?;
+        protocol_info.active_policies = protocol_info.active_policies.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        ctx.accounts.global_stats.record_policy_created(
+            &ctx.accounts.capital_pool.token_mint,
+            coverage_amount,
+            &ctx.accounts.premium_mint.key(),
+            premium_amount,
+        )?;
+        
+        // SOL premiums are wrapped from the insured's lamports as needed
+        if is_native_mint(&ctx.accounts.insured_token.mint) {
+            wrap_sol(
+                &ctx.accounts.insured,
+                &ctx.accounts.insured_token,
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program,
+                premium_amount,
+            )?;
+        }
+        
+        // The protocol fee goes to the fee vault, less any share owed to the covered
+        // protocol's treasury, and the rest of the premium to the treasury
+        let fee_amount = protocol_fee_amount(premium_amount, effective_protocol_fee(protocol_state, protocol_info))?;
+        let revenue_share_amount = revenue_share_amount(fee_amount, protocol_info.revenue_share_bps)?;
+        let vault_fee_amount = fee_amount.checked_sub(revenue_share_amount).ok_or(ErrorCode::MathOverflow)?;
+        protocol_state.fees_collected = protocol_state.fees_collected.checked_add(vault_fee_amount).ok_or(ErrorCode::MathOverflow)?;
+        protocol_info.fees_generated = protocol_info.fees_generated.checked_add(fee_amount).ok_or(ErrorCode::MathOverflow)?;
+        protocol_info.revenue_shared = protocol_info.revenue_shared.checked_add(revenue_share_amount).ok_or(ErrorCode::MathOverflow)?;
+        
+        if vault_fee_amount > 0 {
+            let fee_vault = ctx.accounts.fee_vault.as_ref().ok_or(ErrorCode::FeeVaultRequired)?;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.insured_token.to_account_info(),
+                mint: ctx.accounts.premium_mint.to_account_info(),
+                to: fee_vault.to_account_info(),
+                authority: ctx.accounts.insured.to_account_info(),
+            };
+            
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            
+            token::transfer_checked(cpi_ctx, vault_fee_amount, ctx.accounts.premium_mint.decimals)?;
+        }
+        
+        if revenue_share_amount > 0 {
+            let revenue_share_token = ctx.accounts.revenue_share_token.as_ref().ok_or(ErrorCode::RevenueShareAccountRequired)?;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.insured_token.to_account_info(),
+                mint: ctx.accounts.premium_mint.to_account_info(),
+                to: revenue_share_token.to_account_info(),
+                authority: ctx.accounts.insured.to_account_info(),
+            };
+            
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            
+            token::transfer_checked(cpi_ctx, revenue_share_amount, ctx.accounts.premium_mint.decimals)?;
+        }
+        
+        // The rest of the premium is escrowed and reaches the treasury as it's earned
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.insured_token.to_account_info(),
-            to: ctx.accounts.treasury_token.to_account_info(),
+            mint: ctx.accounts.premium_mint.to_account_info(),
+            to: ctx.accounts.premium_escrow.to_account_info(),
             authority: ctx.accounts.insured.to_account_info(),
         };
         
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         
-        token::transfer(cpi_ctx, premium_amount)?;
+        let escrow_amount = premium_amount.checked_sub(fee_amount).ok_or(ErrorCode::MathOverflow)?;
+        token::transfer_checked(cpi_ctx, escrow_amount, ctx.accounts.premium_mint.decimals)?;
+        policy.premium_escrowed = escrow_amount;
         
-        Ok(())
+        emit!(PolicyCreated {
+            policy: policy.key(),
+            insured: policy.insured,
+            protocol: policy.protocol,
+            pool: policy.pool,
+            coverage_amount,
+            premium_amount,
+            fee_amount,
+            revenue_share_amount,
+            staker_discount_bps,
+            start_time: policy.start_time,
+            end_time: policy.end_time,
+        });
+        
+        write_receipt(&ctx.accounts.memo_program, premium_receipt(&policy.key(), premium_amount, fee_amount))
+    }
+    
+    // Swaps another token into the premium mint through Jupiter first. The Jupiter
+    // program and route accounts are passed as remaining accounts.
+    pub fn create_policy_with_swap(
+        ctx: Context<CreatePolicy>,
+        coverage_amount: u64,
+        premium_amount: u64,
+        duration_days: u16,
+        swap_data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        swap::swap_into(ctx.remaining_accounts, &mut ctx.accounts.insured_token, swap_data, min_amount_out)?;
+        create_policy(ctx, coverage_amount, premium_amount, duration_days)
+    }
+    
+    // Prices the policy on-chain, floored at the premium token's minimum, and buys it at
+    // that price in the same instruction, so a quote can't go stale between fetching it
+    // and sending the purchase
+    pub fn create_policy_at_quote(
+        ctx: Context<CreatePolicy>,
+        coverage_amount: u64,
+        max_premium: u64,
+        duration_days: u16,
+    ) -> Result<()> {
+        let staker_discount_bps = match (&ctx.accounts.governance, &ctx.accounts.governance_stake) {
+            (Some(governance), Some(stake)) => staker_discount_bps(stake.amount, governance.min_proposal_stake),
+            _ => 0,
+        };
+        let premium_amount = required_premium(
+            &ctx.accounts.protocol_info,
+            &ctx.accounts.protocol_state,
+            staker_discount_bps,
+            coverage_amount,
+            duration_days,
+        )?
+        .max(ctx.accounts.mint_registry.accepted(&ctx.accounts.premium_mint)?.min_premium);
+        require!(premium_amount <= max_premium, ErrorCode::QuoteAboveMaxPremium);
+        
+        create_policy(ctx, coverage_amount, premium_amount, duration_days)
+    }
+    
+    // === Treasury Functions ===
+    
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        treasurer: Pubkey,
+    ) -> Result<()> {
+        treasury::initialize_treasury(ctx, treasurer)
+    }
+    
+    pub fn set_treasurer(
+        ctx: Context<ConfigureTreasury>,
+        treasurer: Pubkey,
+    ) -> Result<()> {
+        treasury::set_treasurer(ctx, treasurer)
+    }
+    
+    pub fn add_treasury_vault(
+        ctx: Context<AddTreasuryVault>,
+        spend_limit: u64,
+        window_secs: i64,
+    ) -> Result<()> {
+        treasury::add_treasury_vault(ctx, spend_limit, window_secs)
+    }
+    
+    pub fn set_treasury_spend_limit(
+        ctx: Context<SetTreasurySpendLimit>,
+        spend_limit: u64,
+        window_secs: i64,
+    ) -> Result<()> {
+        treasury::set_treasury_spend_limit(ctx, spend_limit, window_secs)
+    }
+    
+    pub fn withdraw_treasury(
+        ctx: Context<WithdrawTreasury>,
+        amount: u64,
+    ) -> Result<()> {
+        treasury::withdraw_treasury(ctx, amount)
+    }
+    
+    pub fn withdraw_treasury_lamports(
+        ctx: Context<WithdrawTreasuryLamports>,
+        amount: u64,
+    ) -> Result<()> {
+        treasury::withdraw_treasury_lamports(ctx, amount)
     }
     
     // === Risk Assessment Functions ===
@@ -95,17 +614,18 @@ pub mod solana_insurance_protocol {
         operational_risk_params: OperationalRiskParams,
     ) -> Result<()> {
         let protocol_info = &mut ctx.accounts.protocol_info;
+        let clock = Clock::get()?;
         
-        // Only the protocol authority or the protocol admin can update the risk parameters
-        require!(
-            ctx.accounts.authority.key() == protocol_info.authority || 
-            ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
-            ErrorCode::UnauthorizedAccess
-        );
+        // Reject out-of-range inputs rather than scoring garbage
+        code_risk_params.validate()?;
+        economic_risk_params.validate()?;
+        operational_risk_params.validate()?;
         
         // Calculate individual risk components
         let code_risk = assess_code_risk(
-            code_risk_params.audit_count,
+            protocol_info.verified_audit_count, // Use audits attached by whitelisted firms
+            protocol_info.last_audit_date,
+            clock.unix_timestamp,
             code_risk_params.bug_bounty_size,
             code_risk_params.complexity_score,
         );
@@ -116,123 +636,1111 @@ pub mod solana_insurance_protocol {
             economic_risk_params.concentration_risk,
         );
         
-        let operational_risk = assess_operational_risk(
-            operational_risk_params.governance_count,
-            operational_risk_params.admin_count,
-            operational_risk_params.oracle_dependency,
+        let operational_risk = apply_incident_history(
+            assess_operational_risk(
+                operational_risk_params.governance_count,
+                operational_risk_params.admin_count,
+                protocol_info.oracle_dependency,
+            ),
+            protocol_info.alerts_confirmed,
+        );
+        
+        let loss_experience = assess_loss_experience(
+            protocol_info.premiums_earned,
+            protocol_info.claims_paid,
         );
         
         // Calculate the composite risk score
-        let risk_score = calculate_composite_risk_score(code_risk, economic_risk, operational_risk);
+        let risk_score = calculate_composite_risk_score(
+            code_risk,
+            economic_risk,
+            operational_risk,
+            loss_experience,
+        );
         
-        // Update the protocol's risk score
-        protocol_info.risk_score = risk_score;
+        // Update the protocol's risk score, pausing coverage sales if it is now uninsurable
+        protocol_info.set_risk_components(code_risk, economic_risk, operational_risk);
+        let max_insurable_risk_score = ctx.accounts.protocol_state.max_insurable_risk_score;
+        if protocol_info.apply_risk_score(risk_score, max_insurable_risk_score) {
+            emit!(ProtocolAutoDeactivated {
+                protocol: protocol_info.key(),
+                risk_score,
+                max_insurable_risk_score,
+            });
+        }
+        protocol_info.last_risk_update = clock.unix_timestamp;
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        emit!(ProtocolRiskUpdated {
+            protocol: protocol_info.key(),
+            updated_by: ctx.accounts.authority.key(),
+            risk_score,
+            code_risk,
+            economic_risk,
+            operational_risk,
+            loss_experience,
+        });
         
         Ok(())
     }
     
-    // === Capital Management Functions ===
+    // Halts policy sales, capital movements and claims during an attack or while a bug is patched
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        let protocol_state = &mut ctx.accounts.protocol_state;
+        require!(!protocol_state.is_paused, ErrorCode::ProtocolPaused);
+        protocol_state.is_paused = true;
+        
+        emit!(ProtocolPaused {
+            authority: ctx.accounts.authority.key(),
+            paused_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
     
-    pub fn initialize_capital_pool(
-        ctx: Context<InitializeCapitalPool>,
-        pool_type: u8,
-        yield_rate_bps: u64,
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        let protocol_state = &mut ctx.accounts.protocol_state;
+        require!(protocol_state.is_paused, ErrorCode::ProtocolNotPaused);
+        protocol_state.is_paused = false;
+        
+        emit!(ProtocolUnpaused {
+            authority: ctx.accounts.authority.key(),
+            unpaused_at: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+    
+    pub fn set_protocol_fee(
+        ctx: Context<SetProtocolFee>,
+        new_fee_bps: u64,
     ) -> Result<()> {
-        capital_management::initialize_capital_pool(ctx, pool_type, yield_rate_bps)
+        fees::set_protocol_fee(ctx, new_fee_bps)
     }
     
-    pub fn provide_capital(
-        ctx: Context<ProvideCapital>,
+    pub fn execute_protocol_fee_change(ctx: Context<ExecuteProtocolFeeChange>) -> Result<()> {
+        fees::execute_protocol_fee_change(ctx)
+    }
+    
+    pub fn set_fee_vault(ctx: Context<SetFeeVault>) -> Result<()> {
+        fees::set_fee_vault(ctx)
+    }
+    
+    pub fn withdraw_protocol_fees(
+        ctx: Context<WithdrawProtocolFees>,
         amount: u64,
     ) -> Result<()> {
-        capital_management::provide_capital(ctx, amount)
+        fees::withdraw_protocol_fees(ctx, amount)
     }
     
-    pub fn withdraw_capital(
-        ctx: Context<WithdrawCapital>,
+    // === Governance Functions ===
+    
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        min_proposal_stake: u64,
+        quorum_votes: u64,
+        voting_period_secs: i64,
+    ) -> Result<()> {
+        governance::initialize_governance(ctx, min_proposal_stake, quorum_votes, voting_period_secs)
+    }
+    
+    pub fn stake_governance(
+        ctx: Context<StakeGovernance>,
         amount: u64,
     ) -> Result<()> {
-        capital_management::withdraw_capital(ctx, amount)
+        governance::stake_governance(ctx, amount)
     }
     
-    // === Claims Processing Functions ===
+    pub fn unstake_governance(
+        ctx: Context<UnstakeGovernance>,
+        amount: u64,
+    ) -> Result<()> {
+        governance::unstake_governance(ctx, amount)
+    }
+    
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction,
+    ) -> Result<()> {
+        governance::create_proposal(ctx, action)
+    }
+    
+    pub fn vote(
+        ctx: Context<Vote>,
+        in_favor: bool,
+    ) -> Result<()> {
+        governance::vote(ctx, in_favor)
+    }
+    
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        governance::execute_proposal(ctx)
+    }
+    
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        authority::propose_authority_transfer(ctx, new_authority)
+    }
+    
+    pub fn accept_authority_transfer(
+        ctx: Context<AcceptAuthorityTransfer>,
+        vault_index: u8,
+    ) -> Result<()> {
+        authority::accept_authority_transfer(ctx, vault_index)
+    }
+    
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        config::initialize_config(ctx)
+    }
+    
+    pub fn set_features(
+        ctx: Context<SetFeatures>,
+        features: u8,
+    ) -> Result<()> {
+        config::set_features(ctx, features)
+    }
+    
+    pub fn initialize_oracle_config(ctx: Context<InitializeOracleConfig>) -> Result<()> {
+        oracle_guards::initialize_oracle_config(ctx)
+    }
+    
+    pub fn initialize_mint_registry(ctx: Context<InitializeMintRegistry>) -> Result<()> {
+        mint_registry::initialize_mint_registry(ctx)
+    }
+    
+    pub fn register_mint(
+        ctx: Context<RegisterMint>,
+        price_feed_id: [u8; 32],
+        max_policy_coverage: u64,
+        max_pool_capital: u64,
+        min_premium: u64,
+    ) -> Result<()> {
+        mint_registry::register_mint(ctx, price_feed_id, max_policy_coverage, max_pool_capital, min_premium)
+    }
+    
+    pub fn set_mint_accepted(
+        ctx: Context<SetMintAccepted>,
+        mint: Pubkey,
+        is_accepted: bool,
+    ) -> Result<()> {
+        mint_registry::set_mint_accepted(ctx, mint, is_accepted)
+    }
+    
+    pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+        global_stats::initialize_global_stats(ctx)
+    }
+    
+    // Upgrade accounts created under an older layout to the current one
+    pub fn migrate_protocol_state(ctx: Context<MigrateProtocolState>) -> Result<()> {
+        migrations::migrate_protocol_state(ctx)
+    }
+    
+    pub fn migrate_config(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_config(ctx)
+    }
+    
+    pub fn migrate_protocol_registry(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_protocol_registry(ctx)
+    }
+    
+    pub fn migrate_protocol_info(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_protocol_info(ctx)
+    }
+    
+    pub fn migrate_policy(ctx: Context<MigratePolicy>) -> Result<()> {
+        migrations::migrate_policy(ctx)
+    }
+    
+    pub fn migrate_claim(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_claim(ctx)
+    }
+    
+    pub fn migrate_capital_pool(ctx: Context<MigrateAccountWithMint>) -> Result<()> {
+        migrations::migrate_capital_pool(ctx)
+    }
+    
+    pub fn migrate_capital_provider(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_capital_provider(ctx)
+    }
+    
+    pub fn migrate_watcher(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_watcher(ctx)
+    }
+    
+    pub fn migrate_governance_stake(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_governance_stake(ctx)
+    }
+    
+    pub fn migrate_global_stats(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrations::migrate_global_stats(ctx)
+    }
+    
+    // Configuration changes only take effect after the timelock
+    pub fn schedule_change(
+        ctx: Context<ScheduleChange>,
+        change: ParameterChange,
+    ) -> Result<()> {
+        timelock::schedule_change(ctx, change)
+    }
+    
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        timelock::execute_change(ctx)
+    }
+    
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        timelock::cancel_change(ctx)
+    }
+    
+    // Stops coverage sales on a protocol; either the admin or the protocol itself can pull the plug
+    pub fn deactivate_protocol(
+        ctx: Context<DeactivateProtocol>,
+        reason: DeactivationReason,
+    ) -> Result<()> {
+        require!(reason != DeactivationReason::None, ErrorCode::InvalidDeactivationReason);
+        
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        
+        // Otherwise the protocol could take over a suspension and then lift it itself
+        if ctx.accounts.authority.key() != ctx.accounts.protocol_state.authority {
+            require!(protocol_info.is_active, ErrorCode::ProtocolNotActive);
+        }
+        
+        protocol_info.is_active = false;
+        protocol_info.deactivation_reason = reason;
+        protocol_info.deactivated_by = ctx.accounts.authority.key();
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        emit!(ProtocolDeactivated {
+            protocol: protocol_info.key(),
+            deactivated_by: protocol_info.deactivated_by,
+            reason,
+        });
+        
+        Ok(())
+    }
+    
+    pub fn begin_protocol_deregistration(ctx: Context<BeginProtocolDeregistration>) -> Result<()> {
+        deregistration::begin_protocol_deregistration(ctx)
+    }
+    
+    pub fn close_expired_policy(ctx: Context<ClosePolicy>) -> Result<()> {
+        deregistration::close_expired_policy(ctx)
+    }
+    
+    pub fn cancel_policy(ctx: Context<CancelPolicy>) -> Result<()> {
+        deregistration::cancel_policy(ctx)
+    }
+    
+    pub fn release_earned_premium(ctx: Context<ReleaseEarnedPremium>) -> Result<()> {
+        premium_escrow::release_earned_premium(ctx)
+    }
+    
+    pub fn deregister_protocol(ctx: Context<DeregisterProtocol>) -> Result<()> {
+        deregistration::deregister_protocol(ctx)
+    }
+    
+    pub fn set_keeper_reward(ctx: Context<SetKeeperReward>, reward_per_crank: u64) -> Result<()> {
+        keeper::set_keeper_reward(ctx, reward_per_crank)
+    }
+    
+    // Rent from closed accounts goes to the treasury
+    pub fn close_stale_policy(ctx: Context<CloseStalePolicy>) -> Result<()> {
+        cleanup::close_stale_policy(ctx)
+    }
+    
+    pub fn close_stale_claim(ctx: Context<CloseStaleClaim>) -> Result<()> {
+        cleanup::close_stale_claim(ctx)
+    }
+    
+    pub fn close_empty_provider(ctx: Context<CloseEmptyProvider>) -> Result<()> {
+        cleanup::close_empty_provider(ctx)
+    }
+    
+    pub fn reactivate_protocol(ctx: Context<ReactivateProtocol>) -> Result<()> {
+        let protocol_info = &mut ctx.accounts.protocol_info;
+        let authority = ctx.accounts.authority.key();
+        
+        // The protocol can only undo its own deactivation; anything else needs the admin
+        if authority != ctx.accounts.protocol_state.authority {
+            require!(protocol_info.deactivated_by == authority, ErrorCode::UnauthorizedAccess);
+        }
+        
+        require!(!protocol_info.is_deregistering, ErrorCode::ProtocolDeregistering);
+        
+        // Reactivating a protocol that is still above the threshold would be undone by the next update
+        require!(
+            protocol_info.risk_score <= ctx.accounts.protocol_state.max_insurable_risk_score,
+            ErrorCode::RiskScoreAboveMaximum
+        );
+        
+        protocol_info.is_active = true;
+        if protocol_info.incident_state.is_incident() {
+            resolve_incident(protocol_info)?;
+        }
+        protocol_info.deactivation_reason = DeactivationReason::None;
+        protocol_info.deactivated_by = Pubkey::default();
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+        
+        emit!(ProtocolReactivated {
+            protocol: protocol_info.key(),
+            risk_score: protocol_info.risk_score,
+            reactivated_by: authority,
+        });
+        
+        Ok(())
+    }
+    
+    pub fn submit_risk_attestation(
+        ctx: Context<SubmitRiskAttestation>,
+        attestation: RiskAttestation,
+    ) -> Result<()> {
+        risk_attestation::submit_risk_attestation(ctx, attestation)
+    }
+    
+    pub fn engage_safeguard_assessor(ctx: Context<EngageSafeguardAssessor>) -> Result<()> {
+        safeguards::engage_safeguard_assessor(ctx)
+    }
+    
+    pub fn attest_safeguards(
+        ctx: Context<AttestSafeguards>,
+        safeguard_flags: u8,
+    ) -> Result<()> {
+        safeguards::attest_safeguards(ctx, safeguard_flags)
+    }
+    
+    // === Risk Committee Functions ===
+    
+    pub fn initialize_risk_committee(
+        ctx: Context<InitializeRiskCommittee>,
+        members: Vec<Pubkey>,
+        quorum: u8,
+    ) -> Result<()> {
+        risk_committee::initialize_risk_committee(ctx, members, quorum)
+    }
+    
+    pub fn update_risk_committee(
+        ctx: Context<UpdateRiskCommittee>,
+        members: Vec<Pubkey>,
+        quorum: u8,
+    ) -> Result<()> {
+        risk_committee::update_risk_committee(ctx, members, quorum)
+    }
+    
+    pub fn submit_committee_score(
+        ctx: Context<SubmitCommitteeScore>,
+        code_risk: u8,
+        economic_risk: u8,
+        operational_risk: u8,
+    ) -> Result<()> {
+        risk_committee::submit_committee_score(ctx, code_risk, economic_risk, operational_risk)
+    }
+    
+    // === Audit Registry Functions ===
+    
+    pub fn whitelist_auditor(
+        ctx: Context<WhitelistAuditor>,
+        name: String,
+    ) -> Result<()> {
+        audit_registry::whitelist_auditor(ctx, name)
+    }
+    
+    pub fn set_auditor_status(
+        ctx: Context<SetAuditorStatus>,
+        is_active: bool,
+    ) -> Result<()> {
+        audit_registry::set_auditor_status(ctx, is_active)
+    }
+    
+    pub fn attach_audit(
+        ctx: Context<AttachAudit>,
+        audit_date: i64,
+        scope: String,
+        report_hash: [u8; 32],
+    ) -> Result<()> {
+        audit_registry::attach_audit(ctx, audit_date, scope, report_hash)
+    }
+    
+    // === Capital Management Functions ===
+    
+    pub fn initialize_capital_pool(
+        ctx: Context<InitializeCapitalPool>,
+        pool_type: CapitalPoolType,
+        yield_rate_bps: u64,
+    ) -> Result<()> {
+        capital_management::initialize_capital_pool(ctx, pool_type, yield_rate_bps)
+    }
+    
+    pub fn provide_capital(
+        ctx: Context<ProvideCapital>,
+        amount: u64,
+    ) -> Result<()> {
+        capital_management::provide_capital(ctx, amount)
+    }
+    
+    pub fn set_pool_withdrawals_paused(
+        ctx: Context<ConfigureCapitalPool>,
+        withdrawals_paused: bool,
+    ) -> Result<()> {
+        capital_management::set_pool_withdrawals_paused(ctx, withdrawals_paused)
+    }
+    
+    pub fn run_stress_test(
+        ctx: Context<RunStressTest>,
+        report_id: u64,
+        loss_bps: u64,
+    ) -> Result<()> {
+        solvency::run_stress_test(ctx, report_id, loss_bps)
+    }
+    
+    pub fn withdraw_capital(
+        ctx: Context<WithdrawCapital>,
+        amount: u64,
+    ) -> Result<()> {
+        capital_management::withdraw_capital(ctx, amount)
+    }
+    
+    pub fn withdraw_and_close_position(ctx: Context<WithdrawCapital>) -> Result<()> {
+        capital_management::withdraw_and_close_position(ctx)
+    }
+    
+    pub fn checkpoint_rewards(ctx: Context<CheckpointRewards>) -> Result<()> {
+        capital_management::checkpoint_rewards(ctx)
+    }
+    
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        capital_management::close_position(ctx)
+    }
+    
+    pub fn assert_invariants(ctx: Context<AssertInvariants>) -> Result<()> {
+        invariants::assert_invariants(ctx)
+    }
+    
+    pub fn sync_pool_balance(ctx: Context<SyncPoolBalance>) -> Result<()> {
+        invariants::sync_pool_balance(ctx)
+    }
+    
+    pub fn assert_registry_invariants(ctx: Context<AssertRegistryInvariants>) -> Result<()> {
+        invariants::assert_registry_invariants(ctx)
+    }
+    
+    pub fn reconcile_registry_counts(ctx: Context<ReconcileRegistryCounts>) -> Result<()> {
+        invariants::reconcile_registry_counts(ctx)
+    }
+    
+    // === Claims Processing Functions ===
+    
+    pub fn submit_claim(
+        ctx: Context<SubmitClaim>,
+        amount: u64,
+        evidence: String,
+    ) -> Result<()> {
+        claims::submit_claim(ctx, amount, evidence)
+    }
+    
+    pub fn resolve_claim(
+        ctx: Context<ResolveClaim>,
+        approve: bool,
+        resolution_notes: String,
+    ) -> Result<()> {
+        claims::resolve_claim(ctx, approve, resolution_notes)
+    }
+    
+    pub fn pay_claim(ctx: Context<PayClaim>) -> Result<()> {
+        claims::pay_claim(ctx)
+    }
+    
+    pub fn resolve_and_pay_claim(
+        ctx: Context<ResolveAndPayClaim>,
+        resolution_notes: String,
+    ) -> Result<()> {
+        claims::resolve_and_pay_claim(ctx, resolution_notes)
+    }
+    
+    pub fn add_claim_resolver(
+        ctx: Context<ConfigureClaimResolvers>,
+        resolver: Pubkey,
+    ) -> Result<()> {
+        claims::add_claim_resolver(ctx, resolver)
+    }
+    
+    pub fn remove_claim_resolver(
+        ctx: Context<ConfigureClaimResolvers>,
+        resolver: Pubkey,
+    ) -> Result<()> {
+        claims::remove_claim_resolver(ctx, resolver)
+    }
+    
+    pub fn configure_claims_committee(
+        ctx: Context<ConfigureClaimsCommittee>,
+        large_claim_threshold: u64,
+    ) -> Result<()> {
+        realms::configure_claims_committee(ctx, large_claim_threshold)
+    }
+    
+    pub fn remove_claims_committee(ctx: Context<RemoveClaimsCommittee>) -> Result<()> {
+        realms::remove_claims_committee(ctx)
+    }
+    
+    pub fn initialize_emergency_council(
+        ctx: Context<InitializeEmergencyCouncil>,
+        members: Vec<Pubkey>,
+    ) -> Result<()> {
+        emergency_council::initialize_emergency_council(ctx, members)
+    }
+    
+    pub fn update_emergency_council(
+        ctx: Context<UpdateEmergencyCouncil>,
+        members: Vec<Pubkey>,
+    ) -> Result<()> {
+        emergency_council::update_emergency_council(ctx, members)
+    }
+    
+    pub fn veto_claim(
+        ctx: Context<VetoClaim>,
+        reason: String,
+    ) -> Result<()> {
+        emergency_council::veto_claim(ctx, reason)
+    }
+    
+    pub fn set_pool_payouts_frozen(
+        ctx: Context<SetPoolPayoutsFrozen>,
+        frozen: bool,
+        reason: String,
+    ) -> Result<()> {
+        emergency_council::set_pool_payouts_frozen(ctx, frozen, reason)
+    }
+    
+    pub fn create_loss_event(ctx: Context<CreateLossEvent>) -> Result<()> {
+        loss_events::create_loss_event(ctx)
+    }
+    
+    pub fn register_loss_emitter(
+        ctx: Context<RegisterLossEmitter>,
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+    ) -> Result<()> {
+        wormhole::register_loss_emitter(ctx, emitter_chain, emitter_address)
+    }
+    
+    pub fn remove_loss_emitter(ctx: Context<RemoveLossEmitter>) -> Result<()> {
+        wormhole::remove_loss_emitter(ctx)
+    }
+    
+    pub fn create_cross_chain_loss_event(ctx: Context<CreateCrossChainLossEvent>) -> Result<()> {
+        wormhole::create_cross_chain_loss_event(ctx)
+    }
+    
+    pub fn configure_lst_depeg(
+        ctx: Context<ConfigureLstDepeg>,
+        lst_price_feed: [u8; 32],
+        sol_price_feed: [u8; 32],
+        threshold_bps: u64,
+    ) -> Result<()> {
+        lst_depeg::configure_lst_depeg(ctx, lst_price_feed, sol_price_feed, threshold_bps)
+    }
+    
+    pub fn refresh_lst_exchange_rate(ctx: Context<RefreshLstExchangeRate>) -> Result<()> {
+        lst_depeg::refresh_lst_exchange_rate(ctx)
+    }
+    
+    pub fn trigger_lst_depeg(ctx: Context<TriggerLstDepeg>) -> Result<()> {
+        lst_depeg::trigger_lst_depeg(ctx)
+    }
+    
+    // Validator downtime only; slashing isn't covered
+    pub fn configure_validator_cover(
+        ctx: Context<ConfigureValidatorCover>,
+        min_epoch_credits: u64,
+    ) -> Result<()> {
+        validator_cover::configure_validator_cover(ctx, min_epoch_credits)
+    }
+    
+    pub fn trigger_validator_downtime(ctx: Context<TriggerValidatorDowntime>, epoch: u64) -> Result<()> {
+        validator_cover::trigger_validator_downtime(ctx, epoch)
+    }
+    
+    pub fn attest_snapshot(ctx: Context<AttestSnapshot>, slot: u64) -> Result<()> {
+        snapshots::attest_snapshot(ctx, slot)
+    }
+    
+    // === Exploit Detection Functions ===
+    
+    pub fn initialize_watcher_registry(
+        ctx: Context<InitializeWatcherRegistry>,
+        min_stake: u64,
+    ) -> Result<()> {
+        watchers::initialize_watcher_registry(ctx, min_stake)
+    }
+    
+    pub fn stake_watcher(
+        ctx: Context<StakeWatcher>,
+        amount: u64,
+    ) -> Result<()> {
+        watchers::stake_watcher(ctx, amount)
+    }
+    
+    pub fn set_bounty_vault(ctx: Context<SetBountyVault>) -> Result<()> {
+        watchers::set_bounty_vault(ctx)
+    }
+    
+    pub fn claim_alert_bounty(ctx: Context<ClaimAlertBounty>) -> Result<()> {
+        watchers::claim_alert_bounty(ctx)
+    }
+    
+    pub fn unstake_watcher(
+        ctx: Context<UnstakeWatcher>,
+        amount: u64,
+    ) -> Result<()> {
+        watchers::unstake_watcher(ctx, amount)
+    }
+    
+    pub fn create_exploit_alert(
+        ctx: Context<CreateExploitAlert>,
+        anomaly_type: AnomalyType,
+        severity: AlertSeverity,
+        details_hash: [u8; 32],
+        details_uri: String,
+    ) -> Result<()> {
+        exploit_detection::create_exploit_alert(ctx, anomaly_type, severity, details_hash, details_uri)
+    }
+    
+    pub fn resolve_exploit_alert(
+        ctx: Context<ResolveExploitAlert>,
+        is_confirmed: bool,
+        is_malicious: bool,
+        resolution_notes: String,
+    ) -> Result<()> {
+        exploit_detection::resolve_exploit_alert(ctx, is_confirmed, is_malicious, resolution_notes)
+    }
+    
+    pub fn subscribe_to_alerts(ctx: Context<SubscribeToAlerts>) -> Result<()> {
+        alert_subscriptions::subscribe_to_alerts(ctx)
+    }
+    
+    pub fn unsubscribe_from_alerts(ctx: Context<UnsubscribeFromAlerts>) -> Result<()> {
+        alert_subscriptions::unsubscribe_from_alerts(ctx)
+    }
+    
+    pub fn attach_postmortem(
+        ctx: Context<AttachPostmortem>,
+        report_hash: [u8; 32],
+        root_cause: RootCause,
+        loss_estimate: u64,
+    ) -> Result<()> {
+        postmortems::attach_postmortem(ctx, report_hash, root_cause, loss_estimate)
+    }
+    
+    pub fn create_bonded_alert(
+        ctx: Context<CreateBondedAlert>,
+        anomaly_type: AnomalyType,
+        severity: AlertSeverity,
+        details_hash: [u8; 32],
+        details_uri: String,
+    ) -> Result<()> {
+        bonded_alerts::create_bonded_alert(ctx, anomaly_type, severity, details_hash, details_uri)
+    }
+    
+    pub fn claim_alert_bond(ctx: Context<ClaimAlertBond>) -> Result<()> {
+        bonded_alerts::claim_alert_bond(ctx)
+    }
+    
+    pub fn confirm_alert(ctx: Context<ConfirmAlert>) -> Result<()> {
+        exploit_detection::confirm_alert(ctx)
+    }
+    
+    pub fn acknowledge_alert(ctx: Context<AcknowledgeAlert>) -> Result<()> {
+        exploit_detection::acknowledge_alert(ctx)
+    }
+    
+    pub fn escalate_alert(ctx: Context<EscalateAlert>) -> Result<()> {
+        exploit_detection::escalate_alert(ctx)
+    }
+    
+    pub fn add_alert_evidence(
+        ctx: Context<AddAlertEvidence>,
+        suspicious_txs: Vec<SuspiciousTransaction>,
+        affected_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        exploit_detection::add_alert_evidence(ctx, suspicious_txs, affected_accounts)
+    }
+    
+    pub fn update_alert_severity(
+        ctx: Context<UpdateAlertSeverity>,
+        severity: AlertSeverity,
+        reason: String,
+    ) -> Result<()> {
+        exploit_detection::update_alert_severity(ctx, severity, reason)
+    }
+    
+    pub fn expire_alert(ctx: Context<ExpireAlert>) -> Result<()> {
+        exploit_detection::expire_alert(ctx)
+    }
+    
+    pub fn check_tvl_anomaly(
+        ctx: Context<CheckTvlAnomaly>,
+        attestation: TvlAttestation,
+    ) -> Result<()> {
+        tvl_monitor::check_tvl_anomaly(ctx, attestation)
+    }
+    
+    pub fn configure_tvl_adapter(
+        ctx: Context<ConfigureTvlAdapter>,
+        program_id: Pubkey,
+        kind: TvlAdapterKind,
+        accounts: Vec<TvlAdapterAccount>,
+    ) -> Result<()> {
+        tvl_adapters::configure_tvl_adapter(ctx, program_id, kind, accounts)
+    }
+    
+    pub fn refresh_tvl_from_adapter(ctx: Context<RefreshTvlFromAdapter>) -> Result<()> {
+        tvl_adapters::refresh_tvl_from_adapter(ctx)
+    }
+    
+    pub fn configure_heartbeat(
+        ctx: Context<ConfigureHeartbeat>,
+        keeper: Pubkey,
+        max_missed_epochs: u64,
+    ) -> Result<()> {
+        heartbeat::configure_heartbeat(ctx, keeper, max_missed_epochs)
+    }
+    
+    pub fn submit_heartbeat(ctx: Context<SubmitHeartbeat>) -> Result<()> {
+        heartbeat::submit_heartbeat(ctx)
+    }
+    
+    pub fn check_heartbeat(ctx: Context<CheckHeartbeat>) -> Result<()> {
+        heartbeat::check_heartbeat(ctx)
+    }
+    
+    // Delegate keys for heartbeats and alert acknowledgements
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        delegate: Pubkey,
+        permissions: u8,
+        expires_at: i64,
+    ) -> Result<()> {
+        session_keys::create_session_key(ctx, delegate, permissions, expires_at)
+    }
+    
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        session_keys::revoke_session_key(ctx)
+    }
+    
+    pub fn configure_price_feeds(
+        ctx: Context<ConfigurePriceFeeds>,
+        primary_feed: Pubkey,
+        secondary_feed: Pubkey,
+        max_deviation_bps: u64,
+    ) -> Result<()> {
+        oracle_deviation::configure_price_feeds(ctx, primary_feed, secondary_feed, max_deviation_bps)
+    }
+    
+    pub fn check_price_deviation(
+        ctx: Context<CheckPriceDeviation>,
+        primary: PriceAttestation,
+        secondary: PriceAttestation,
+    ) -> Result<()> {
+        oracle_deviation::check_price_deviation(ctx, primary, secondary)
+    }
+    
+    pub fn check_pyth_price_deviation(ctx: Context<CheckPythPriceDeviation>) -> Result<()> {
+        oracle_deviation::check_pyth_price_deviation(ctx)
+    }
+    
+    pub fn clear_circuit_breaker(ctx: Context<ConfigureCircuitBreaker>) -> Result<()> {
+        circuit_breaker::clear_circuit_breaker(ctx)
+    }
+    
+    // === View Functions ===
+    
+    pub fn get_policy_status(ctx: Context<GetPolicyStatus>) -> Result<PolicyStatusView> {
+        views::get_policy_status(ctx)
+    }
+    
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStatsView> {
+        views::get_pool_stats(ctx)
+    }
+    
+    pub fn get_claim_status(ctx: Context<GetClaimStatus>) -> Result<ClaimStatusView> {
+        views::get_claim_status(ctx)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolState::SIZE,
+        seeds = [b"protocol-state"],
+        bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolRegistry::SIZE,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterProtocol<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolInfo::SIZE,
+        seeds = [b"protocol-info", authority.key().as_ref()],
+        bump
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ProtocolEntry::SIZE,
+        seeds = [b"protocol-entry".as_ref(), &registry.next_index.to_le_bytes()],
+        bump
+    )]
+    pub protocol_entry: Account<'info, ProtocolEntry>,
+    
+    // Only needed while the registration allowlist is on
+    #[account(
+        seeds = [b"registration-approval", authority.key().as_ref()],
+        bump = registration_approval.bump
+    )]
+    pub registration_approval: Option<Account<'info, RegistrationApproval>>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolInfo<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = protocol_info.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-entry".as_ref(), &protocol_info.registry_index.to_le_bytes()],
+        bump = protocol_entry.bump
+    )]
+    pub protocol_entry: Account<'info, ProtocolEntry>,
+    
+    #[account(
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptProtocolAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-entry".as_ref(), &protocol_info.registry_index.to_le_bytes()],
+        bump = protocol_entry.bump
+    )]
+    pub protocol_entry: Account<'info, ProtocolEntry>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePolicy<'info> {
+    #[account(mut)]
+    pub insured: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = insured,
+        space = PolicyCounter::SIZE,
+        seeds = [b"policy-counter", insured.key().as_ref()],
+        bump
+    )]
+    pub policy_counter: Account<'info, PolicyCounter>,
+    
+    #[account(
+        init,
+        payer = insured,
+        space = Policy::SIZE,
+        seeds = [b"policy", insured.key().as_ref(), protocol_info.key().as_ref(), &policy_counter.next_index.to_le_bytes()],
+        bump
+    )]
+    pub policy: Account<'info, Policy>,
+    
+    #[account(
+        mut,
+        constraint = protocol_info.is_active @ ErrorCode::ProtocolNotActive
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
     
-    pub fn submit_claim(
-        ctx: Context<SubmitClaim>,
-        amount: u64,
-        evidence: String,
-    ) -> Result<()> {
-        claims::submit_claim(ctx, amount, evidence)
-    }
+    #[account(
+        mut,
+        constraint = insured_token.owner == insured.key()
+    )]
+    pub insured_token: Account<'info, TokenAccount>,
     
-    pub fn resolve_claim(
-        ctx: Context<ResolveClaim>,
-        approve: bool,
-        resolution_notes: String,
-    ) -> Result<()> {
-        claims::resolve_claim(ctx, approve, resolution_notes)
-    }
+    // Premiums can only be paid in a mint the treasury has a vault for
+    #[account(
+        seeds = [b"treasury-vault", insured_token.mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
     
-    // === Exploit Detection Functions ===
+    // Only needed while a protocol fee is charged
+    #[account(
+        mut,
+        constraint = fee_vault.key() == protocol_state.fee_vault @ ErrorCode::InvalidFeeVault
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
     
-    pub fn create_exploit_alert(
-        ctx: Context<CreateExploitAlert>,
-        anomaly_type: u8,
-        severity: u8,
-        details: String,
-    ) -> Result<()> {
-        exploit_detection::create_exploit_alert(ctx, anomaly_type, severity, details)
-    }
+    // Only needed while the protocol takes a revenue share
+    #[account(
+        mut,
+        constraint = revenue_share_token.key() == protocol_info.revenue_share_account @ ErrorCode::InvalidRevenueShareAccount
+    )]
+    pub revenue_share_token: Option<Account<'info, TokenAccount>>,
     
-    pub fn resolve_exploit_alert(
-        ctx: Context<ResolveExploitAlert>,
-        is_confirmed: bool,
-        resolution_notes: String,
-    ) -> Result<()> {
-        exploit_detection::resolve_exploit_alert(ctx, is_confirmed, resolution_notes)
-    }
-}
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    // Only needed to claim the governance staker discount
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Option<Account<'info, Governance>>,
     
     #[account(
-        init,
-        payer = authority,
-        space = ProtocolState::SIZE,
-        seeds = [b"protocol-state"],
-        bump
+        mut,
+        seeds = [b"governance-stake", insured.key().as_ref()],
+        bump = governance_stake.bump
     )]
-    pub protocol_state: Account<'info, ProtocolState>,
+    pub governance_stake: Option<Account<'info, GovernanceStake>>,
+    
+    #[account(address = treasury_vault.mint)]
+    pub premium_mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
     
+    // Holds the premium net of fees until it's earned or refunded
     #[account(
         init,
-        payer = authority,
-        space = ProtocolRegistry::SIZE,
-        seeds = [b"protocol-registry"],
-        bump
+        payer = insured,
+        seeds = [b"premium-escrow", policy.key().as_ref()],
+        bump,
+        token::mint = premium_mint,
+        token::authority = policy
     )]
-    pub registry: Account<'info, ProtocolRegistry>,
+    pub premium_escrow: Account<'info, TokenAccount>,
     
-    pub system_program: Program<'info, System>,
+    // Only needed for a memo receipt of the payment
+    pub memo_program: Option<Program<'info, Memo>>,
+    
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
 }
 
 #[derive(Accounts)]
-pub struct RegisterProtocol<'info> {
+pub struct UpdateProtocolRisk<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    // Scoring is the committee's job; neither the protocol nor the admin can set its own score
     #[account(
-        init,
-        payer = authority,
-        space = ProtocolInfo::SIZE,
-        seeds = [b"protocol-info", authority.key().as_ref()],
-        bump
+        seeds = [b"risk-committee"],
+        bump = risk_committee.bump,
+        constraint = risk_committee.member_index(&authority.key()).is_some() @ ErrorCode::UnauthorizedAccess
     )]
+    pub risk_committee: Account<'info, RiskCommittee>,
+    
+    #[account(mut)]
     pub protocol_info: Account<'info, ProtocolInfo>,
     
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
     #[account(
         mut,
         seeds = [b"protocol-registry"],
@@ -240,49 +1748,68 @@ pub struct RegisterProtocol<'info> {
     )]
     pub registry: Account<'info, ProtocolRegistry>,
     
-    pub system_program: Program<'info, System>,
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
 }
 
 #[derive(Accounts)]
-pub struct CreatePolicy<'info> {
-    #[account(mut)]
-    pub insured: Signer<'info>,
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
     
     #[account(
-        init,
-        payer = insured,
-        space = Policy::SIZE,
-        seeds = [b"policy", insured.key().as_ref(), protocol_info.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
     )]
-    pub policy: Account<'info, Policy>,
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct DeactivateProtocol<'info> {
+    pub authority: Signer<'info>,
     
     #[account(
         mut,
-        constraint = protocol_info.is_active @ ErrorCode::ProtocolNotActive
+        constraint = protocol_info.authority == authority.key() ||
+            protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
     )]
     pub protocol_info: Account<'info, ProtocolInfo>,
     
     #[account(
-        mut,
-        constraint = insured_token.owner == insured.key(),
-        constraint = insured_token.mint == treasury_token.mint
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
     )]
-    pub insured_token: Account<'info, TokenAccount>,
+    pub protocol_state: Account<'info, ProtocolState>,
     
-    #[account(mut)]
-    pub treasury_token: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateProtocolRisk<'info> {
-    #[account(mut)]
+pub struct ReactivateProtocol<'info> {
     pub authority: Signer<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = protocol_info.authority == authority.key() ||
+            protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
     pub protocol_info: Account<'info, ProtocolInfo>,
     
     #[account(
@@ -290,30 +1817,137 @@ pub struct UpdateProtocolRisk<'info> {
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
 }
 
 #[account]
 pub struct ProtocolState {
     pub authority: Pubkey,
     pub protocol_fee: u64,
+    pub risk_oracle: Pubkey,
+    pub max_insurable_risk_score: u8,
+    pub premium_curve: [PremiumCurvePoint; PREMIUM_CURVE_POINTS],
+    pub safeguard_discount_bps: [u64; SAFEGUARD_COUNT],
+    pub alert_response: AlertResponseConfig,
+    pub tvl_anomaly: TvlAnomalyConfig,
+    pub circuit_breaker: CircuitBreaker,
+    pub is_paused: bool,
+    pub fee_vault: Pubkey,
+    pub fees_collected: u64,
+    pub fees_withdrawn: u64,
+    pub pending_protocol_fee: u64,
+    pub protocol_fee_effective_at: i64, // 0 when no fee change is scheduled
+    pub change_count: u64,
+    pub pending_authority: Pubkey,  // Default key when no handover is in progress
+    pub authority_multisig: Pubkey, // Squads multisig behind the authority vault, if any
     pub bump: u8,
+    pub version: u8,
+    // Version 2
+    pub claim_resolvers: [Pubkey; MAX_CLAIM_RESOLVERS], // Keys allowed to adjudicate claims
+    pub claim_resolver_count: u8,
 }
 
 impl ProtocolState {
     pub const SIZE: usize = 8 + // discriminator
                            32 + // authority
                            8 +  // protocol_fee
-                           1;   // bump
+                           32 + // risk_oracle
+                           1 +  // max_insurable_risk_score
+                           PremiumCurvePoint::SIZE * PREMIUM_CURVE_POINTS + // premium_curve
+                           8 * SAFEGUARD_COUNT + // safeguard_discount_bps
+                           AlertResponseConfig::SIZE + // alert_response
+                           TvlAnomalyConfig::SIZE + // tvl_anomaly
+                           CircuitBreaker::SIZE + // circuit_breaker
+                           1 +  // is_paused
+                           32 + // fee_vault
+                           8 +  // fees_collected
+                           8 +  // fees_withdrawn
+                           8 +  // pending_protocol_fee
+                           8 +  // protocol_fee_effective_at
+                           8 +  // change_count
+                           32 + // pending_authority
+                           32 + // authority_multisig
+                           1 +  // bump
+                           1 +  // version
+                           32 * MAX_CLAIM_RESOLVERS + // claim_resolvers
+                           1;   // claim_resolver_count
+    
+    pub fn is_claim_resolver(&self, key: &Pubkey) -> bool {
+        self.claim_resolvers[..self.claim_resolver_count as usize].contains(key)
+    }
 }
 
 #[account]
 pub struct ProtocolRegistry {
-    pub protocol_count: u64,
+    pub registered_count: u64, // Currently registered protocols
+    pub next_index: u64,     // Entries ever written; indexes aren't reused after deregistration
+    pub version: u8,
+    // Version 3
+    pub allowlist_enabled: bool, // Registration needs an admin approval
+    // Version 8
+    pub directory: Pubkey,       // ProtocolDirectory kept current on registration; default when unused
+    // Version 9
+    pub active_count: u64,       // Registered protocols currently selling coverage
 }
 
 impl ProtocolRegistry {
     pub const SIZE: usize = 8 + // discriminator
-                           8;   // protocol_count
+                           8 +  // registered_count
+                           8 +  // next_index
+                           1 +  // version
+                           1 +  // allowlist_enabled
+                           32 + // directory
+                           8;   // active_count
+    
+    // Brings active_count in line with the protocol's status. Call it wherever is_active
+    // may have changed, including automatic suspensions and reactivations.
+    pub fn sync_active(&mut self, protocol_info: &mut ProtocolInfo) -> Result<()> {
+        if protocol_info.is_active && !protocol_info.counted_active {
+            self.active_count = self.active_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        } else if !protocol_info.is_active && protocol_info.counted_active {
+            self.active_count = self.active_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+        protocol_info.counted_active = protocol_info.is_active;
+        
+        Ok(())
+    }
+}
+
+// Per-index pointer to a registered protocol. Clients enumerate the registry by
+// deriving entries 0..next_index and skipping the deregistered ones.
+#[account]
+pub struct ProtocolEntry {
+    pub index: u64,
+    pub protocol: Pubkey,
+    pub authority: Pubkey,
+    pub registered_at: i64,
+    pub is_registered: bool,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ProtocolEntry {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // index
+                           32 +     // protocol
+                           32 +     // authority
+                           8 +      // registered_at
+                           1 +      // is_registered
+                           1 +      // bump
+                           1;       // version
 }
 
 #[account]
@@ -323,7 +1957,44 @@ pub struct ProtocolInfo {
     pub tvl_usd: u64,
     pub risk_score: u8,
     pub is_active: bool,
+    pub last_risk_update: i64,
+    pub premiums_earned: u64,
+    pub claims_paid: u64,
+    pub verified_audit_count: u8,
+    pub last_audit_date: i64,
+    pub safeguard_flags: u8,
+    pub safeguard_assessor: Pubkey, // Firm the protocol engaged to attest its safeguards
+    pub code_risk: u8,
+    pub economic_risk: u8,
+    pub operational_risk: u8,
+    pub incident_started_at: i64, // 0 when there is no confirmed incident
+    pub alert_surcharge_bps: u64,
+    pub surcharging_alerts: u16,  // Unresolved alerts currently adding the surcharge
+    pub alerts_raised: u32,
+    pub alerts_confirmed: u32,
+    pub alerts_false: u32,        // Resolved as unconfirmed
+    pub oracle_dependency: bool,  // Set by the admin through the timelock
+    pub deactivation_reason: DeactivationReason, // None unless deactivated by hand
+    pub deactivated_by: Pubkey,
+    pub active_policies: u32,     // Policies not yet wound down
+    pub is_deregistering: bool,
+    pub registry_index: u64,      // Index of the protocol's ProtocolEntry
+    pub metadata_uri: String,     // Off-chain description of the protocol, e.g. docs or audits
     pub bump: u8,
+    pub version: u8,
+    // Version 5
+    pub fee_override_bps: Option<u64>, // Replaces the global protocol fee for this protocol's policies
+    pub revenue_share_bps: u64,   // Share of the fee paid to the protocol's own treasury
+    pub revenue_share_account: Pubkey, // Token account receiving the revenue share
+    pub fees_generated: u64,      // Fees charged on this protocol's policies, revenue share included
+    pub revenue_shared: u64,
+    // Version 6
+    pub incident_state: IncidentState,
+    pub open_alerts: u32,         // Alerts not yet confirmed, dismissed or expired
+    // Version 9
+    pub counted_active: bool,     // Included in the registry's active_count
+    // Version 17
+    pub pending_authority: Pubkey, // Proposed new authority until it accepts; default key when none
 }
 
 impl ProtocolInfo {
@@ -333,53 +2004,345 @@ impl ProtocolInfo {
                            8 +      // tvl_usd
                            1 +      // risk_score
                            1 +      // is_active
-                           1;       // bump
+                           8 +      // last_risk_update
+                           8 +      // premiums_earned
+                           8 +      // claims_paid
+                           1 +      // verified_audit_count
+                           8 +      // last_audit_date
+                           1 +      // safeguard_flags
+                           32 +     // safeguard_assessor
+                           1 +      // code_risk
+                           1 +      // economic_risk
+                           1 +      // operational_risk
+                           8 +      // incident_started_at
+                           8 +      // alert_surcharge_bps
+                           2 +      // surcharging_alerts
+                           4 +      // alerts_raised
+                           4 +      // alerts_confirmed
+                           4 +      // alerts_false
+                           1 +      // oracle_dependency
+                           1 +      // deactivation_reason
+                           32 +     // deactivated_by
+                           4 +      // active_policies
+                           1 +      // is_deregistering
+                           8 +      // registry_index
+                           100 +    // metadata_uri (max 96 chars + 4 bytes for string length)
+                           1 +      // bump
+                           1 +      // version
+                           9 +      // fee_override_bps
+                           8 +      // revenue_share_bps
+                           32 +     // revenue_share_account
+                           8 +      // fees_generated
+                           8 +      // revenue_shared
+                           1 +      // incident_state
+                           4 +      // open_alerts
+                           1 +      // counted_active
+                           32;      // pending_authority
+    
+    // Component scores are kept so each pool tier can reweight them
+    pub fn set_risk_components(&mut self, code_risk: u8, economic_risk: u8, operational_risk: u8) {
+        self.code_risk = code_risk;
+        self.economic_risk = economic_risk;
+        self.operational_risk = operational_risk;
+    }
+    
+    // Stores a new risk score and deactivates the protocol if it exceeds the maximum.
+    // Returns true when this update deactivated the protocol.
+    pub fn apply_risk_score(&mut self, risk_score: u8, max_insurable_risk_score: u8) -> bool {
+        self.risk_score = risk_score;
+        
+        if self.is_active && risk_score > max_insurable_risk_score {
+            self.suspend();
+            return true;
+        }
+        
+        false
+    }
+    
+    // Automatic suspensions can only be lifted by the admin, even if the
+    // protocol had deactivated itself before
+    pub fn suspend(&mut self) {
+        self.is_active = false;
+        self.deactivated_by = Pubkey::default();
+    }
 }
 
 #[account]
 pub struct Policy {
     pub insured: Pubkey,
     pub protocol: Pubkey,
+    pub pool: Pubkey,
     pub coverage_amount: u64,
     pub premium_amount: u64,
     pub start_time: i64,
     pub end_time: i64,
     pub is_active: bool,
-    pub is_claimed: bool,
+    pub is_claimed: bool,         // Set once any claim on the policy is approved
     pub bump: u8,
+    pub version: u8,
+    // Version 7
+    pub premium_mint: Pubkey,
+    pub premium_decimals: u8,
+    // Version 10
+    pub amount_paid: u64,         // Claim payouts already made against the coverage
+    pub pending_reserved: u64,    // Approved claims reserved in the pool but not yet paid
+    // Version 11
+    pub index: u64,               // The insured's policy counter when it was created; 0 for older policies
+    // Version 12
+    pub premium_escrowed: u64,    // Premium net of fees held in the policy's escrow; 0 for older policies
+    pub premium_released: u64,    // Part of it already earned and moved to the treasury
+    pub premium_refunded: u64,    // Part of it returned to the insured on cancellation
+    // Version 17
+    pub claim_count: u32,         // Claims filed against the policy; the next claim's index
+    pub open_claims: u32,         // Filed claims not yet rejected, paid or vetoed
+    pub coverage_released: u64,   // Coverage approved claims took out of the pool's active coverage
 }
 
 impl Policy {
     pub const SIZE: usize = 8 +     // discriminator
                            32 +     // insured
                            32 +     // protocol
+                           32 +     // pool
                            8 +      // coverage_amount
                            8 +      // premium_amount
                            8 +      // start_time
                            8 +      // end_time
                            1 +      // is_active
                            1 +      // is_claimed
-                           1;       // bump
+                           1 +      // bump
+                           1 +      // version
+                           32 +     // premium_mint
+                           1 +      // premium_decimals
+                           8 +      // amount_paid
+                           8 +      // pending_reserved
+                           8 +      // index
+                           8 +      // premium_escrowed
+                           8 +      // premium_released
+                           8 +      // premium_refunded
+                           4 +      // claim_count
+                           4 +      // open_claims
+                           8;       // coverage_released
+    
+    // Size of version 6 policies, the last before the premium mint was recorded
+    pub const SIZE_BEFORE_PREMIUM_MINT: usize = 8 + 32 * 3 + 8 * 4 + 4;
+    
+    // What can still be claimed: the coverage less what's been paid or set aside
+    pub fn remaining_coverage(&self) -> Result<u64> {
+        self.coverage_amount
+            .checked_sub(self.amount_paid)
+            .and_then(|remaining| remaining.checked_sub(self.pending_reserved))
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+}
+
+// Per-insured count of policies bought. Policies are created at an address that includes
+// the count, so nobody can occupy an insured's next policy address ahead of them and the
+// same protocol can be covered again once a policy ends. Policies from before the counter
+// keep their original address without the index.
+#[account]
+pub struct PolicyCounter {
+    pub insured: Pubkey,
+    pub next_index: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl PolicyCounter {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // insured
+                           8 +      // next_index
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct ProtocolInitialized {
+    pub authority: Pubkey,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct ProtocolRegistered {
+    pub protocol: Pubkey,
+    pub authority: Pubkey,
+    pub registry_index: u64,
+    pub protocol_name: String,
+    pub tvl_usd: u64,
+}
+
+#[event]
+pub struct PolicyCreated {
+    pub policy: Pubkey,
+    pub insured: Pubkey,
+    pub protocol: Pubkey,
+    pub pool: Pubkey,
+    pub coverage_amount: u64,
+    pub premium_amount: u64,
+    pub fee_amount: u64,
+    pub revenue_share_amount: u64, // Part of the fee paid to the protocol's treasury
+    pub staker_discount_bps: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+// Emitted for every risk score update, whichever source it came from
+#[event]
+pub struct ProtocolRiskUpdated {
+    pub protocol: Pubkey,
+    pub updated_by: Pubkey,
+    pub risk_score: u8,
+    pub code_risk: u8,
+    pub economic_risk: u8,
+    pub operational_risk: u8,
+    pub loss_experience: u8,
+}
+
+#[event]
+pub struct ProtocolAutoDeactivated {
+    pub protocol: Pubkey,
+    pub risk_score: u8,
+    pub max_insurable_risk_score: u8,
+}
+
+#[event]
+pub struct ProtocolPaused {
+    pub authority: Pubkey,
+    pub paused_at: i64,
+}
+
+#[event]
+pub struct ProtocolUnpaused {
+    pub authority: Pubkey,
+    pub unpaused_at: i64,
+}
+
+#[event]
+pub struct ProtocolReactivated {
+    pub protocol: Pubkey,
+    pub risk_score: u8,
+    pub reactivated_by: Pubkey,
+}
+
+#[event]
+pub struct ProtocolInfoUpdated {
+    pub protocol: Pubkey,
+    pub authority: Pubkey,
+    pub protocol_name: String,
+    pub metadata_uri: String,
+}
+
+#[event]
+pub struct ProtocolAuthorityTransferProposed {
+    pub protocol: Pubkey,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct ProtocolAuthorityTransferred {
+    pub protocol: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct ProtocolDeactivated {
+    pub protocol: Pubkey,
+    pub deactivated_by: Pubkey,
+    pub reason: DeactivationReason,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeactivationReason {
+    None,
+    RiskDeterioration,
+    SuspectedExploit,
+    ProtocolRequest,
+    Sunset,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PremiumCurvePoint {
+    pub risk_score: u8,
+    pub rate_bps: u64,
+}
+
+impl PremiumCurvePoint {
+    pub const SIZE: usize = 1 +     // risk_score
+                           8;       // rate_bps
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RiskWeights {
+    pub code: u8,
+    pub economic: u8,
+    pub operational: u8,
+    pub loss_experience: u8,
+}
+
+impl RiskWeights {
+    pub const SIZE: usize = 1 +     // code
+                           1 +      // economic
+                           1 +      // operational
+                           1;       // loss_experience
+    
+    pub fn is_valid(&self) -> bool {
+        self.code as u16 + self.economic as u16 + self.operational as u16 + self.loss_experience as u16 == 100
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CodeRiskParams {
-    pub audit_count: u8,
     pub bug_bounty_size: u64,
     pub complexity_score: u8,
 }
 
+impl CodeRiskParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.complexity_score <= MAX_RISK_INPUT_SCORE,
+            ErrorCode::InvalidComplexityScore
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct EconomicRiskParams {
     pub liquidity_depth: u64,
     pub concentration_risk: u8,
 }
 
+impl EconomicRiskParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.concentration_risk <= MAX_RISK_INPUT_SCORE,
+            ErrorCode::InvalidConcentrationRisk
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct OperationalRiskParams {
     pub governance_count: u8,
     pub admin_count: u8,
-    pub oracle_dependency: bool,
+}
+
+impl OperationalRiskParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.governance_count <= MAX_GOVERNANCE_COUNT,
+            ErrorCode::InvalidGovernanceCount
+        );
+        // Every protocol has at least one key able to administer it
+        require!(
+            self.admin_count > 0 && self.admin_count <= MAX_ADMIN_COUNT,
+            ErrorCode::InvalidAdminCount
+        );
+        Ok(())
+    }
 }
 
 #[error_code]
@@ -414,4 +2377,390 @@ pub enum ErrorCode {
     InvalidAnomalyType,
     #[msg("Invalid severity")]
     InvalidSeverity,
+    #[msg("Risk oracle is not set")]
+    RiskOracleNotSet,
+    #[msg("Missing Ed25519 signature verification instruction")]
+    MissingEd25519Instruction,
+    #[msg("Invalid Ed25519 signature verification instruction")]
+    InvalidEd25519Instruction,
+    #[msg("Attestation was not signed by the risk oracle")]
+    AttestationSignerMismatch,
+    #[msg("Signed message does not match the attestation")]
+    AttestationMessageMismatch,
+    #[msg("Attestation is for a different protocol")]
+    AttestationProtocolMismatch,
+    #[msg("Stale risk attestation")]
+    StaleRiskAttestation,
+    #[msg("Invalid risk score")]
+    InvalidRiskScore,
+    #[msg("Risk score is above the maximum insurable risk score")]
+    RiskScoreAboveMaximum,
+    #[msg("Invalid premium curve")]
+    InvalidPremiumCurve,
+    #[msg("Auditor name is too long")]
+    AuditorNameTooLong,
+    #[msg("Audit scope is too long")]
+    AuditScopeTooLong,
+    #[msg("Auditor is not whitelisted")]
+    AuditorNotWhitelisted,
+    #[msg("Invalid audit date")]
+    InvalidAuditDate,
+    #[msg("Invalid committee size")]
+    InvalidCommitteeSize,
+    #[msg("Invalid committee quorum")]
+    InvalidCommitteeQuorum,
+    #[msg("Duplicate committee member")]
+    DuplicateCommitteeMember,
+    #[msg("Signer is not a committee member")]
+    NotCommitteeMember,
+    #[msg("Invalid safeguard discount")]
+    InvalidSafeguardDiscount,
+    #[msg("Invalid safeguard flags")]
+    InvalidSafeguardFlags,
+    #[msg("Complexity score must be between 0 and 100")]
+    InvalidComplexityScore,
+    #[msg("Concentration risk must be between 0 and 100")]
+    InvalidConcentrationRisk,
+    #[msg("Governance count is out of range")]
+    InvalidGovernanceCount,
+    #[msg("Admin count is out of range")]
+    InvalidAdminCount,
+    #[msg("Risk weights must sum to 100")]
+    InvalidRiskWeights,
+    #[msg("Protocol risk is above the pool tier's maximum")]
+    ProtocolNotAcceptedByPool,
+    #[msg("Capital pool does not back this policy")]
+    PolicyPoolMismatch,
+    #[msg("Invalid loss scenario")]
+    InvalidLossScenario,
+    #[msg("Invalid capital pool in stress test")]
+    InvalidStressTestPool,
+    #[msg("Watcher does not hold the minimum stake")]
+    WatcherNotStaked,
+    #[msg("Insufficient watcher stake")]
+    InsufficientWatcherStake,
+    #[msg("Exploit alert is already resolved")]
+    AlertAlreadyResolved,
+    #[msg("Policy was purchased after the incident started")]
+    PolicyPurchasedDuringIncident,
+    #[msg("Invalid alert response config")]
+    InvalidAlertResponseConfig,
+    #[msg("Critical alerts require the affected pool")]
+    AffectedPoolRequired,
+    #[msg("Pool is not the alert's affected pool")]
+    AffectedPoolMismatch,
+    #[msg("Pool withdrawals are paused")]
+    PoolWithdrawalsPaused,
+    #[msg("Exploit alert is not confirmed")]
+    AlertNotConfirmed,
+    #[msg("Alert bounty already claimed")]
+    BountyAlreadyClaimed,
+    #[msg("No alert bounty configured")]
+    NoBountyConfigured,
+    #[msg("Invalid slash percentage")]
+    InvalidSlashBps,
+    #[msg("A confirmed alert cannot be malicious")]
+    InvalidAlertResolution,
+    #[msg("Exploit alert has not expired yet")]
+    AlertNotExpired,
+    #[msg("Invalid TVL anomaly configuration")]
+    InvalidTvlAnomalyConfig,
+    #[msg("Reporting watcher account required")]
+    ReporterWatcherRequired,
+    #[msg("Invalid alert confirmation threshold")]
+    InvalidConfirmationThreshold,
+    #[msg("Exploit alert is already active")]
+    AlertAlreadyActive,
+    #[msg("Watcher already confirmed this alert")]
+    AlertAlreadyConfirmedByWatcher,
+    #[msg("Policy was not in force when the loss event began")]
+    PolicyNotCoveringLossEvent,
+    #[msg("Loss event is for a different protocol")]
+    LossEventProtocolMismatch,
+    #[msg("Exploit alert does not require acknowledgement")]
+    AcknowledgementNotRequired,
+    #[msg("Exploit alert already acknowledged")]
+    AlertAlreadyAcknowledged,
+    #[msg("Acknowledgement deadline has passed")]
+    AcknowledgementDeadlinePassed,
+    #[msg("Acknowledgement deadline has not passed yet")]
+    AcknowledgementDeadlineNotPassed,
+    #[msg("Invalid watcher rate limit")]
+    InvalidWatcherRateLimit,
+    #[msg("Watcher has raised too many alerts in the current window")]
+    WatcherRateLimited,
+    #[msg("Invalid heartbeat configuration")]
+    InvalidHeartbeatConfig,
+    #[msg("Heartbeats have not been missed")]
+    HeartbeatNotMissed,
+    #[msg("Missed heartbeat alert already raised")]
+    HeartbeatAlertAlreadyRaised,
+    #[msg("Invalid price feed configuration")]
+    InvalidPriceFeedConfig,
+    #[msg("Protocol does not depend on price oracles")]
+    ProtocolNotOracleDependent,
+    #[msg("Details URI too long")]
+    DetailsUriTooLong,
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreakerConfig,
+    #[msg("Global circuit breaker is not tripped")]
+    CircuitBreakerNotTripped,
+    #[msg("Global circuit breaker is tripped")]
+    CircuitBreakerTripped,
+    #[msg("Exploit alert is not resolved")]
+    AlertNotResolved,
+    #[msg("Invalid alert subscription account")]
+    InvalidAlertSubscription,
+    #[msg("New severity must be higher than the current one")]
+    SeverityNotUpgraded,
+    #[msg("A severity change needs a reason of at most 200 characters")]
+    InvalidSeverityReason,
+    #[msg("Invalid trusted watcher configuration")]
+    InvalidTrustedWatcherConfig,
+    #[msg("Bonded alerts are disabled")]
+    BondedAlertsDisabled,
+    #[msg("Alert has no outstanding bond to claim")]
+    AlertBondNotClaimable,
+    #[msg("Alert evidence exceeds the maximum number of transactions or accounts")]
+    TooMuchAlertEvidence,
+    #[msg("Evidence is already recorded on the alert")]
+    DuplicateAlertEvidence,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Protocol is not paused")]
+    ProtocolNotPaused,
+    #[msg("Fee vault must be owned by the protocol state and match the configured vault")]
+    InvalidFeeVault,
+    #[msg("A fee vault is required while a protocol fee is charged")]
+    FeeVaultRequired,
+    #[msg("Amount exceeds the uncollected protocol fees")]
+    InsufficientProtocolFees,
+    #[msg("Protocol fee exceeds the maximum of 10%")]
+    ProtocolFeeTooHigh,
+    #[msg("No protocol fee change is scheduled")]
+    NoPendingProtocolFee,
+    #[msg("Timelock has not elapsed yet")]
+    TimelockNotElapsed,
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    #[msg("Governance stake is locked until the votes it was used in close")]
+    GovernanceStakeLocked,
+    #[msg("Insufficient governance stake")]
+    InsufficientGovernanceStake,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Voting on this proposal is still open")]
+    VotingNotClosed,
+    #[msg("Proposal did not pass")]
+    ProposalNotPassed,
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Account targeted by the proposal is missing or doesn't match")]
+    ProposalAccountMissing,
+    #[msg("Change has already been executed or cancelled")]
+    ChangeNotPending,
+    #[msg("Account targeted by the change is missing or doesn't match")]
+    ChangeAccountMissing,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Authority is not a vault of the given multisig")]
+    InvalidMultisigAuthority,
+    #[msg("A deactivation reason is required")]
+    InvalidDeactivationReason,
+    #[msg("Protocol is being deregistered")]
+    ProtocolDeregistering,
+    #[msg("Protocol is not being deregistered")]
+    ProtocolNotDeregistering,
+    #[msg("Protocol has a confirmed incident")]
+    ProtocolHasOpenIncident,
+    #[msg("Policy is still in force")]
+    PolicyStillInForce,
+    #[msg("Policy has a claim that hasn't been settled")]
+    ClaimPending,
+    #[msg("Protocol still has policies that haven't been wound down")]
+    ProtocolHasActivePolicies,
+    #[msg("Protocol name is too long")]
+    ProtocolNameTooLong,
+    #[msg("Metadata URI is too long")]
+    MetadataUriTooLong,
+    #[msg("Invalid treasury configuration")]
+    InvalidTreasuryConfig,
+    #[msg("Token account is not the treasury vault for this mint")]
+    InvalidTreasuryVault,
+    #[msg("Withdrawal exceeds the treasury spending limit for this window")]
+    TreasurySpendLimitExceeded,
+    #[msg("Claim is not approved")]
+    ClaimNotApproved,
+    #[msg("Claim is still in its challenge window")]
+    ChallengeWindowOpen,
+    #[msg("Claim's challenge window has closed")]
+    ChallengeWindowClosed,
+    #[msg("Payouts from this pool are frozen")]
+    PoolPayoutsFrozen,
+    #[msg("Invalid emergency council size")]
+    InvalidCouncilSize,
+    #[msg("A reason of at most 200 characters is required")]
+    InvalidCouncilReason,
+    #[msg("Signer is not an emergency council member")]
+    NotCouncilMember,
+    #[msg("Invalid config")]
+    InvalidConfig,
+    #[msg("Policy duration is outside the allowed range")]
+    InvalidPolicyDuration,
+    #[msg("Policy is still in its claim waiting period")]
+    ClaimWaitingPeriod,
+    #[msg("Account can't be migrated")]
+    InvalidMigration,
+    #[msg("Account is already on the current version")]
+    AlreadyMigrated,
+    #[msg("Claim resolver set is full")]
+    ClaimResolverSetFull,
+    #[msg("Claim resolver is already registered")]
+    ClaimResolverAlreadyRegistered,
+    #[msg("Claim resolver is not registered")]
+    ClaimResolverNotRegistered,
+    #[msg("Account is still within its retention period")]
+    RetentionPeriodNotOver,
+    #[msg("Account required for cleanup is missing")]
+    CleanupAccountMissing,
+    #[msg("Capital provider still holds capital or rewards")]
+    ProviderNotEmpty,
+    #[msg("Protocol registration hasn't been approved")]
+    RegistrationNotApproved,
+    #[msg("Feature is disabled")]
+    FeatureDisabled,
+    #[msg("Invalid session key permissions or expiry")]
+    InvalidSessionKey,
+    #[msg("Session key has expired")]
+    SessionKeyExpired,
+    #[msg("Session key doesn't cover this action")]
+    SessionKeyNotPermitted,
+    #[msg("Invalid revenue share")]
+    InvalidRevenueShare,
+    #[msg("Revenue share token account is required")]
+    RevenueShareAccountRequired,
+    #[msg("Revenue share token account doesn't match the protocol's")]
+    InvalidRevenueShareAccount,
+    #[msg("Incident state transition not allowed")]
+    InvalidIncidentTransition,
+    #[msg("Pass exactly one of the token and SOL payout accounts")]
+    InvalidPayoutAccount,
+    #[msg("SOL payouts are only possible from a wrapped SOL account")]
+    NotNativeMint,
+    #[msg("Not a fully verified Pyth price update")]
+    InvalidPriceUpdate,
+    #[msg("Price update is for a different feed")]
+    PriceFeedMismatch,
+    #[msg("Price update is too old")]
+    StalePriceUpdate,
+    #[msg("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Not a verified Wormhole VAA")]
+    InvalidVaa,
+    #[msg("VAA was emitted by a different contract")]
+    VaaEmitterMismatch,
+    #[msg("Invalid loss proof")]
+    InvalidLossProof,
+    #[msg("Swap route must go through Jupiter")]
+    InvalidSwapProgram,
+    #[msg("Swap returned less than the minimum amount out")]
+    SwapSlippageExceeded,
+    #[msg("Not an SPL stake pool")]
+    InvalidStakePool,
+    #[msg("Stake pool hasn't been updated this epoch")]
+    StaleStakePool,
+    #[msg("Invalid LST depeg configuration")]
+    InvalidLstDepegConfig,
+    #[msg("LST is within its depeg threshold")]
+    LstNotDepegged,
+    #[msg("Account required to pay the keeper reward is missing")]
+    KeeperAccountMissing,
+    #[msg("Invalid TVL adapter configuration")]
+    InvalidTvlAdapter,
+    #[msg("Accounts don't match the TVL adapter's configuration")]
+    TvlAdapterAccountMismatch,
+    #[msg("Not a governance account of the realm")]
+    InvalidRealmsGovernance,
+    #[msg("Approving a claim this large takes a claims committee vote")]
+    ClaimNeedsCommitteeVote,
+    #[msg("Quoted premium is above the maximum premium")]
+    QuoteAboveMaxPremium,
+    #[msg("Snapshots can only be taken at the current slot")]
+    SnapshotSlotMismatch,
+    #[msg("Invalid number of accounts to snapshot")]
+    InvalidSnapshotAccounts,
+    #[msg("Not a readable vote account")]
+    InvalidVoteAccount,
+    #[msg("Invalid validator cover configuration")]
+    InvalidValidatorCover,
+    #[msg("Validator earned enough vote credits in the epoch")]
+    ValidatorNotDown,
+    #[msg("Policy is not for this protocol")]
+    PolicyProtocolMismatch,
+    #[msg("Capital pool accounting invariant violated")]
+    PoolInvariantViolated,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Claim evidence is too long")]
+    EvidenceTooLong,
+    #[msg("Resolution notes are too long")]
+    ResolutionNotesTooLong,
+    #[msg("Protocol directory is already set")]
+    ProtocolDirectoryAlreadySet,
+    #[msg("Protocol directory must be passed to keep it current")]
+    ProtocolDirectoryRequired,
+    #[msg("Account is not the registry's protocol directory")]
+    InvalidProtocolDirectory,
+    #[msg("Protocol info doesn't match the registry entry")]
+    DirectoryEntryMismatch,
+    #[msg("Capital position still holds capital")]
+    PositionNotEmpty,
+    #[msg("Token account is not the capital pool's vault")]
+    InvalidPoolTokenAccount,
+    #[msg("Coverage amount must be greater than zero")]
+    ZeroCoverageAmount,
+    #[msg("Premium amount must be greater than zero")]
+    ZeroPremiumAmount,
+    #[msg("Oracle guards are invalid")]
+    InvalidOracleGuards,
+    #[msg("No room for another oracle feed override")]
+    OracleFeedOverridesFull,
+    #[msg("Pool vault holds less than its accounting; sync the pool balance")]
+    PoolBalanceShortfall,
+    #[msg("Policy's premium escrow must be passed")]
+    PremiumEscrowRequired,
+    #[msg("Policy still has premium in escrow")]
+    PremiumStillEscrowed,
+    #[msg("Protocol registry counts are inconsistent")]
+    RegistryInvariantViolated,
+    #[msg("Invalid pool payout limits")]
+    InvalidPayoutLimits,
+    #[msg("Claim exceeds the pool's per-claim payout maximum")]
+    ClaimExceedsPoolPayoutMax,
+    #[msg("Payout would exceed the pool's daily payout cap")]
+    DailyPayoutCapExceeded,
+    #[msg("Mint is not in the mint registry")]
+    MintNotRegistered,
+    #[msg("Mint is not accepted for new business")]
+    MintNotAccepted,
+    #[msg("Mint's decimals don't match the mint registry")]
+    MintDecimalsMismatch,
+    #[msg("Mint registry is full")]
+    MintRegistryFull,
+    #[msg("Amount exceeds the mint's cap")]
+    MintCapExceeded,
+    #[msg("Watcher has alerts that are still open")]
+    WatcherHasOpenAlerts,
+    #[msg("Treasury doesn't hold that many lamports above its rent")]
+    InsufficientTreasuryLamports,
+    #[msg("Change loosens a setting and must go through the timelock")]
+    ChangeRequiresTimelock,
+    #[msg("Not enough emergency council members signed")]
+    CouncilQuorumNotMet,
+    #[msg("Prices must be attested by two different staked watchers, neither the admin nor the protocol")]
+    PriceAttestersNotIndependent,
+    #[msg("Assessor is not the one the protocol engaged")]
+    AssessorNotEngaged,
 }
\ No newline at end of file
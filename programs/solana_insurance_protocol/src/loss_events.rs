@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PARAMETRIC_CLAIMS};
+use crate::exploit_detection::ExploitAlert;
+use crate::mint_registry::MintRegistry;
+use crate::{ProtocolState, ErrorCode};
+
+// Confirmed incident that claims can reference instead of proving the loss from scratch
+#[account]
+pub struct LossEvent {
+    pub alert: Pubkey, // Posted VAA or LST depeg trigger for losses not raised as alerts
+    pub protocol: Pubkey,
+    pub affected_mint: Pubkey,
+    pub incident_start: i64,
+    pub incident_end: i64,
+    pub created_by: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl LossEvent {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // alert
+                           32 +     // protocol
+                           32 +     // affected_mint
+                           8 +      // incident_start
+                           8 +      // incident_end
+                           32 +     // created_by
+                           1 +      // bump
+                           1;       // version
+    
+    // Whether coverage running from `start_time` to `end_time` was in force when the incident began
+    pub fn covers(&self, start_time: i64, end_time: i64) -> bool {
+        start_time < self.incident_start && end_time > self.incident_start
+    }
+}
+
+#[event]
+pub struct LossEventCreated {
+    pub loss_event: Pubkey,
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub affected_mint: Pubkey,
+    pub incident_start: i64,
+}
+
+pub fn create_loss_event(ctx: Context<CreateLossEvent>) -> Result<()> {
+    let exploit_alert = &ctx.accounts.exploit_alert;
+    let loss_event = &mut ctx.accounts.loss_event;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.config.is_enabled(FEATURE_PARAMETRIC_CLAIMS),
+        ErrorCode::FeatureDisabled
+    );
+    require!(exploit_alert.is_confirmed, ErrorCode::AlertNotConfirmed);
+    // Only a listed token can be one the protocol's policies and pools are held in
+    ctx.accounts.mint_registry.registered(&ctx.accounts.affected_mint)?;
+    
+    loss_event.alert = exploit_alert.key();
+    loss_event.protocol = exploit_alert.protocol;
+    loss_event.affected_mint = ctx.accounts.affected_mint.key();
+    loss_event.incident_start = exploit_alert.alert_time;
+    loss_event.incident_end = clock.unix_timestamp;
+    loss_event.created_by = ctx.accounts.authority.key();
+    loss_event.bump = ctx.bumps.loss_event;
+    loss_event.version = ACCOUNT_VERSION;
+    
+    emit!(LossEventCreated {
+        loss_event: loss_event.key(),
+        alert: loss_event.alert,
+        protocol: loss_event.protocol,
+        affected_mint: loss_event.affected_mint,
+        incident_start: loss_event.incident_start,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateLossEvent<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub exploit_alert: Account<'info, ExploitAlert>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = LossEvent::SIZE,
+        seeds = [b"loss-event", exploit_alert.key().as_ref()],
+        bump
+    )]
+    pub loss_event: Account<'info, LossEvent>,
+    
+    pub affected_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+    
+    pub system_program: Program<'info, System>,
+}
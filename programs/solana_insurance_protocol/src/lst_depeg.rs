@@ -0,0 +1,312 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use anchor_spl::token::spl_token;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PARAMETRIC_CLAIMS};
+use crate::loss_events::{LossEvent, LossEventCreated};
+use crate::mint_registry::MintRegistry;
+use crate::oracle_guards::{load_guarded_price, OracleConfig};
+use crate::{ProtocolInfo, ProtocolState, ErrorCode};
+
+// SPL stake pool program, which jitoSOL and most other LSTs are issued from
+pub const SPL_STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGJTSJJVEy3fsmbqTXv9UZvBs");
+
+// Exchange rates are lamports per whole pool token, at this precision
+pub const EXCHANGE_RATE_PRECISION: u128 = 1_000_000_000;
+
+// Offsets into the stake pool program's StakePool account
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+const POOL_MINT_OFFSET: usize = 162;
+const TOTAL_LAMPORTS_OFFSET: usize = 258;
+const POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+const LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+
+// Depeg trigger for a protocol that is a liquid staking token. Policies on that protocol
+// are depeg cover: they pay out when the stake pool's exchange rate falls from its peak,
+// or the LST trades below it, by more than the threshold.
+#[account]
+pub struct LstDepegTrigger {
+    pub protocol: Pubkey,
+    pub stake_pool: Pubkey,
+    pub lst_mint: Pubkey,
+    pub lst_price_feed: [u8; 32], // Pyth LST/USD feed id
+    pub sol_price_feed: [u8; 32], // Pyth SOL/USD feed id
+    pub threshold_bps: u64,
+    pub peak_exchange_rate: u64,
+    pub trigger_count: u32,
+    pub last_triggered_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl LstDepegTrigger {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // stake_pool
+                           32 +     // lst_mint
+                           32 +     // lst_price_feed
+                           32 +     // sol_price_feed
+                           8 +      // threshold_bps
+                           8 +      // peak_exchange_rate
+                           4 +      // trigger_count
+                           8 +      // last_triggered_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct LstDepegTriggered {
+    pub protocol: Pubkey,
+    pub loss_event: Pubkey,
+    pub exchange_rate: u64,
+    pub peak_exchange_rate: u64,
+    pub market_rate: u64,
+}
+
+pub struct StakePoolRate {
+    pub pool_mint: Pubkey,
+    pub exchange_rate: u64,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+// Reads the pool's exchange rate, which is only current once the pool has been
+// updated this epoch
+pub fn load_stake_pool_rate(stake_pool: &AccountInfo) -> Result<StakePoolRate> {
+    require_keys_eq!(*stake_pool.owner, SPL_STAKE_POOL_PROGRAM_ID, ErrorCode::InvalidStakePool);
+
+    let data = stake_pool.try_borrow_data()?;
+    require!(
+        data.len() >= LAST_UPDATE_EPOCH_OFFSET + 8 && data[0] == STAKE_POOL_ACCOUNT_TYPE,
+        ErrorCode::InvalidStakePool
+    );
+    require!(
+        read_u64(&data, LAST_UPDATE_EPOCH_OFFSET) == Clock::get()?.epoch,
+        ErrorCode::StaleStakePool
+    );
+
+    let total_lamports = read_u64(&data, TOTAL_LAMPORTS_OFFSET);
+    let pool_token_supply = read_u64(&data, POOL_TOKEN_SUPPLY_OFFSET);
+    require!(pool_token_supply > 0, ErrorCode::InvalidStakePool);
+
+    Ok(StakePoolRate {
+        pool_mint: Pubkey::try_from(&data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32]).unwrap(),
+        exchange_rate: (total_lamports as u128 * EXCHANGE_RATE_PRECISION / pool_token_supply as u128) as u64,
+    })
+}
+
+// How far `value` is below `reference`, in bps
+fn drop_bps(reference: u64, value: u64) -> u64 {
+    if reference == 0 || value >= reference {
+        return 0;
+    }
+    ((reference - value) as u128 * 10000 / reference as u128) as u64
+}
+
+pub fn configure_lst_depeg(
+    ctx: Context<ConfigureLstDepeg>,
+    lst_price_feed: [u8; 32],
+    sol_price_feed: [u8; 32],
+    threshold_bps: u64,
+) -> Result<()> {
+    require!(
+        threshold_bps > 0 && threshold_bps <= 10000 && lst_price_feed != sol_price_feed,
+        ErrorCode::InvalidLstDepegConfig
+    );
+
+    let rate = load_stake_pool_rate(&ctx.accounts.stake_pool)?;
+    let mint_registry = &ctx.accounts.mint_registry;
+    mint_registry.check_price_feed(&rate.pool_mint, &lst_price_feed)?;
+    mint_registry.check_price_feed(&spl_token::native_mint::ID, &sol_price_feed)?;
+
+    let trigger = &mut ctx.accounts.lst_depeg_trigger;
+    if trigger.protocol == Pubkey::default() {
+        trigger.protocol = ctx.accounts.protocol_info.key();
+        trigger.trigger_count = 0;
+        trigger.last_triggered_at = 0;
+        trigger.bump = ctx.bumps.lst_depeg_trigger;
+        trigger.version = ACCOUNT_VERSION;
+    }
+    trigger.stake_pool = ctx.accounts.stake_pool.key();
+    trigger.lst_mint = rate.pool_mint;
+    trigger.lst_price_feed = lst_price_feed;
+    trigger.sol_price_feed = sol_price_feed;
+    trigger.threshold_bps = threshold_bps;
+    trigger.peak_exchange_rate = rate.exchange_rate;
+
+    Ok(())
+}
+
+// Permissionless crank that raises the peak rate as staking rewards accrue
+pub fn refresh_lst_exchange_rate(ctx: Context<RefreshLstExchangeRate>) -> Result<()> {
+    let rate = load_stake_pool_rate(&ctx.accounts.stake_pool)?;
+    let trigger = &mut ctx.accounts.lst_depeg_trigger;
+    trigger.peak_exchange_rate = std::cmp::max(trigger.peak_exchange_rate, rate.exchange_rate);
+
+    Ok(())
+}
+
+// Permissionless. Settles a depeg as a loss event that depeg policies claim against.
+pub fn trigger_lst_depeg(ctx: Context<TriggerLstDepeg>) -> Result<()> {
+    let trigger = &mut ctx.accounts.lst_depeg_trigger;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.config.is_enabled(FEATURE_PARAMETRIC_CLAIMS),
+        ErrorCode::FeatureDisabled
+    );
+    // One loss event per depeg rather than one per crank while it lasts
+    require!(
+        clock.unix_timestamp - trigger.last_triggered_at > ctx.accounts.config.params.price_deviation_cooldown_secs,
+        ErrorCode::LstNotDepegged
+    );
+
+    let rate = load_stake_pool_rate(&ctx.accounts.stake_pool)?;
+    let oracle_config = &ctx.accounts.oracle_config;
+    let lst_price = load_guarded_price(oracle_config, &ctx.accounts.lst_price_update, &trigger.lst_price_feed)?;
+    let sol_price = load_guarded_price(oracle_config, &ctx.accounts.sol_price_update, &trigger.sol_price_feed)?;
+
+    // LST price in SOL, at the same precision as the exchange rate
+    let exponent = std::cmp::min(lst_price.exponent, sol_price.exponent);
+    let market_rate = std::cmp::min(
+        lst_price.scaled_to(exponent) as u128 * EXCHANGE_RATE_PRECISION / sol_price.scaled_to(exponent) as u128,
+        u64::MAX as u128
+    ) as u64;
+
+    require!(
+        drop_bps(trigger.peak_exchange_rate, rate.exchange_rate) >= trigger.threshold_bps ||
+        drop_bps(rate.exchange_rate, market_rate) >= trigger.threshold_bps,
+        ErrorCode::LstNotDepegged
+    );
+
+    let loss_event = &mut ctx.accounts.loss_event;
+    loss_event.alert = trigger.key();
+    loss_event.protocol = trigger.protocol;
+    loss_event.affected_mint = trigger.lst_mint;
+    loss_event.incident_start = clock.unix_timestamp;
+    loss_event.incident_end = clock.unix_timestamp;
+    loss_event.created_by = ctx.accounts.payer.key();
+    loss_event.bump = ctx.bumps.loss_event;
+    loss_event.version = ACCOUNT_VERSION;
+
+    trigger.trigger_count = trigger.trigger_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    trigger.last_triggered_at = clock.unix_timestamp;
+
+    // Later depegs are measured from where this one left the pool
+    let peak_exchange_rate = trigger.peak_exchange_rate;
+    trigger.peak_exchange_rate = rate.exchange_rate;
+
+    emit!(LossEventCreated {
+        loss_event: loss_event.key(),
+        alert: loss_event.alert,
+        protocol: loss_event.protocol,
+        affected_mint: loss_event.affected_mint,
+        incident_start: loss_event.incident_start,
+    });
+    emit!(LstDepegTriggered {
+        protocol: trigger.protocol,
+        loss_event: loss_event.key(),
+        exchange_rate: rate.exchange_rate,
+        peak_exchange_rate,
+        market_rate,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureLstDepeg<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = LstDepegTrigger::SIZE,
+        seeds = [b"lst-depeg", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub lst_depeg_trigger: Account<'info, LstDepegTrigger>,
+
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    /// CHECK: owner and layout are validated by load_stake_pool_rate
+    pub stake_pool: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshLstExchangeRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"lst-depeg", lst_depeg_trigger.protocol.as_ref()],
+        bump = lst_depeg_trigger.bump,
+        has_one = stake_pool
+    )]
+    pub lst_depeg_trigger: Account<'info, LstDepegTrigger>,
+
+    /// CHECK: must be the trigger's stake pool; layout is validated by load_stake_pool_rate
+    pub stake_pool: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TriggerLstDepeg<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lst-depeg", lst_depeg_trigger.protocol.as_ref()],
+        bump = lst_depeg_trigger.bump,
+        has_one = stake_pool
+    )]
+    pub lst_depeg_trigger: Account<'info, LstDepegTrigger>,
+
+    /// CHECK: must be the trigger's stake pool; layout is validated by load_stake_pool_rate
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: owner, layout, feed id and freshness are validated by load_guarded_price
+    pub lst_price_update: UncheckedAccount<'info>,
+
+    /// CHECK: owner, layout, feed id and freshness are validated by load_guarded_price
+    pub sol_price_update: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"oracle-config"],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = LossEvent::SIZE,
+        seeds = [b"loss-event", lst_depeg_trigger.key().as_ref(), &lst_depeg_trigger.trigger_count.to_le_bytes()],
+        bump
+    )]
+    pub loss_event: Account<'info, LossEvent>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
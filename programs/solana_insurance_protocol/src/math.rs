@@ -0,0 +1,61 @@
+// Rounding policy for token amounts: whatever is owed to the protocol (premiums) rounds
+// up and whatever is owed to users (yield, refunds) rounds down, so rounding dust always
+// stays with the pools and a small policy can't round down to a free one.
+
+// `value * numerator / denominator`, rounded down; None on overflow or a zero denominator
+pub fn mul_div_floor(value: u64, numerator: u128, denominator: u128) -> Option<u64> {
+    let product = (value as u128).checked_mul(numerator)?;
+    u64::try_from(product.checked_div(denominator)?).ok()
+}
+
+// `value * numerator / denominator`, rounded up; None on overflow or a zero denominator
+pub fn mul_div_ceil(value: u64, numerator: u128, denominator: u128) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = (value as u128).checked_mul(numerator)?;
+    u64::try_from(product.div_ceil(denominator)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_division_rounds_neither_way() {
+        assert_eq!(mul_div_floor(1_000, 3, 3), Some(1_000));
+        assert_eq!(mul_div_ceil(1_000, 3, 3), Some(1_000));
+        assert_eq!(mul_div_floor(0, 7, 3), Some(0));
+        assert_eq!(mul_div_ceil(0, 7, 3), Some(0));
+    }
+
+    #[test]
+    fn remainders_round_down_or_up() {
+        assert_eq!(mul_div_floor(10, 1, 3), Some(3));
+        assert_eq!(mul_div_ceil(10, 1, 3), Some(4));
+        assert_eq!(mul_div_floor(1, 1, 10_000), Some(0));
+        assert_eq!(mul_div_ceil(1, 1, 10_000), Some(1));
+    }
+
+    #[test]
+    fn zero_denominator_is_none() {
+        assert_eq!(mul_div_floor(10, 1, 0), None);
+        assert_eq!(mul_div_ceil(10, 1, 0), None);
+        assert_eq!(mul_div_ceil(0, 1, 0), None);
+    }
+
+    #[test]
+    fn u64_max_operands() {
+        // The product fits in a u128 and the quotient back in a u64
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX as u128, u64::MAX as u128), Some(u64::MAX));
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX as u128, u64::MAX as u128), Some(u64::MAX));
+        assert_eq!(mul_div_floor(u64::MAX, 1, 2), Some(u64::MAX / 2));
+        assert_eq!(mul_div_ceil(u64::MAX, 1, 2), Some(u64::MAX / 2 + 1));
+
+        // A quotient past u64::MAX is None, as is a product past u128::MAX
+        assert_eq!(mul_div_floor(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_ceil(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_floor(u64::MAX, u128::MAX, u128::MAX), None);
+        assert_eq!(mul_div_ceil(u64::MAX, u128::MAX, u128::MAX), None);
+    }
+}
@@ -0,0 +1,414 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token::Mint;
+use crate::config::{Config, ALL_FEATURES, DEFAULT_FEE_RATES};
+use crate::global_stats::GlobalStats;
+use crate::governance::GovernanceStake;
+use crate::incident::IncidentState;
+use crate::treasury::TreasuryVault;
+use crate::watchers::Watcher;
+use crate::{CapitalPool, CapitalProvider, Claim, Policy, ProtocolInfo, ProtocolRegistry, ProtocolState, ErrorCode};
+
+// Layout version written by the current program. Every account's fields up to and
+// including `version` keep their original layout and fields added since are appended
+// after it, so an older account is upgraded by growing it to the new size: the added
+// bytes are zero and deserialize as the new fields' defaults.
+pub const ACCOUNT_VERSION: u8 = 17;
+
+pub trait Versioned {
+    fn version(&self) -> u8;
+    fn set_version(&mut self, version: u8);
+}
+
+impl Versioned for ProtocolState {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for Config {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for ProtocolRegistry {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for ProtocolInfo {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for Policy {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for Claim {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for CapitalPool {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for CapitalProvider {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for Watcher {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for GovernanceStake {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+impl Versioned for GlobalStats {
+    fn version(&self) -> u8 { self.version }
+    fn set_version(&mut self, version: u8) { self.version = version; }
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub new_size: u64,
+}
+
+// The protocol state can't be loaded as-is to check the admin before it's migrated,
+// so the authority is read from the raw data; it's the first field in every layout
+pub fn migrate_protocol_state(ctx: Context<MigrateProtocolState>) -> Result<()> {
+    let account = ctx.accounts.protocol_state.to_account_info();
+    require!(account.owner == &crate::ID, ErrorCode::InvalidMigration);
+    let authority = {
+        let data = account.data.borrow();
+        require!(data.len() >= 8 + 32, ErrorCode::InvalidMigration);
+        Pubkey::try_from(&data[8..8 + 32]).unwrap()
+    };
+    require!(authority == ctx.accounts.authority.key(), ErrorCode::UnauthorizedAccess);
+
+    migrate::<ProtocolState>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        ProtocolState::SIZE,
+    )
+}
+
+// Configs from before feature flags keep every feature on, and ones from before fee rates
+// take the rates that used to be built in
+pub fn migrate_config(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let previous_size = account.data_len();
+    migrate::<Config>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Config::SIZE,
+    )?;
+
+    if previous_size < Config::SIZE {
+        let mut config = Config::try_deserialize(&mut &account.data.borrow()[..])?;
+        if previous_size < Config::SIZE_BEFORE_FEE_RATES {
+            config.features = ALL_FEATURES;
+        }
+        config.fee_rates = DEFAULT_FEE_RATES;
+        config.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+pub fn migrate_protocol_registry(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate::<ProtocolRegistry>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        ProtocolRegistry::SIZE,
+    )
+}
+
+// Protocols from before incident tracking start out Confirmed if they have an open incident
+pub fn migrate_protocol_info(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let previous_size = account.data_len();
+    migrate::<ProtocolInfo>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        ProtocolInfo::SIZE,
+    )?;
+
+    if previous_size < ProtocolInfo::SIZE {
+        let mut protocol_info = ProtocolInfo::try_deserialize(&mut &account.data.borrow()[..])?;
+        if protocol_info.incident_started_at != 0 {
+            protocol_info.incident_state = IncidentState::Confirmed;
+            protocol_info.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+        }
+    }
+
+    Ok(())
+}
+
+// Policies from before the premium mint was recorded paid their premium into a treasury
+// vault, which has to be passed so they take its mint; their refunds are checked against
+// it. Claimed ones released their whole coverage when the claim was approved.
+pub fn migrate_policy(ctx: Context<MigratePolicy>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let previous_size = account.data_len();
+    migrate::<Policy>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Policy::SIZE,
+    )?;
+
+    if previous_size < Policy::SIZE {
+        let mut policy = Policy::try_deserialize(&mut &account.data.borrow()[..])?;
+        if previous_size <= Policy::SIZE_BEFORE_PREMIUM_MINT {
+            let (Some(treasury_vault), Some(premium_mint)) = (&ctx.accounts.treasury_vault, &ctx.accounts.premium_mint) else {
+                return err!(ErrorCode::InvalidMigration);
+            };
+            require_keys_eq!(premium_mint.key(), treasury_vault.mint, ErrorCode::InvalidMigration);
+            policy.premium_mint = premium_mint.key();
+            policy.premium_decimals = premium_mint.decimals;
+        }
+        // A claimed policy's whole coverage was released when its claim was approved
+        if policy.is_claimed {
+            policy.coverage_released = policy.coverage_amount;
+        }
+        policy.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+pub fn migrate_claim(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate::<Claim>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Claim::SIZE,
+    )
+}
+
+pub fn migrate_capital_pool(ctx: Context<MigrateAccountWithMint>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let previous_size = account.data_len();
+    migrate::<CapitalPool>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        CapitalPool::SIZE,
+    )?;
+
+    if previous_size < CapitalPool::SIZE {
+        let mut capital_pool = CapitalPool::try_deserialize(&mut &account.data.borrow()[..])?;
+        require!(capital_pool.token_mint == ctx.accounts.mint.key(), ErrorCode::InvalidMigration);
+        capital_pool.token_decimals = ctx.accounts.mint.decimals;
+        capital_pool.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+pub fn migrate_capital_provider(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate::<CapitalProvider>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        CapitalProvider::SIZE,
+    )
+}
+
+pub fn migrate_watcher(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate::<Watcher>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Watcher::SIZE,
+    )
+}
+
+// Stakes from before staked_at was recorded read it as zero, so they count as held all along
+pub fn migrate_governance_stake(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate::<GovernanceStake>(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        GovernanceStake::SIZE,
+    )
+}
+
+// The old totals summed every token together and can't be split, so per-token premiums
+// and claims start from zero. Active coverage is rebuilt from the capital pools, passed
+// as remaining accounts, so the coverage invariant holds for policies already sold.
+pub fn migrate_global_stats(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let previous_size = account.data_len();
+    migrate::<GlobalStats>(
+        &account,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        GlobalStats::SIZE,
+    )?;
+
+    if previous_size < GlobalStats::SIZE {
+        let mut global_stats = GlobalStats::try_deserialize(&mut &account.data.borrow()[..])?;
+        let mut seen_pools: Vec<Pubkey> = Vec::new();
+        for pool_info in ctx.remaining_accounts {
+            require!(pool_info.owner == &crate::ID && !seen_pools.contains(pool_info.key), ErrorCode::InvalidMigration);
+            let pool = CapitalPool::try_deserialize(&mut &pool_info.data.borrow()[..])?;
+            let (pool_address, _) = Pubkey::find_program_address(&[b"capital-pool", &[pool.pool_type as u8]], &crate::ID);
+            require!(pool_address == *pool_info.key, ErrorCode::InvalidMigration);
+            seen_pools.push(*pool_info.key);
+
+            let totals = global_stats.mint_totals_mut(&pool.token_mint)?;
+            totals.active_coverage = totals.active_coverage.checked_add(pool.active_coverage).ok_or(ErrorCode::MathOverflow)?;
+        }
+        global_stats.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+    }
+
+    Ok(())
+}
+
+// Grows the account to `size`, topping up its rent from the authority, and rewrites
+// it in the current layout
+fn migrate<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    size: usize,
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Versioned,
+{
+    require!(account.owner == &T::owner(), ErrorCode::InvalidMigration);
+
+    if account.data_len() < size {
+        let rent_due = Rent::get()?.minimum_balance(size).saturating_sub(account.lamports());
+        if rent_due > 0 {
+            let cpi_accounts = Transfer {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            };
+            let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, rent_due)?;
+        }
+        account.realloc(size, true)?;
+    }
+
+    // Also checks the discriminator, so only accounts of type T can be migrated
+    let mut data = T::try_deserialize(&mut &account.data.borrow()[..])?;
+    let from_version = data.version();
+    require!(from_version < ACCOUNT_VERSION, ErrorCode::AlreadyMigrated);
+
+    data.set_version(ACCOUNT_VERSION);
+    data.try_serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        from_version,
+        to_version: ACCOUNT_VERSION,
+        new_size: account.data_len() as u64,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: May still be in an older layout that doesn't deserialize; the owner
+    /// and discriminator are checked in the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: May still be in an older layout that doesn't deserialize; the owner
+    /// and discriminator are checked in the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    // Only needed for policies from before the premium mint was recorded: the vault
+    // their premium was paid into, and its mint
+    #[account(
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Option<Account<'info, TreasuryVault>>,
+
+    pub premium_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// For layouts that added fields derived from a token mint
+#[derive(Accounts)]
+pub struct MigrateAccountWithMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: May still be in an older layout that doesn't deserialize; the owner
+    /// and discriminator are checked in the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocolState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Loaded by hand in the handler since it may still be in an older layout
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump
+    )]
+    pub protocol_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
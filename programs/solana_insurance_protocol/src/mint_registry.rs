@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolState, ErrorCode};
+
+// Tokens the registry can list
+pub const MAX_REGISTERED_MINTS: usize = 16;
+
+// How the protocol handles one token. Caps of zero are off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub price_feed_id: [u8; 32],   // Pyth feed pricing the token everywhere it is valued; zero for none
+    pub max_policy_coverage: u64,  // Largest coverage a single policy can buy in the token
+    pub max_pool_capital: u64,     // Most capital a pool in the token can hold
+    pub min_premium: u64,          // Smallest premium a policy can pay in the token
+    pub is_accepted: bool,         // Delisted tokens stop taking new business but still pay out
+}
+
+impl MintConfig {
+    pub const SIZE: usize = 32 +    // mint
+                           1 +      // decimals
+                           32 +     // price_feed_id
+                           8 +      // max_policy_coverage
+                           8 +      // max_pool_capital
+                           8 +      // min_premium
+                           1;       // is_accepted
+
+    pub fn check_policy_coverage(&self, coverage_amount: u64) -> Result<()> {
+        require!(
+            self.max_policy_coverage == 0 || coverage_amount <= self.max_policy_coverage,
+            ErrorCode::MintCapExceeded
+        );
+        Ok(())
+    }
+
+    pub fn check_pool_capital(&self, total_capital: u64) -> Result<()> {
+        require!(
+            self.max_pool_capital == 0 || total_capital <= self.max_pool_capital,
+            ErrorCode::MintCapExceeded
+        );
+        Ok(())
+    }
+}
+
+const EMPTY_MINT_CONFIG: MintConfig = MintConfig {
+    mint: Pubkey::new_from_array([0; 32]),
+    decimals: 0,
+    price_feed_id: [0; 32],
+    max_policy_coverage: 0,
+    max_pool_capital: 0,
+    min_premium: 0,
+    is_accepted: false,
+};
+
+#[event]
+pub struct MintRegistered {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub price_feed_id: [u8; 32],
+    pub max_policy_coverage: u64,
+    pub max_pool_capital: u64,
+    pub min_premium: u64,
+}
+
+#[event]
+pub struct MintAcceptanceSet {
+    pub mint: Pubkey,
+    pub is_accepted: bool,
+}
+
+// The tokens premiums, pools and payouts can be denominated in, with each one's
+// decimals, price feed and caps in one place
+#[account]
+pub struct MintRegistry {
+    pub mints: [MintConfig; MAX_REGISTERED_MINTS],
+    pub mint_count: u8,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl MintRegistry {
+    pub const SIZE: usize = 8 +     // discriminator
+                           MintConfig::SIZE * MAX_REGISTERED_MINTS + // mints
+                           1 +      // mint_count
+                           1 +      // bump
+                           1;       // version
+
+    pub fn find(&self, mint: &Pubkey) -> Option<&MintConfig> {
+        self.mints[..self.mint_count as usize]
+            .iter()
+            .find(|config| config.mint == *mint)
+    }
+
+    // The mint's config, checked against the mint account itself
+    pub fn registered(&self, mint: &Account<Mint>) -> Result<&MintConfig> {
+        let config = self.find(&mint.key()).ok_or(ErrorCode::MintNotRegistered)?;
+        require!(config.decimals == mint.decimals, ErrorCode::MintDecimalsMismatch);
+        Ok(config)
+    }
+
+    // The feed the token is priced with, if it's registered with one
+    pub fn price_feed(&self, mint: &Pubkey) -> Option<[u8; 32]> {
+        self.find(mint)
+            .map(|config| config.price_feed_id)
+            .filter(|feed| *feed != [0; 32])
+    }
+
+    // Feeds configured elsewhere for a registered token have to be the registry's
+    pub fn check_price_feed(&self, mint: &Pubkey, feed: &[u8; 32]) -> Result<()> {
+        if let Some(registered) = self.price_feed(mint) {
+            require!(*feed == registered, ErrorCode::PriceFeedMismatch);
+        }
+        Ok(())
+    }
+
+    // As `registered`, for new policies, pools and deposits
+    pub fn accepted(&self, mint: &Account<Mint>) -> Result<&MintConfig> {
+        let config = self.registered(mint)?;
+        require!(config.is_accepted, ErrorCode::MintNotAccepted);
+        Ok(config)
+    }
+}
+
+pub fn initialize_mint_registry(ctx: Context<InitializeMintRegistry>) -> Result<()> {
+    let mint_registry = &mut ctx.accounts.mint_registry;
+    mint_registry.mints = [EMPTY_MINT_CONFIG; MAX_REGISTERED_MINTS];
+    mint_registry.mint_count = 0;
+    mint_registry.bump = ctx.bumps.mint_registry;
+    mint_registry.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+// Lists the mint as accepted, or updates its feed and caps if it's already listed
+pub fn register_mint(
+    ctx: Context<RegisterMint>,
+    price_feed_id: [u8; 32],
+    max_policy_coverage: u64,
+    max_pool_capital: u64,
+    min_premium: u64,
+) -> Result<()> {
+    let mint_registry = &mut ctx.accounts.mint_registry;
+    let mint = ctx.accounts.mint.key();
+    let count = mint_registry.mint_count as usize;
+
+    let config = MintConfig {
+        mint,
+        decimals: ctx.accounts.mint.decimals,
+        price_feed_id,
+        max_policy_coverage,
+        max_pool_capital,
+        min_premium,
+        is_accepted: true,
+    };
+    match mint_registry.mints[..count].iter().position(|config| config.mint == mint) {
+        Some(index) => mint_registry.mints[index] = config,
+        None => {
+            require!(count < MAX_REGISTERED_MINTS, ErrorCode::MintRegistryFull);
+            mint_registry.mints[count] = config;
+            mint_registry.mint_count = mint_registry.mint_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+
+    emit!(MintRegistered {
+        mint,
+        decimals: config.decimals,
+        price_feed_id,
+        max_policy_coverage,
+        max_pool_capital,
+        min_premium,
+    });
+
+    Ok(())
+}
+
+// Listed mints stay in the registry so claims already owed in them can be paid
+pub fn set_mint_accepted(
+    ctx: Context<SetMintAccepted>,
+    mint: Pubkey,
+    is_accepted: bool,
+) -> Result<()> {
+    let mint_registry = &mut ctx.accounts.mint_registry;
+    let count = mint_registry.mint_count as usize;
+    let config = mint_registry.mints[..count]
+        .iter_mut()
+        .find(|config| config.mint == mint)
+        .ok_or(ErrorCode::MintNotRegistered)?;
+    config.is_accepted = is_accepted;
+
+    emit!(MintAcceptanceSet {
+        mint,
+        is_accepted,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMintRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MintRegistry::SIZE,
+        seeds = [b"mint-registry"],
+        bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMint<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintAccepted<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
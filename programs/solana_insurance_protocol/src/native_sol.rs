@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, spl_token, CloseAccount, SyncNative, Token, TokenAccount};
+
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID
+}
+
+// Tops a wSOL account up to `amount` from its owner's lamports, so SOL premiums can
+// be paid without wrapping them first
+pub fn wrap_sol<'info>(
+    owner: &Signer<'info>,
+    token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    let shortfall = amount.saturating_sub(token_account.amount);
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = system_program::Transfer {
+        from: owner.to_account_info(),
+        to: token_account.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, shortfall)?;
+
+    let cpi_accounts = SyncNative {
+        account: token_account.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token::sync_native(cpi_ctx)
+}
+
+// Unwraps a temporary wSOL account that a payout was just sent to and forwards it to
+// `recipient` as plain lamports. The account is closed to the payer that funded its
+// rent, which then passes the payout amount on.
+pub fn unwrap_sol<'info>(
+    unwrap_account: &mut Account<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    payer: &Signer<'info>,
+    recipient: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    unwrap_account.reload()?;
+    let amount = unwrap_account.amount;
+
+    let cpi_accounts = CloseAccount {
+        account: unwrap_account.to_account_info(),
+        destination: payer.to_account_info(),
+        authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::close_account(cpi_ctx)?;
+
+    let cpi_accounts = system_program::Transfer {
+        from: payer.to_account_info(),
+        to: recipient,
+    };
+    let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
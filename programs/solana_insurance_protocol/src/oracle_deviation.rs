@@ -0,0 +1,364 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::alert_subscriptions::{notify_subscribers, AlertNotification};
+use crate::config::Config;
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::exploit_detection::{raise_system_alert, AlertSeverity, AnomalyType, SystemAlert};
+use crate::oracle_guards::{load_guarded_price, OracleConfig};
+use crate::risk_attestation::{verify_oracle_signature_at, MAX_ATTESTATION_AGE_SECS};
+use crate::watchers::{Watcher, WatcherRegistry};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ErrorCode};
+
+// Price of the protocol's key asset as reported by a staked watcher, who signs the
+// Borsh serialization of this struct
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PriceAttestation {
+    pub attester: Pubkey,
+    pub protocol: Pubkey,
+    pub price: u64,
+    pub attested_at: i64,
+}
+
+// Two Pyth feeds for the same asset, by feed id. Signed attestations come from staked
+// watchers instead, so only the deviation threshold applies to them.
+#[account]
+pub struct PriceFeedPair {
+    pub protocol: Pubkey,
+    pub primary_feed: Pubkey,
+    pub secondary_feed: Pubkey,
+    pub max_deviation_bps: u64,
+    pub last_alert_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl PriceFeedPair {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // primary_feed
+                           32 +     // secondary_feed
+                           8 +      // max_deviation_bps
+                           8 +      // last_alert_at
+                           1 +      // bump
+                           1;       // version
+}
+
+pub fn price_deviation_bps(a: u64, b: u64) -> u64 {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    if low == 0 {
+        return if high == 0 { 0 } else { 10000 };
+    }
+    std::cmp::min((high - low) as u128 * 10000 / low as u128, u64::MAX as u128) as u64
+}
+
+// Sets up a protocol's first feed pair, which takes effect immediately since there's no
+// monitoring yet to change. Later changes go through `ParameterChange::PriceFeeds`.
+pub fn configure_price_feeds(
+    ctx: Context<ConfigurePriceFeeds>,
+    primary_feed: Pubkey,
+    secondary_feed: Pubkey,
+    max_deviation_bps: u64,
+) -> Result<()> {
+    require!(
+        primary_feed != secondary_feed && max_deviation_bps > 0,
+        ErrorCode::InvalidPriceFeedConfig
+    );
+    
+    let price_feeds = &mut ctx.accounts.price_feeds;
+    price_feeds.protocol = ctx.accounts.protocol_info.key();
+    price_feeds.last_alert_at = 0;
+    price_feeds.bump = ctx.bumps.price_feeds;
+    price_feeds.version = ACCOUNT_VERSION;
+    price_feeds.primary_feed = primary_feed;
+    price_feeds.secondary_feed = secondary_feed;
+    price_feeds.max_deviation_bps = max_deviation_bps;
+    
+    Ok(())
+}
+
+// Compares prices attested by two different staked watchers, neither of them the admin or
+// the protocol, so no single party can raise or suppress the alert. Expects the primary
+// attestation's Ed25519 instruction two positions back and the secondary's immediately
+// before this one.
+pub fn check_price_deviation(
+    ctx: Context<CheckPriceDeviation>,
+    primary: PriceAttestation,
+    secondary: PriceAttestation,
+) -> Result<()> {
+    let price_feeds = &mut ctx.accounts.price_feeds;
+    let protocol_info = &ctx.accounts.protocol_info;
+    let clock = Clock::get()?;
+    
+    require!(protocol_info.oracle_dependency, ErrorCode::ProtocolNotOracleDependent);
+    
+    let primary_attester = ctx.accounts.primary_watcher.owner;
+    let secondary_attester = ctx.accounts.secondary_watcher.owner;
+    let registry = &ctx.accounts.watcher_registry;
+    let admin = ctx.accounts.protocol_state.authority;
+    require!(
+        primary_attester != secondary_attester &&
+        ctx.accounts.primary_watcher.is_staked(registry) &&
+        ctx.accounts.secondary_watcher.is_staked(registry) &&
+        ![admin, protocol_info.authority].iter().any(|key| *key == primary_attester || *key == secondary_attester),
+        ErrorCode::PriceAttestersNotIndependent
+    );
+    
+    for (attestation, attester) in [(&primary, primary_attester), (&secondary, secondary_attester)] {
+        require!(attestation.attester == attester, ErrorCode::AttestationSignerMismatch);
+        require!(
+            attestation.protocol == protocol_info.key(),
+            ErrorCode::AttestationProtocolMismatch
+        );
+        require!(
+            attestation.attested_at <= clock.unix_timestamp &&
+            clock.unix_timestamp - attestation.attested_at <= MAX_ATTESTATION_AGE_SECS,
+            ErrorCode::StaleRiskAttestation
+        );
+    }
+    
+    let instructions_sysvar = ctx.accounts.instructions_sysvar.to_account_info();
+    verify_oracle_signature_at(&instructions_sysvar, 2, &primary_attester, &primary.try_to_vec()?)?;
+    verify_oracle_signature_at(&instructions_sysvar, 1, &secondary_attester, &secondary.try_to_vec()?)?;
+    
+    let deviation_bps = price_deviation_bps(primary.price, secondary.price);
+    if deviation_bps > price_feeds.max_deviation_bps &&
+        clock.unix_timestamp - price_feeds.last_alert_at > ctx.accounts.config.params.price_deviation_cooldown_secs
+    {
+        raise_system_alert(
+            &ctx.accounts.payer,
+            &ctx.accounts.exploit_alert,
+            &mut ctx.accounts.protocol_info,
+            &ctx.accounts.protocol_state,
+            &ctx.accounts.system_program,
+            ctx.bumps.exploit_alert,
+            deviation_alert(deviation_bps),
+        )?;
+        ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(&ctx.accounts.protocol_info)
+        })?;
+        notify_subscribers(
+            ctx.remaining_accounts,
+            ctx.accounts.protocol_info.key(),
+            ctx.accounts.exploit_alert.key(),
+            AlertNotification::Activated,
+        )?;
+        price_feeds.last_alert_at = clock.unix_timestamp;
+    }
+    
+    Ok(())
+}
+
+// Same check against Pyth price updates posted earlier in the transaction
+pub fn check_pyth_price_deviation(ctx: Context<CheckPythPriceDeviation>) -> Result<()> {
+    let price_feeds = &mut ctx.accounts.price_feeds;
+    let clock = Clock::get()?;
+    
+    require!(ctx.accounts.protocol_info.oracle_dependency, ErrorCode::ProtocolNotOracleDependent);
+    
+    let oracle_config = &ctx.accounts.oracle_config;
+    let primary = load_guarded_price(oracle_config, &ctx.accounts.primary_price_update, &price_feeds.primary_feed.to_bytes())?;
+    let secondary = load_guarded_price(oracle_config, &ctx.accounts.secondary_price_update, &price_feeds.secondary_feed.to_bytes())?;
+    
+    // Compare at the finer of the two exponents
+    let exponent = std::cmp::min(primary.exponent, secondary.exponent);
+    let deviation_bps = price_deviation_bps(primary.scaled_to(exponent), secondary.scaled_to(exponent));
+    if deviation_bps > price_feeds.max_deviation_bps &&
+        clock.unix_timestamp - price_feeds.last_alert_at > ctx.accounts.config.params.price_deviation_cooldown_secs
+    {
+        raise_system_alert(
+            &ctx.accounts.payer,
+            &ctx.accounts.exploit_alert,
+            &mut ctx.accounts.protocol_info,
+            &ctx.accounts.protocol_state,
+            &ctx.accounts.system_program,
+            ctx.bumps.exploit_alert,
+            deviation_alert(deviation_bps),
+        )?;
+        ctx.accounts.registry.sync_active(&mut ctx.accounts.protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(&ctx.accounts.protocol_info)
+        })?;
+        notify_subscribers(
+            ctx.remaining_accounts,
+            ctx.accounts.protocol_info.key(),
+            ctx.accounts.exploit_alert.key(),
+            AlertNotification::Activated,
+        )?;
+        price_feeds.last_alert_at = clock.unix_timestamp;
+    }
+    
+    Ok(())
+}
+
+fn deviation_alert(deviation_bps: u64) -> SystemAlert {
+    SystemAlert {
+        anomaly_type: AnomalyType::OracleManipulation,
+        severity: AlertSeverity::High,
+        details: format!("Price feeds diverge by {} bps", deviation_bps),
+    }
+}
+
+#[derive(Accounts)]
+pub struct ConfigurePriceFeeds<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = PriceFeedPair::SIZE,
+        seeds = [b"price-feeds", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub price_feeds: Account<'info, PriceFeedPair>,
+    
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckPriceDeviation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"price-feeds", protocol_info.key().as_ref()],
+        bump = price_feeds.bump
+    )]
+    pub price_feeds: Account<'info, PriceFeedPair>,
+    
+    #[account(
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+    
+    #[account(
+        seeds = [b"watcher", primary_watcher.owner.as_ref()],
+        bump = primary_watcher.bump
+    )]
+    pub primary_watcher: Account<'info, Watcher>,
+    
+    #[account(
+        seeds = [b"watcher", secondary_watcher.owner.as_ref()],
+        bump = secondary_watcher.bump
+    )]
+    pub secondary_watcher: Account<'info, Watcher>,
+    
+    /// CHECK: created at this PDA by the check when it raises an alert
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        bump
+    )]
+    pub exploit_alert: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// CHECK: address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckPythPriceDeviation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"price-feeds", protocol_info.key().as_ref()],
+        bump = price_feeds.bump
+    )]
+    pub price_feeds: Account<'info, PriceFeedPair>,
+    
+    /// CHECK: owner, layout, feed id and freshness are validated by load_guarded_price
+    pub primary_price_update: UncheckedAccount<'info>,
+    
+    /// CHECK: owner, layout, feed id and freshness are validated by load_guarded_price
+    pub secondary_price_update: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"oracle-config"],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+    
+    /// CHECK: created at this PDA by the check when it raises an alert
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        bump
+    )]
+    pub exploit_alert: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub system_program: Program<'info, System>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
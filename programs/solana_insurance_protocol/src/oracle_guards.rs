@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::pyth::{load_pyth_price, PythPrice};
+use crate::{ProtocolState, ErrorCode};
+
+// Feeds that can have guards of their own instead of the defaults
+pub const MAX_FEED_OVERRIDES: usize = 16;
+
+// What a price update has to meet before any instruction acts on it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct OracleGuards {
+    pub max_slot_age: u64,       // Slots since the update was posted
+    pub max_age_secs: i64,       // Seconds since the price was published
+    pub max_confidence_bps: u64, // Confidence interval relative to the price
+}
+
+impl OracleGuards {
+    pub const SIZE: usize = 8 +     // max_slot_age
+                           8 +      // max_age_secs
+                           8;       // max_confidence_bps
+
+    pub fn is_valid(&self) -> bool {
+        self.max_slot_age > 0 &&
+        self.max_age_secs > 0 &&
+        self.max_confidence_bps > 0 &&
+        self.max_confidence_bps <= 10000
+    }
+}
+
+// About a minute of slots, in line with the publish time limit
+pub const DEFAULT_ORACLE_GUARDS: OracleGuards = OracleGuards {
+    max_slot_age: 150,
+    max_age_secs: 60,
+    max_confidence_bps: 200,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeedGuards {
+    pub feed_id: [u8; 32],
+    pub guards: OracleGuards,
+}
+
+impl FeedGuards {
+    pub const SIZE: usize = 32 +    // feed_id
+                           OracleGuards::SIZE; // guards
+}
+
+// Guards shared by every instruction that reads a price: parametric triggers, TVL
+// refreshes and deviation checks. Volatile or thinly published feeds can be given
+// their own limits. Changed through the timelocked `ParameterChange::OracleGuards`.
+#[account]
+pub struct OracleConfig {
+    pub default_guards: OracleGuards,
+    pub feed_overrides: [FeedGuards; MAX_FEED_OVERRIDES],
+    pub override_count: u8,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl OracleConfig {
+    pub const SIZE: usize = 8 +     // discriminator
+                           OracleGuards::SIZE + // default_guards
+                           FeedGuards::SIZE * MAX_FEED_OVERRIDES + // feed_overrides
+                           1 +      // override_count
+                           1 +      // bump
+                           1;       // version
+
+    pub fn guards_for(&self, feed_id: &[u8; 32]) -> &OracleGuards {
+        self.feed_overrides[..self.override_count as usize]
+            .iter()
+            .find(|feed| feed.feed_id == *feed_id)
+            .map_or(&self.default_guards, |feed| &feed.guards)
+    }
+
+    // A zero feed id sets the defaults; otherwise the feed's override is added,
+    // replaced or, with no guards, removed
+    pub fn set_guards(&mut self, feed_id: &[u8; 32], guards: Option<OracleGuards>) -> Result<()> {
+        let count = self.override_count as usize;
+        if *feed_id == [0; 32] {
+            self.default_guards = guards.ok_or(ErrorCode::InvalidOracleGuards)?;
+            return Ok(());
+        }
+
+        let index = self.feed_overrides[..count].iter().position(|feed| feed.feed_id == *feed_id);
+        match (index, guards) {
+            (Some(index), Some(guards)) => self.feed_overrides[index].guards = guards,
+            (Some(index), None) => {
+                // Move the last override into the freed slot
+                self.feed_overrides[index] = self.feed_overrides[count - 1];
+                self.override_count = self.override_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+            }
+            (None, Some(guards)) => {
+                require!(count < MAX_FEED_OVERRIDES, ErrorCode::OracleFeedOverridesFull);
+                self.feed_overrides[count] = FeedGuards { feed_id: *feed_id, guards };
+                self.override_count = self.override_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+            }
+            (None, None) => {}
+        }
+
+        Ok(())
+    }
+}
+
+pub fn initialize_oracle_config(ctx: Context<InitializeOracleConfig>) -> Result<()> {
+    let oracle_config = &mut ctx.accounts.oracle_config;
+    oracle_config.default_guards = DEFAULT_ORACLE_GUARDS;
+    oracle_config.feed_overrides = [FeedGuards { feed_id: [0; 32], guards: DEFAULT_ORACLE_GUARDS }; MAX_FEED_OVERRIDES];
+    oracle_config.override_count = 0;
+    oracle_config.bump = ctx.bumps.oracle_config;
+    oracle_config.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+// Reads a Pyth price update for `feed_id` under the guards configured for that feed
+pub fn load_guarded_price(
+    oracle_config: &OracleConfig,
+    price_update: &AccountInfo,
+    feed_id: &[u8; 32],
+) -> Result<PythPrice> {
+    load_pyth_price(price_update, feed_id, oracle_config.guards_for(feed_id))
+}
+
+#[derive(Accounts)]
+pub struct InitializeOracleConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = OracleConfig::SIZE,
+        seeds = [b"oracle-config"],
+        bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
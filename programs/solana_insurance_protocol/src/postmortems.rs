@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::exploit_detection::ExploitAlert;
+use crate::risk_assessment::{assess_loss_experience, calculate_composite_risk_score, postmortem_risk_penalty};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ProtocolAutoDeactivated, ErrorCode};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RootCause {
+    SmartContractBug,
+    OracleFailure,
+    EconomicAttack,
+    KeyCompromise,
+    GovernanceAttack,
+    Infrastructure,
+    Other,
+}
+
+#[account]
+pub struct Postmortem {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub author: Pubkey,
+    pub report_hash: [u8; 32],
+    pub root_cause: RootCause,
+    pub loss_estimate: u64,
+    pub risk_penalty: u8,   // Added to the risk component matching the root cause
+    pub attached_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Postmortem {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // alert
+                           32 +     // protocol
+                           32 +     // author
+                           32 +     // report_hash
+                           1 +      // root_cause
+                           8 +      // loss_estimate
+                           1 +      // risk_penalty
+                           8 +      // attached_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct PostmortemAttached {
+    pub alert: Pubkey,
+    pub protocol: Pubkey,
+    pub root_cause: RootCause,
+    pub loss_estimate: u64,
+    pub risk_score: u8,
+}
+
+pub fn attach_postmortem(
+    ctx: Context<AttachPostmortem>,
+    report_hash: [u8; 32],
+    root_cause: RootCause,
+    loss_estimate: u64,
+) -> Result<()> {
+    let exploit_alert = &ctx.accounts.exploit_alert;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let postmortem = &mut ctx.accounts.postmortem;
+    let clock = Clock::get()?;
+
+    require!(exploit_alert.is_resolved, ErrorCode::AlertNotResolved);
+    // The report moves the protocol's risk score, so the protocol can't write its own
+    let protocol_state = &ctx.accounts.protocol_state;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == protocol_state.authority || protocol_state.is_claim_resolver(&authority),
+        ErrorCode::UnauthorizedAccess
+    );
+
+    // Only a confirmed incident says something about the protocol's risk
+    let risk_penalty = if exploit_alert.is_confirmed {
+        postmortem_risk_penalty(loss_estimate, protocol_info.tvl_usd)
+    } else {
+        0
+    };
+
+    postmortem.alert = exploit_alert.key();
+    postmortem.protocol = protocol_info.key();
+    postmortem.author = ctx.accounts.authority.key();
+    postmortem.report_hash = report_hash;
+    postmortem.root_cause = root_cause;
+    postmortem.loss_estimate = loss_estimate;
+    postmortem.risk_penalty = risk_penalty;
+    postmortem.attached_at = clock.unix_timestamp;
+    postmortem.bump = ctx.bumps.postmortem;
+    postmortem.version = ACCOUNT_VERSION;
+
+    if risk_penalty > 0 {
+        let add_penalty = |score: u8| std::cmp::min(score as u16 + risk_penalty as u16, 100) as u8;
+        let mut code_risk = protocol_info.code_risk;
+        let mut economic_risk = protocol_info.economic_risk;
+        let mut operational_risk = protocol_info.operational_risk;
+        match root_cause {
+            RootCause::SmartContractBug => code_risk = add_penalty(code_risk),
+            RootCause::OracleFailure | RootCause::EconomicAttack => economic_risk = add_penalty(economic_risk),
+            RootCause::KeyCompromise |
+            RootCause::GovernanceAttack |
+            RootCause::Infrastructure |
+            RootCause::Other => operational_risk = add_penalty(operational_risk),
+        }
+
+        let loss_experience = assess_loss_experience(
+            protocol_info.premiums_earned,
+            protocol_info.claims_paid,
+        );
+        let risk_score = calculate_composite_risk_score(
+            code_risk,
+            economic_risk,
+            operational_risk,
+            loss_experience,
+        );
+
+        protocol_info.set_risk_components(code_risk, economic_risk, operational_risk);
+        let max_insurable_risk_score = ctx.accounts.protocol_state.max_insurable_risk_score;
+        if protocol_info.apply_risk_score(risk_score, max_insurable_risk_score) {
+            emit!(ProtocolAutoDeactivated {
+                protocol: protocol_info.key(),
+                risk_score,
+                max_insurable_risk_score,
+            });
+        }
+        protocol_info.last_risk_update = clock.unix_timestamp;
+        ctx.accounts.registry.sync_active(protocol_info)?;
+        update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+            directory.record(protocol_info)
+        })?;
+    }
+
+    emit!(PostmortemAttached {
+        alert: exploit_alert.key(),
+        protocol: protocol_info.key(),
+        root_cause,
+        loss_estimate,
+        risk_score: protocol_info.risk_score,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AttachPostmortem<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &exploit_alert.alert_time.to_le_bytes()],
+        bump = exploit_alert.bump
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Postmortem::SIZE,
+        seeds = [b"postmortem", exploit_alert.key().as_ref()],
+        bump
+    )]
+    pub postmortem: Account<'info, Postmortem>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, TransferChecked};
+use crate::deregistration::unexpired_premium;
+use crate::treasury::TreasuryVault;
+use crate::{Policy, ErrorCode};
+
+// A policy's premium, net of the protocol fee, is held in an escrow token account owned by
+// the policy and moves to the treasury as it's earned over the policy's term. What hasn't
+// been earned when the policy is cancelled goes back to the insured straight from the
+// escrow, so a refund doesn't depend on anyone releasing treasury funds.
+
+#[event]
+pub struct PremiumReleased {
+    pub policy: Pubkey,
+    pub amount: u64,
+    pub premium_released: u64,
+}
+
+impl Policy {
+    // Escrowed premium earned by `now`, pro rata over the policy's term
+    pub fn earned_premium(&self, now: i64) -> u64 {
+        self.premium_escrowed.saturating_sub(unexpired_premium(self, now))
+    }
+
+    // Whether everything escrowed has left the escrow
+    pub fn is_escrow_settled(&self) -> bool {
+        self.premium_released.saturating_add(self.premium_refunded) >= self.premium_escrowed
+    }
+}
+
+pub(crate) fn transfer_from_escrow<'info>(
+    policy: &Account<'info, Policy>,
+    premium_escrow: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    mint: &Account<'info, Mint>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let index = policy.index.to_le_bytes();
+    let seeds = &[
+        b"policy".as_ref(),
+        policy.insured.as_ref(),
+        policy.protocol.as_ref(),
+        &index,
+        &[policy.bump]
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: premium_escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to: destination,
+        authority: policy.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// Returns the escrow's rent to the insured, who paid it. Tokens sent to the escrow
+// from outside would keep it from closing, so anything left is swept to the treasury.
+pub(crate) fn close_escrow<'info>(
+    policy: &Account<'info, Policy>,
+    premium_escrow: &mut Account<'info, TokenAccount>,
+    treasury_token: &Account<'info, TokenAccount>,
+    mint: &Account<'info, Mint>,
+    insured: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    premium_escrow.reload()?;
+    transfer_from_escrow(policy, premium_escrow, treasury_token.to_account_info(), mint, token_program, premium_escrow.amount)?;
+
+    let index = policy.index.to_le_bytes();
+    let seeds = &[
+        b"policy".as_ref(),
+        policy.insured.as_ref(),
+        policy.protocol.as_ref(),
+        &index,
+        &[policy.bump]
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = CloseAccount {
+        account: premium_escrow.to_account_info(),
+        destination: insured,
+        authority: policy.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::close_account(cpi_ctx)
+}
+
+// Moves the premium earned so far from the escrow to the treasury
+pub(crate) fn release_to_treasury<'info>(
+    policy: &mut Account<'info, Policy>,
+    premium_escrow: &Account<'info, TokenAccount>,
+    treasury_vault: &mut Account<'info, TreasuryVault>,
+    treasury_token: &Account<'info, TokenAccount>,
+    mint: &Account<'info, Mint>,
+    token_program: &Program<'info, Token>,
+    now: i64,
+) -> Result<u64> {
+    let amount = policy.earned_premium(now).saturating_sub(policy.premium_released);
+    transfer_from_escrow(policy, premium_escrow, treasury_token.to_account_info(), mint, token_program, amount)?;
+
+    policy.premium_released = policy.premium_released.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    treasury_vault.total_inflows = treasury_vault.total_inflows.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(amount)
+}
+
+// Permissionless. Once the policy has run its term the escrow is emptied and closed.
+pub fn release_earned_premium(ctx: Context<ReleaseEarnedPremium>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let amount = release_to_treasury(
+        &mut ctx.accounts.policy,
+        &ctx.accounts.premium_escrow,
+        &mut ctx.accounts.treasury_vault,
+        &ctx.accounts.treasury_token,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        now,
+    )?;
+
+    let policy = &ctx.accounts.policy;
+    if policy.is_escrow_settled() {
+        close_escrow(
+            policy,
+            &mut ctx.accounts.premium_escrow,
+            &ctx.accounts.treasury_token,
+            &ctx.accounts.token_mint,
+            ctx.accounts.insured.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    emit!(PremiumReleased {
+        policy: policy.key(),
+        amount,
+        premium_released: policy.premium_released,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEarnedPremium<'info> {
+    pub caller: Signer<'info>,
+
+    // Bound to the escrow by its seeds; older policies have no escrow
+    #[account(mut)]
+    pub policy: Account<'info, Policy>,
+
+    #[account(
+        mut,
+        seeds = [b"premium-escrow", policy.key().as_ref()],
+        bump
+    )]
+    pub premium_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", policy.premium_mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(
+        mut,
+        constraint = treasury_token.key() == treasury_vault.token_account @ ErrorCode::InvalidTreasuryVault
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
+
+    #[account(address = policy.premium_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Receives the escrow's rent once it's closed
+    #[account(mut, address = policy.insured)]
+    pub insured: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
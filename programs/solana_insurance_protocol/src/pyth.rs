@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::oracle_guards::OracleGuards;
+use crate::ErrorCode;
+
+// Pyth pull-oracle receiver. Price updates are posted to accounts it owns in the same
+// transaction as the instruction reading them.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+// Anchor discriminator of the receiver's PriceUpdateV2 account
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+// Layout of the receiver's PriceUpdateV2 after its discriminator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+// A price that passed validation: positive, fresh and with a tight enough confidence interval
+#[derive(Clone, Copy)]
+pub struct PythPrice {
+    pub price: u64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    // The price with `exponent` as its exponent, so feeds can be compared
+    pub fn scaled_to(&self, exponent: i32) -> u64 {
+        let shift = self.exponent - exponent;
+        let scaled = if shift >= 0 {
+            (self.price as u128).saturating_mul(10u128.saturating_pow(shift as u32))
+        } else {
+            self.price as u128 / 10u128.saturating_pow(shift.unsigned_abs())
+        };
+        std::cmp::min(scaled, u64::MAX as u128) as u64
+    }
+
+    // Value in whole USD of `amount` base units of a token with `decimals` decimals
+    pub fn usd_value(&self, amount: u64, decimals: u8) -> u64 {
+        let value = amount as u128 * self.price as u128;
+        let exponent = self.exponent - decimals as i32;
+        let value = if exponent >= 0 {
+            value.saturating_mul(10u128.saturating_pow(exponent as u32))
+        } else {
+            value / 10u128.saturating_pow(exponent.unsigned_abs())
+        };
+        std::cmp::min(value, u64::MAX as u128) as u64
+    }
+}
+
+// Reads and validates a fully verified price update for `feed_id` posted by the Pyth receiver.
+// Instructions go through oracle_guards::load_guarded_price for the feed's configured guards.
+pub fn load_pyth_price(price_update: &AccountInfo, feed_id: &[u8; 32], guards: &OracleGuards) -> Result<PythPrice> {
+    require_keys_eq!(*price_update.owner, PYTH_RECEIVER_PROGRAM_ID, ErrorCode::InvalidPriceUpdate);
+
+    let data = price_update.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        ErrorCode::InvalidPriceUpdate
+    );
+    let update = PriceUpdateV2::deserialize(&mut &data[8..])
+        .map_err(|_| error!(ErrorCode::InvalidPriceUpdate))?;
+    let message = update.price_message;
+
+    require!(update.verification_level == VerificationLevel::Full, ErrorCode::InvalidPriceUpdate);
+    require!(message.feed_id == *feed_id, ErrorCode::PriceFeedMismatch);
+    require!(message.price > 0, ErrorCode::InvalidPriceUpdate);
+
+    let clock = Clock::get()?;
+    require!(
+        update.posted_slot <= clock.slot && clock.slot - update.posted_slot <= guards.max_slot_age,
+        ErrorCode::StalePriceUpdate
+    );
+    require!(
+        message.publish_time <= clock.unix_timestamp &&
+        clock.unix_timestamp - message.publish_time <= guards.max_age_secs,
+        ErrorCode::StalePriceUpdate
+    );
+
+    let price = message.price as u64;
+    require!(
+        message.conf as u128 * 10000 <= price as u128 * guards.max_confidence_bps as u128,
+        ErrorCode::PriceConfidenceTooWide
+    );
+
+    Ok(PythPrice {
+        price,
+        conf: message.conf,
+        exponent: message.exponent,
+        publish_time: message.publish_time,
+    })
+}
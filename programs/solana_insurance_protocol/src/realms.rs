@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolState, ErrorCode};
+
+// SPL Governance, the program behind Realms
+pub const SPL_GOVERNANCE_PROGRAM_ID: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+// Offset of the realm in a governance account, after its account type
+const GOVERNANCE_REALM_OFFSET: usize = 1;
+
+// SPL Governance account types, the first byte of each of its accounts
+const REALM_ACCOUNT_TYPES: [u8; 2] = [1, 16]; // RealmV1, RealmV2
+const GOVERNANCE_ACCOUNT_TYPES: [u8; 8] = [
+    3, 4, 9, 10,    // Governance, program, mint and token governance V1
+    18, 19, 20, 21, // The same in V2
+];
+
+// A Realms governance that decides large claims. SPL Governance only signs as the
+// governance, or its native treasury, when executing a proposal that passed, so a
+// claim approved by one of those keys was voted on by the DAO.
+#[account]
+pub struct ClaimsCommittee {
+    pub realm: Pubkey,
+    pub governance: Pubkey,
+    pub native_treasury: Pubkey,
+    pub large_claim_threshold: u64, // Approving claims of at least this amount takes a proposal
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ClaimsCommittee {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // realm
+                           32 +     // governance
+                           32 +     // native_treasury
+                           8 +      // large_claim_threshold
+                           1 +      // bump
+                           1;       // version
+
+    pub fn is_committee_signer(&self, key: &Pubkey) -> bool {
+        *key == self.governance || *key == self.native_treasury
+    }
+}
+
+#[event]
+pub struct ClaimsCommitteeConfigured {
+    pub realm: Pubkey,
+    pub governance: Pubkey,
+    pub large_claim_threshold: u64,
+}
+
+// Reads the committee from its PDA, which is empty until one is configured
+pub fn load_claims_committee(claims_committee: &AccountInfo) -> Result<Option<ClaimsCommittee>> {
+    if claims_committee.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(ClaimsCommittee::try_deserialize(&mut &claims_committee.data.borrow()[..])?))
+}
+
+// Checks the governance is an SPL Governance account of the realm
+pub fn check_realms_governance(realm: &AccountInfo, governance: &AccountInfo) -> Result<()> {
+    require_keys_eq!(*realm.owner, SPL_GOVERNANCE_PROGRAM_ID, ErrorCode::InvalidRealmsGovernance);
+    require_keys_eq!(*governance.owner, SPL_GOVERNANCE_PROGRAM_ID, ErrorCode::InvalidRealmsGovernance);
+
+    let realm_data = realm.try_borrow_data()?;
+    require!(
+        realm_data.first().is_some_and(|account_type| REALM_ACCOUNT_TYPES.contains(account_type)),
+        ErrorCode::InvalidRealmsGovernance
+    );
+    let data = governance.try_borrow_data()?;
+    require!(
+        data.len() >= GOVERNANCE_REALM_OFFSET + 32 &&
+        GOVERNANCE_ACCOUNT_TYPES.contains(&data[0]) &&
+        data[GOVERNANCE_REALM_OFFSET..GOVERNANCE_REALM_OFFSET + 32] == realm.key().to_bytes(),
+        ErrorCode::InvalidRealmsGovernance
+    );
+    Ok(())
+}
+
+// Points the committee at a governance of the realm
+pub fn set_claims_committee(
+    claims_committee: &mut ClaimsCommittee,
+    realm: Pubkey,
+    governance: Pubkey,
+    large_claim_threshold: u64,
+) {
+    let (native_treasury, _) = Pubkey::find_program_address(
+        &[b"native-treasury", governance.as_ref()],
+        &SPL_GOVERNANCE_PROGRAM_ID,
+    );
+    claims_committee.realm = realm;
+    claims_committee.governance = governance;
+    claims_committee.native_treasury = native_treasury;
+    claims_committee.large_claim_threshold = large_claim_threshold;
+
+    emit!(ClaimsCommitteeConfigured {
+        realm,
+        governance,
+        large_claim_threshold,
+    });
+}
+
+// Sets up the first committee. Since it only takes large claims away from the resolvers
+// it applies straight away; changing it afterwards goes through the timelock.
+pub fn configure_claims_committee(
+    ctx: Context<ConfigureClaimsCommittee>,
+    large_claim_threshold: u64,
+) -> Result<()> {
+    check_realms_governance(&ctx.accounts.realm, &ctx.accounts.governance)?;
+    require!(large_claim_threshold > 0, ErrorCode::InvalidRealmsGovernance);
+
+    let claims_committee = &mut ctx.accounts.claims_committee;
+    claims_committee.bump = ctx.bumps.claims_committee;
+    claims_committee.version = ACCOUNT_VERSION;
+    set_claims_committee(
+        claims_committee,
+        ctx.accounts.realm.key(),
+        ctx.accounts.governance.key(),
+        large_claim_threshold,
+    );
+
+    Ok(())
+}
+
+// Hands large claims back to the registered resolvers
+pub fn remove_claims_committee(_ctx: Context<RemoveClaimsCommittee>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureClaimsCommittee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ClaimsCommittee::SIZE,
+        seeds = [b"claims-committee"],
+        bump
+    )]
+    pub claims_committee: Account<'info, ClaimsCommittee>,
+
+    /// CHECK: must be owned by SPL Governance; checked in the handler
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: must be an SPL Governance account of the realm; checked in the handler
+    pub governance: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveClaimsCommittee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"claims-committee"],
+        bump = claims_committee.bump
+    )]
+    pub claims_committee: Account<'info, ClaimsCommittee>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
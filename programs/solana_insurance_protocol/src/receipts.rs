@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use anchor_spl::memo::{self, BuildMemo, Memo};
+
+// Structured receipts attached to premium payments, refunds and payouts through the
+// SPL Memo program, so transfers can be reconciled from the transaction alone
+pub fn premium_receipt(policy: &Pubkey, premium_amount: u64, fee_amount: u64) -> String {
+    format!(
+        r#"{{"type":"premium","policy":"{}","amount":{},"fee":{}}}"#,
+        policy, premium_amount, fee_amount
+    )
+}
+
+pub fn refund_receipt(policy: &Pubkey, refund_amount: u64) -> String {
+    format!(r#"{{"type":"refund","policy":"{}","amount":{}}}"#, policy, refund_amount)
+}
+
+pub fn payout_receipt(claim: &Pubkey, policy: &Pubkey, amount: u64) -> String {
+    format!(
+        r#"{{"type":"payout","claim":"{}","policy":"{}","amount":{}}}"#,
+        claim, policy, amount
+    )
+}
+
+// Receipts are only written when the caller passes the memo program
+pub fn write_receipt(memo_program: &Option<Program<Memo>>, receipt: String) -> Result<()> {
+    let Some(memo_program) = memo_program else {
+        return Ok(());
+    };
+    let cpi_ctx = CpiContext::new(memo_program.to_account_info(), BuildMemo {});
+    memo::build_memo(cpi_ctx, receipt.as_bytes())
+}
@@ -1,9 +1,20 @@
-use crate::ErrorCode;
+use anchor_lang::prelude::*;
+
+use crate::math::mul_div_ceil;
+use crate::{ErrorCode, PremiumCurvePoint, RiskWeights};
 
 // Risk assessment factors with weights
-pub const CODE_RISK_WEIGHT: u8 = 30;
-pub const ECONOMIC_RISK_WEIGHT: u8 = 40;
-pub const OPERATIONAL_RISK_WEIGHT: u8 = 30;
+pub const CODE_RISK_WEIGHT: u8 = 25;
+pub const ECONOMIC_RISK_WEIGHT: u8 = 35;
+pub const OPERATIONAL_RISK_WEIGHT: u8 = 25;
+pub const LOSS_EXPERIENCE_WEIGHT: u8 = 15;
+
+pub const DEFAULT_RISK_WEIGHTS: RiskWeights = RiskWeights {
+    code: CODE_RISK_WEIGHT,
+    economic: ECONOMIC_RISK_WEIGHT,
+    operational: OPERATIONAL_RISK_WEIGHT,
+    loss_experience: LOSS_EXPERIENCE_WEIGHT,
+};
 
 // Risk score ranges from 0 to 100
 // 0-25: Low risk
@@ -11,25 +22,43 @@ pub const OPERATIONAL_RISK_WEIGHT: u8 = 30;
 // 51-75: Medium-high risk
 // 76-100: High risk
 
+// Protocols scoring above the configured maximum stop selling coverage.
+// The default never triggers until the admin lowers it.
+pub const DEFAULT_MAX_INSURABLE_RISK_SCORE: u8 = 100;
+
+// Bounds enforced on submitted risk parameters
+pub const MAX_RISK_INPUT_SCORE: u8 = 100;
+pub const MAX_GOVERNANCE_COUNT: u8 = 100;
+pub const MAX_ADMIN_COUNT: u8 = 32;
+
+// Audits older than this only count for half when assessing code risk
+pub const AUDIT_RECENCY_WINDOW_SECS: i64 = 365 * 86400;
+
 pub fn assess_code_risk(
-    audit_count: u8,
+    verified_audit_count: u8,
+    last_audit_date: i64,
+    current_time: i64,
     bug_bounty_size: u64,
     complexity_score: u8,
 ) -> u8 {
     // Weighted code risk calculation
-    // More audits, higher bug bounty, and lower complexity reduce risk
-    let audit_factor = 100 - std::cmp::min(audit_count, 5) * 20;
-    let bounty_factor = match bug_bounty_size {
+    // More (and more recent) verified audits, higher bug bounty, and lower complexity reduce risk
+    let audit_count = if current_time - last_audit_date <= AUDIT_RECENCY_WINDOW_SECS {
+        verified_audit_count
+    } else {
+        verified_audit_count / 2
+    };
+    let audit_factor: u16 = 100 - std::cmp::min(audit_count, 5) as u16 * 20;
+    let bounty_factor: u16 = match bug_bounty_size {
         0 => 100,
         1..=50_000 => 75,
         50_001..=250_000 => 50,
         250_001..=1_000_000 => 25,
         _ => 0,
     };
-    let complexity_factor = std::cmp::min(complexity_score, 100);
+    let complexity_factor = complexity_score as u16; // 0-100 scale, validated by the caller
     
-    let weighted_code_risk = (audit_factor + bounty_factor + complexity_factor) / 3;
-    weighted_code_risk
+    ((audit_factor + bounty_factor + complexity_factor) / 3) as u8
 }
 
 pub fn assess_economic_risk(
@@ -38,24 +67,23 @@ pub fn assess_economic_risk(
     concentration_risk: u8,
 ) -> u8 {
     // Economic risk increases with higher TVL, lower liquidity depth, and higher concentration
-    let tvl_factor = match tvl_usd {
+    let tvl_factor: u16 = match tvl_usd {
         0..=1_000_000 => 25,                 // < $1M
         1_000_001..=10_000_000 => 50,        // $1M-$10M
         10_000_001..=100_000_000 => 75,      // $10M-$100M
         _ => 100,                           // > $100M
     };
     
-    let liquidity_factor = match liquidity_depth {
+    let liquidity_factor: u16 = match liquidity_depth {
         0..=100_000 => 100,                  // Very low liquidity
         100_001..=1_000_000 => 75,           // Low liquidity
         1_000_001..=10_000_000 => 50,        // Medium liquidity
         _ => 25,                             // High liquidity
     };
     
-    let concentration_factor = concentration_risk; // 0-100 scale
+    let concentration_factor = concentration_risk as u16; // 0-100 scale, validated by the caller
     
-    let weighted_economic_risk = (tvl_factor + liquidity_factor + concentration_factor) / 3;
-    weighted_economic_risk
+    ((tvl_factor + liquidity_factor + concentration_factor) / 3) as u8
 }
 
 pub fn assess_operational_risk(
@@ -66,50 +94,242 @@ pub fn assess_operational_risk(
     // More governance participants and admins reduce risk
     // Oracle dependency increases risk
     
-    let governance_factor = 100 - std::cmp::min(governance_count, 10) * 10;
-    let admin_factor = 100 - std::cmp::min(admin_count, 5) * 20;
-    let oracle_factor = if oracle_dependency { 100 } else { 0 };
+    let governance_factor: u16 = 100 - std::cmp::min(governance_count, 10) as u16 * 10;
+    let admin_factor: u16 = 100 - std::cmp::min(admin_count, 5) as u16 * 20;
+    let oracle_factor: u16 = if oracle_dependency { 100 } else { 0 };
+    
+    ((governance_factor + admin_factor + oracle_factor) / 3) as u8
+}
+
+// Operational risk added per confirmed incident in the protocol's alert history
+pub const CONFIRMED_INCIDENT_RISK: u16 = 15;
+
+pub fn apply_incident_history(
+    operational_risk: u8,
+    confirmed_incidents: u32,
+) -> u8 {
+    // A track record of confirmed exploits says more than the governance setup alone
+    let incident_risk = std::cmp::min(confirmed_incidents, 100) as u16 * CONFIRMED_INCIDENT_RISK;
+    std::cmp::min(operational_risk as u16 + incident_risk, 100) as u8
+}
+
+pub fn postmortem_risk_penalty(
+    loss_estimate: u64,
+    tvl_usd: u64,
+) -> u8 {
+    // Any confirmed incident costs 10 points; larger losses relative to TVL cost more, up to 40
+    let loss_pct = if tvl_usd == 0 {
+        100
+    } else {
+        std::cmp::min(loss_estimate as u128 * 100 / tvl_usd as u128, 100) as u16
+    };
+    std::cmp::min(10 + loss_pct / 2, 40) as u8
+}
+
+pub fn assess_loss_experience(
+    premiums_earned: u64,
+    claims_paid: u64,
+) -> u8 {
+    // Loss ratio of claims paid to premiums earned, as a percentage
+    // A protocol paying out as much as it earned (or more) scores the maximum risk
+    if claims_paid == 0 {
+        return 0;
+    }
+    if premiums_earned == 0 {
+        return 100;
+    }
+    
+    let loss_ratio_pct = (claims_paid as u128 * 100) / premiums_earned as u128;
+    std::cmp::min(loss_ratio_pct, 100) as u8
+}
+
+pub fn median_score(scores: &mut [u8]) -> u8 {
+    // Median of the submitted scores, averaging the two middle values for an even count
+    if scores.is_empty() {
+        return 0;
+    }
     
-    let weighted_operational_risk = (governance_factor + admin_factor + oracle_factor) / 3;
-    weighted_operational_risk
+    scores.sort_unstable();
+    let mid = scores.len() / 2;
+    if scores.len() % 2 == 1 {
+        scores[mid]
+    } else {
+        ((scores[mid - 1] as u16 + scores[mid] as u16) / 2) as u8
+    }
 }
 
 pub fn calculate_composite_risk_score(
     code_risk: u8,
     economic_risk: u8,
     operational_risk: u8,
+    loss_experience: u8,
+) -> u8 {
+    calculate_weighted_risk_score(
+        code_risk,
+        economic_risk,
+        operational_risk,
+        loss_experience,
+        &DEFAULT_RISK_WEIGHTS,
+    )
+}
+
+pub fn calculate_weighted_risk_score(
+    code_risk: u8,
+    economic_risk: u8,
+    operational_risk: u8,
+    loss_experience: u8,
+    weights: &RiskWeights,
 ) -> u8 {
     // Weighted average of all risk factors
     let weighted_score = (
-        (code_risk as u16 * CODE_RISK_WEIGHT as u16) +
-        (economic_risk as u16 * ECONOMIC_RISK_WEIGHT as u16) +
-        (operational_risk as u16 * OPERATIONAL_RISK_WEIGHT as u16)
+        (code_risk as u16 * weights.code as u16) +
+        (economic_risk as u16 * weights.economic as u16) +
+        (operational_risk as u16 * weights.operational as u16) +
+        (loss_experience as u16 * weights.loss_experience as u16)
     ) / 100;
     
     weighted_score as u8
 }
 
-pub fn calculate_premium_rate(risk_score: u8) -> u64 {
+// Premium pricing curve anchor points, interpolated linearly in between
+pub const PREMIUM_CURVE_POINTS: usize = 5;
+
+pub const DEFAULT_PREMIUM_CURVE: [PremiumCurvePoint; PREMIUM_CURVE_POINTS] = [
+    PremiumCurvePoint { risk_score: 0, rate_bps: 25 },     // 0.25% annual premium rate
+    PremiumCurvePoint { risk_score: 25, rate_bps: 50 },    // 0.5% annual premium rate
+    PremiumCurvePoint { risk_score: 50, rate_bps: 100 },   // 1% annual premium rate
+    PremiumCurvePoint { risk_score: 75, rate_bps: 200 },   // 2% annual premium rate
+    PremiumCurvePoint { risk_score: 100, rate_bps: 200 },  // 2% annual premium rate
+];
+
+// Highest annual rate a curve may charge, i.e. the full coverage each year
+pub const MAX_PREMIUM_RATE_BPS: u64 = 10000;
+
+pub fn is_valid_premium_curve(curve: &[PremiumCurvePoint]) -> bool {
+    // The curve must span the full 0-100 risk range with strictly increasing risk scores,
+    // a riskier protocol must never be cheaper to insure, and no rate may exceed 100%
+    let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+        return false;
+    };
+    
+    first.risk_score == 0 &&
+    last.risk_score == 100 &&
+    last.rate_bps <= MAX_PREMIUM_RATE_BPS &&
+    curve.windows(2).all(|pair| {
+        pair[0].risk_score < pair[1].risk_score && pair[0].rate_bps <= pair[1].rate_bps
+    })
+}
+
+pub fn calculate_premium_rate(risk_score: u8, curve: &[PremiumCurvePoint]) -> Result<u64> {
     // Premium rate calculation based on risk score
-    // Returns basis points (1/100 of 1%)
-    match risk_score {
-        0..=25 => 25,        // 0.25% annual premium rate for low risk
-        26..=50 => 50,       // 0.5% annual premium rate for medium-low risk
-        51..=75 => 100,      // 1% annual premium rate for medium-high risk
-        _ => 200,            // 2% annual premium rate for high risk
+    // Returns basis points (1/100 of 1%), interpolated between the surrounding anchor points
+    let risk_score = std::cmp::min(risk_score, 100);
+    
+    for pair in curve.windows(2) {
+        let (lower, upper) = (pair[0], pair[1]);
+        if risk_score <= upper.risk_score {
+            let span = upper.risk_score.saturating_sub(lower.risk_score) as u64;
+            let offset = risk_score.saturating_sub(lower.risk_score) as u64;
+            return upper.rate_bps
+                .checked_sub(lower.rate_bps)
+                .and_then(|rise| rise.checked_mul(offset))
+                .and_then(|rise| rise.checked_div(span))
+                .and_then(|rise| lower.rate_bps.checked_add(rise))
+                .ok_or_else(|| error!(ErrorCode::MathOverflow));
+        }
     }
+    
+    Ok(curve.last().map_or(0, |point| point.rate_bps))
+}
+
+// Safeguards attested by a registered assessor, stored as bit flags on the protocol
+pub const SAFEGUARD_CIRCUIT_BREAKER: u8 = 1 << 0;
+pub const SAFEGUARD_WITHDRAWAL_LIMITS: u8 = 1 << 1;
+pub const SAFEGUARD_TIMELOCKED_UPGRADES: u8 = 1 << 2;
+pub const SAFEGUARD_COUNT: usize = 3;
+pub const ALL_SAFEGUARDS: u8 = (1 << SAFEGUARD_COUNT) - 1;
+
+pub fn apply_safeguard_discounts(
+    premium_rate_bps: u64,
+    safeguard_flags: u8,
+    safeguard_discount_bps: &[u64; SAFEGUARD_COUNT],
+) -> Result<u64> {
+    // Each attested safeguard takes its configured share (in bps) off the premium rate
+    let total_discount_bps: u64 = safeguard_discount_bps
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| safeguard_flags & (1 << i) != 0)
+        .map(|(_, discount)| *discount)
+        .sum();
+    let total_discount_bps = std::cmp::min(total_discount_bps, 10000);
+    
+    mul_div_ceil(premium_rate_bps, (10000 - total_discount_bps) as u128, 10000)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+pub fn apply_premium_surcharge(
+    premium_rate_bps: u64,
+    surcharge_bps: u64,
+) -> Result<u64> {
+    // Surcharges are relative to the rate, e.g. 2500 bps makes the premium 25% more expensive
+    mul_div_ceil(premium_rate_bps, 10000 + surcharge_bps as u128, 10000)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
 }
 
 pub fn calculate_premium_amount(
     coverage_amount: u64,
     premium_rate_bps: u64,
     duration_days: u16,
-) -> u64 {
+) -> Result<u64> {
     // Calculate the premium amount based on coverage, rate, and duration
-    // premium = coverage * rate * (duration / 365)
-    let annual_premium = (coverage_amount * premium_rate_bps) / 10000; // Convert basis points to decimal
-    let daily_premium = annual_premium / 365;
-    let premium_amount = daily_premium * duration_days as u64;
-    
-    premium_amount
-}
\ No newline at end of file
+    // premium = coverage * rate * (duration / 365), rounded up once at the end
+    mul_div_ceil(
+        coverage_amount,
+        premium_rate_bps as u128 * duration_days as u128,
+        10000 * 365,
+    ).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premium_rate_matches_the_anchor_points() {
+        for point in DEFAULT_PREMIUM_CURVE {
+            assert_eq!(calculate_premium_rate(point.risk_score, &DEFAULT_PREMIUM_CURVE).unwrap(), point.rate_bps);
+        }
+    }
+
+    #[test]
+    fn premium_rate_interpolates_between_anchor_points() {
+        assert_eq!(calculate_premium_rate(10, &DEFAULT_PREMIUM_CURVE).unwrap(), 35);
+        assert_eq!(calculate_premium_rate(40, &DEFAULT_PREMIUM_CURVE).unwrap(), 80);
+        assert_eq!(calculate_premium_rate(60, &DEFAULT_PREMIUM_CURVE).unwrap(), 140);
+        assert_eq!(calculate_premium_rate(90, &DEFAULT_PREMIUM_CURVE).unwrap(), 200);
+    }
+
+    #[test]
+    fn premium_rate_clamps_risk_scores_above_100() {
+        assert_eq!(calculate_premium_rate(101, &DEFAULT_PREMIUM_CURVE).unwrap(), 200);
+        assert_eq!(calculate_premium_rate(u8::MAX, &DEFAULT_PREMIUM_CURVE).unwrap(), 200);
+    }
+
+    #[test]
+    fn default_curve_is_valid_and_capped_at_200_bps() {
+        assert!(is_valid_premium_curve(&DEFAULT_PREMIUM_CURVE));
+        let max_rate = (0..=100)
+            .map(|risk_score| calculate_premium_rate(risk_score, &DEFAULT_PREMIUM_CURVE).unwrap())
+            .max();
+        assert_eq!(max_rate, Some(200));
+    }
+
+    #[test]
+    fn curves_charging_over_100_percent_are_rejected() {
+        let mut curve = DEFAULT_PREMIUM_CURVE;
+        curve[PREMIUM_CURVE_POINTS - 1].rate_bps = MAX_PREMIUM_RATE_BPS;
+        assert!(is_valid_premium_curve(&curve));
+        curve[PREMIUM_CURVE_POINTS - 1].rate_bps = MAX_PREMIUM_RATE_BPS + 1;
+        assert!(!is_valid_premium_curve(&curve));
+    }
+}
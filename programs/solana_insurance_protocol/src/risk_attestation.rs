@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::risk_assessment::{apply_incident_history, assess_loss_experience, calculate_composite_risk_score};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ProtocolAutoDeactivated, ProtocolRiskUpdated, ErrorCode};
+
+// Attestations older than this are rejected even if never submitted before
+pub const MAX_ATTESTATION_AGE_SECS: i64 = 300;
+
+// Layout of the Ed25519 program instruction data
+const ED25519_HEADER_SIZE: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+const ED25519_PUBKEY_SIZE: usize = 32;
+
+// Risk scores computed off-chain by the registered risk oracle.
+// The oracle signs the Borsh serialization of this struct.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RiskAttestation {
+    pub protocol: Pubkey,
+    pub code_risk: u8,
+    pub economic_risk: u8,
+    pub operational_risk: u8,
+    pub attested_at: i64,
+}
+
+pub fn submit_risk_attestation(
+    ctx: Context<SubmitRiskAttestation>,
+    attestation: RiskAttestation,
+) -> Result<()> {
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let risk_oracle = ctx.accounts.protocol_state.risk_oracle;
+    let clock = Clock::get()?;
+
+    require!(risk_oracle != Pubkey::default(), ErrorCode::RiskOracleNotSet);
+    require!(
+        attestation.protocol == protocol_info.key(),
+        ErrorCode::AttestationProtocolMismatch
+    );
+    require!(
+        attestation.code_risk <= 100 &&
+        attestation.economic_risk <= 100 &&
+        attestation.operational_risk <= 100,
+        ErrorCode::InvalidRiskScore
+    );
+
+    // Reject replays of older attestations and ones signed too long ago
+    require!(
+        attestation.attested_at > protocol_info.last_risk_update &&
+        attestation.attested_at <= clock.unix_timestamp &&
+        clock.unix_timestamp - attestation.attested_at <= MAX_ATTESTATION_AGE_SECS,
+        ErrorCode::StaleRiskAttestation
+    );
+
+    let message = attestation.try_to_vec()?;
+    verify_oracle_signature(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &risk_oracle,
+        &message,
+    )?;
+
+    // Loss experience and incident history come from on-chain records rather than the oracle
+    let operational_risk = apply_incident_history(
+        attestation.operational_risk,
+        protocol_info.alerts_confirmed,
+    );
+    let loss_experience = assess_loss_experience(
+        protocol_info.premiums_earned,
+        protocol_info.claims_paid,
+    );
+    let risk_score = calculate_composite_risk_score(
+        attestation.code_risk,
+        attestation.economic_risk,
+        operational_risk,
+        loss_experience,
+    );
+    protocol_info.set_risk_components(
+        attestation.code_risk,
+        attestation.economic_risk,
+        operational_risk,
+    );
+    let max_insurable_risk_score = ctx.accounts.protocol_state.max_insurable_risk_score;
+    if protocol_info.apply_risk_score(risk_score, max_insurable_risk_score) {
+        emit!(ProtocolAutoDeactivated {
+            protocol: protocol_info.key(),
+            risk_score,
+            max_insurable_risk_score,
+        });
+    }
+    protocol_info.last_risk_update = attestation.attested_at;
+    ctx.accounts.registry.sync_active(protocol_info)?;
+    update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+        directory.record(protocol_info)
+    })?;
+
+    emit!(ProtocolRiskUpdated {
+        protocol: protocol_info.key(),
+        updated_by: risk_oracle,
+        risk_score,
+        code_risk: attestation.code_risk,
+        economic_risk: attestation.economic_risk,
+        operational_risk,
+        loss_experience,
+    });
+
+    Ok(())
+}
+
+// The signature itself is checked by the Ed25519 program in the preceding instruction;
+// here we only make sure it was over `message` and by `signer`
+pub(crate) fn verify_oracle_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    verify_oracle_signature_at(instructions_sysvar, 1, signer, message)
+}
+
+// Same as `verify_oracle_signature`, for the Ed25519 instruction `instructions_back`
+// positions before this one
+pub(crate) fn verify_oracle_signature_at(
+    instructions_sysvar: &AccountInfo,
+    instructions_back: u16,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index >= instructions_back && instructions_back > 0, ErrorCode::MissingEd25519Instruction);
+    let ed25519_ix = load_instruction_at_checked(
+        (current_index - instructions_back) as usize,
+        instructions_sysvar,
+    )?;
+
+    verify_ed25519_instruction(&ed25519_ix, signer, message)
+}
+
+// Checks that `ix` is an Ed25519 program instruction verifying exactly one signature
+// by `signer` over `message`, with all data contained in the instruction itself
+fn verify_ed25519_instruction(
+    ix: &Instruction,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    require!(ix.program_id == ed25519_program::ID, ErrorCode::MissingEd25519Instruction);
+    require!(ix.accounts.is_empty(), ErrorCode::InvalidEd25519Instruction);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE && data[0] == 1,
+        ErrorCode::InvalidEd25519Instruction
+    );
+
+    let read_u16 = |index: usize| u16::from_le_bytes([data[index], data[index + 1]]);
+    let offsets = ED25519_HEADER_SIZE;
+    let signature_instruction_index = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4) as usize;
+    let public_key_instruction_index = read_u16(offsets + 6);
+    let message_data_offset = read_u16(offsets + 8) as usize;
+    let message_data_size = read_u16(offsets + 10) as usize;
+    let message_instruction_index = read_u16(offsets + 12);
+
+    // u16::MAX means the data lives in the Ed25519 instruction itself
+    require!(
+        signature_instruction_index == u16::MAX &&
+        public_key_instruction_index == u16::MAX &&
+        message_instruction_index == u16::MAX,
+        ErrorCode::InvalidEd25519Instruction
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + ED25519_PUBKEY_SIZE)
+        .ok_or(ErrorCode::InvalidEd25519Instruction)?;
+    require!(public_key == signer.as_ref(), ErrorCode::AttestationSignerMismatch);
+
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ErrorCode::InvalidEd25519Instruction)?;
+    require!(signed_message == message, ErrorCode::AttestationMessageMismatch);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitRiskAttestation<'info> {
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    /// CHECK: address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
@@ -0,0 +1,317 @@
+use anchor_lang::prelude::*;
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::risk_assessment::{
+    apply_incident_history, assess_loss_experience, calculate_composite_risk_score, median_score,
+};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ProtocolAutoDeactivated, ErrorCode};
+
+pub const MAX_COMMITTEE_MEMBERS: usize = 7;
+
+#[account]
+pub struct RiskCommittee {
+    pub members: [Pubkey; MAX_COMMITTEE_MEMBERS],
+    pub member_count: u8,
+    pub quorum: u8,
+    pub epoch: u64, // Bumped on every membership change to invalidate open rounds
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl RiskCommittee {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 * MAX_COMMITTEE_MEMBERS + // members
+                           1 +      // member_count
+                           1 +      // quorum
+                           8 +      // epoch
+                           1 +      // bump
+                           1;       // version
+
+    pub fn member_index(&self, member: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|m| m == member)
+    }
+}
+
+// Open round of committee submissions for one protocol.
+// Scores are indexed by the member's position in the committee.
+#[account]
+pub struct RiskScoreRound {
+    pub protocol: Pubkey,
+    pub committee_epoch: u64,
+    pub round: u64,
+    pub submitted_mask: u8,
+    pub code_scores: [u8; MAX_COMMITTEE_MEMBERS],
+    pub economic_scores: [u8; MAX_COMMITTEE_MEMBERS],
+    pub operational_scores: [u8; MAX_COMMITTEE_MEMBERS],
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl RiskScoreRound {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           8 +      // committee_epoch
+                           8 +      // round
+                           1 +      // submitted_mask
+                           MAX_COMMITTEE_MEMBERS + // code_scores
+                           MAX_COMMITTEE_MEMBERS + // economic_scores
+                           MAX_COMMITTEE_MEMBERS + // operational_scores
+                           1 +      // bump
+                           1;       // version
+
+    fn reset(&mut self) {
+        self.submitted_mask = 0;
+        self.code_scores = [0; MAX_COMMITTEE_MEMBERS];
+        self.economic_scores = [0; MAX_COMMITTEE_MEMBERS];
+        self.operational_scores = [0; MAX_COMMITTEE_MEMBERS];
+    }
+
+    // Collects the submitted scores from one component array
+    fn submitted(&self, scores: &[u8; MAX_COMMITTEE_MEMBERS]) -> Vec<u8> {
+        (0..MAX_COMMITTEE_MEMBERS)
+            .filter(|i| self.submitted_mask & (1 << i) != 0)
+            .map(|i| scores[i])
+            .collect()
+    }
+}
+
+#[event]
+pub struct CommitteeRiskScoreApplied {
+    pub protocol: Pubkey,
+    pub round: u64,
+    pub submissions: u8,
+    pub risk_score: u8,
+}
+
+pub fn initialize_risk_committee(
+    ctx: Context<InitializeRiskCommittee>,
+    members: Vec<Pubkey>,
+    quorum: u8,
+) -> Result<()> {
+    let risk_committee = &mut ctx.accounts.risk_committee;
+    set_members(risk_committee, &members, quorum)?;
+    risk_committee.epoch = 0;
+    risk_committee.bump = ctx.bumps.risk_committee;
+    risk_committee.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn update_risk_committee(
+    ctx: Context<UpdateRiskCommittee>,
+    members: Vec<Pubkey>,
+    quorum: u8,
+) -> Result<()> {
+    let risk_committee = &mut ctx.accounts.risk_committee;
+    set_members(risk_committee, &members, quorum)?;
+    risk_committee.epoch = risk_committee.epoch.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+pub(crate) fn set_members(
+    risk_committee: &mut RiskCommittee,
+    members: &[Pubkey],
+    quorum: u8,
+) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_COMMITTEE_MEMBERS,
+        ErrorCode::InvalidCommitteeSize
+    );
+    require!(
+        quorum > 0 && quorum as usize <= members.len(),
+        ErrorCode::InvalidCommitteeQuorum
+    );
+
+    // Duplicate members would let one key count towards quorum more than once
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), ErrorCode::DuplicateCommitteeMember);
+    }
+
+    risk_committee.members = [Pubkey::default(); MAX_COMMITTEE_MEMBERS];
+    risk_committee.members[..members.len()].copy_from_slice(members);
+    risk_committee.member_count = members.len() as u8;
+    risk_committee.quorum = quorum;
+
+    Ok(())
+}
+
+pub fn submit_committee_score(
+    ctx: Context<SubmitCommitteeScore>,
+    code_risk: u8,
+    economic_risk: u8,
+    operational_risk: u8,
+) -> Result<()> {
+    let risk_committee = &ctx.accounts.risk_committee;
+    let risk_round = &mut ctx.accounts.risk_round;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let clock = Clock::get()?;
+
+    let member_index = risk_committee
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(ErrorCode::NotCommitteeMember)?;
+
+    require!(
+        code_risk <= 100 && economic_risk <= 100 && operational_risk <= 100,
+        ErrorCode::InvalidRiskScore
+    );
+
+    // A fresh round account, or one left open across a membership change, starts over
+    if risk_round.protocol == Pubkey::default() {
+        risk_round.protocol = protocol_info.key();
+        risk_round.round = 0;
+        risk_round.bump = ctx.bumps.risk_round;
+        risk_round.version = ACCOUNT_VERSION;
+    }
+    if risk_round.committee_epoch != risk_committee.epoch {
+        risk_round.reset();
+        risk_round.committee_epoch = risk_committee.epoch;
+    }
+
+    // Members may revise their submission until the round reaches quorum
+    risk_round.code_scores[member_index] = code_risk;
+    risk_round.economic_scores[member_index] = economic_risk;
+    risk_round.operational_scores[member_index] = operational_risk;
+    risk_round.submitted_mask |= 1 << member_index;
+
+    let submissions = risk_round.submitted_mask.count_ones() as u8;
+    if submissions < risk_committee.quorum {
+        return Ok(());
+    }
+
+    // Quorum reached: the median of each component becomes effective
+    let code_risk = median_score(&mut risk_round.submitted(&risk_round.code_scores));
+    let economic_risk = median_score(&mut risk_round.submitted(&risk_round.economic_scores));
+    let operational_risk = apply_incident_history(
+        median_score(&mut risk_round.submitted(&risk_round.operational_scores)),
+        protocol_info.alerts_confirmed,
+    );
+    let loss_experience = assess_loss_experience(
+        protocol_info.premiums_earned,
+        protocol_info.claims_paid,
+    );
+    let risk_score = calculate_composite_risk_score(
+        code_risk,
+        economic_risk,
+        operational_risk,
+        loss_experience,
+    );
+
+    protocol_info.set_risk_components(code_risk, economic_risk, operational_risk);
+    let max_insurable_risk_score = ctx.accounts.protocol_state.max_insurable_risk_score;
+    if protocol_info.apply_risk_score(risk_score, max_insurable_risk_score) {
+        emit!(ProtocolAutoDeactivated {
+            protocol: protocol_info.key(),
+            risk_score,
+            max_insurable_risk_score,
+        });
+    }
+    protocol_info.last_risk_update = clock.unix_timestamp;
+    ctx.accounts.registry.sync_active(protocol_info)?;
+    update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+        directory.record(protocol_info)
+    })?;
+
+    emit!(CommitteeRiskScoreApplied {
+        protocol: protocol_info.key(),
+        round: risk_round.round,
+        submissions,
+        risk_score,
+    });
+
+    risk_round.reset();
+    risk_round.round = risk_round.round.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRiskCommittee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RiskCommittee::SIZE,
+        seeds = [b"risk-committee"],
+        bump
+    )]
+    pub risk_committee: Account<'info, RiskCommittee>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRiskCommittee<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"risk-committee"],
+        bump = risk_committee.bump
+    )]
+    pub risk_committee: Account<'info, RiskCommittee>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitCommitteeScore<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(
+        seeds = [b"risk-committee"],
+        bump = risk_committee.bump
+    )]
+    pub risk_committee: Account<'info, RiskCommittee>,
+
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = RiskScoreRound::SIZE,
+        seeds = [b"risk-round", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub risk_round: Account<'info, RiskScoreRound>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::audit_registry::AuditFirm;
+use crate::risk_assessment::ALL_SAFEGUARDS;
+use crate::{ProtocolInfo, ErrorCode};
+
+#[event]
+pub struct SafeguardsAttested {
+    pub protocol: Pubkey,
+    pub assessor: Pubkey,
+    pub safeguard_flags: u8,
+}
+
+#[event]
+pub struct SafeguardAssessorEngaged {
+    pub protocol: Pubkey,
+    pub assessor: Pubkey,
+}
+
+// The protocol picks which whitelisted firm assesses its safeguards, so a firm it
+// hasn't engaged can't set or strip its discounts. Flags already attested stand until
+// the new assessor attests.
+pub fn engage_safeguard_assessor(ctx: Context<EngageSafeguardAssessor>) -> Result<()> {
+    let protocol_info = &mut ctx.accounts.protocol_info;
+
+    require!(ctx.accounts.audit_firm.is_active, ErrorCode::AuditorNotWhitelisted);
+    protocol_info.safeguard_assessor = ctx.accounts.audit_firm.auditor;
+
+    emit!(SafeguardAssessorEngaged {
+        protocol: protocol_info.key(),
+        assessor: protocol_info.safeguard_assessor,
+    });
+
+    Ok(())
+}
+
+pub fn attest_safeguards(
+    ctx: Context<AttestSafeguards>,
+    safeguard_flags: u8,
+) -> Result<()> {
+    let protocol_info = &mut ctx.accounts.protocol_info;
+
+    require!(ctx.accounts.audit_firm.is_active, ErrorCode::AuditorNotWhitelisted);
+    require!(
+        protocol_info.safeguard_assessor == ctx.accounts.assessor.key(),
+        ErrorCode::AssessorNotEngaged
+    );
+    require!(safeguard_flags & !ALL_SAFEGUARDS == 0, ErrorCode::InvalidSafeguardFlags);
+
+    // A new attestation replaces the previous one, so safeguards can also be revoked
+    protocol_info.safeguard_flags = safeguard_flags;
+
+    emit!(SafeguardsAttested {
+        protocol: protocol_info.key(),
+        assessor: protocol_info.safeguard_assessor,
+        safeguard_flags,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EngageSafeguardAssessor<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"audit-firm", audit_firm.auditor.as_ref()],
+        bump = audit_firm.bump
+    )]
+    pub audit_firm: Account<'info, AuditFirm>,
+
+    #[account(
+        mut,
+        constraint = protocol_info.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AttestSafeguards<'info> {
+    pub assessor: Signer<'info>,
+
+    // Safeguards are attested by the same whitelisted firms that attach audits, and
+    // only by the one the protocol engaged
+    #[account(
+        seeds = [b"audit-firm", assessor.key().as_ref()],
+        bump = audit_firm.bump
+    )]
+    pub audit_firm: Account<'info, AuditFirm>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+}
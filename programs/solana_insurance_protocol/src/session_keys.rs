@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::ErrorCode;
+
+// Actions a session key can be scoped to
+pub const SESSION_PERMISSION_SUBMIT_HEARTBEAT: u8 = 1 << 0;
+pub const SESSION_PERMISSION_ACKNOWLEDGE_ALERT: u8 = 1 << 1;
+pub const ALL_SESSION_PERMISSIONS: u8 = SESSION_PERMISSION_SUBMIT_HEARTBEAT | SESSION_PERMISSION_ACKNOWLEDGE_ALERT;
+
+// Longest a session key can stay valid for
+pub const MAX_SESSION_DURATION_SECS: i64 = 30 * 24 * 60 * 60;
+
+// Lets a hot key sign a limited set of routine instructions on the owner's behalf
+// until it expires, so the owner's key can stay offline
+#[account]
+pub struct SessionKey {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: u8, // SESSION_PERMISSION_* flags
+    pub expires_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SessionKey {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // owner
+                           32 +     // delegate
+                           1 +      // permissions
+                           8 +      // expires_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct SessionKeyCreated {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: u8,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SessionKeyRevoked {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+}
+
+// Passes if `signer` is one of `owners`, or holds an unexpired session key from one
+// of them that covers `permission`
+pub fn authorize_with_session(
+    signer: Pubkey,
+    owners: &[Pubkey],
+    session_key: &Option<Account<SessionKey>>,
+    permission: u8,
+) -> Result<()> {
+    if owners.contains(&signer) {
+        return Ok(());
+    }
+
+    let session_key = session_key.as_ref().ok_or(ErrorCode::UnauthorizedAccess)?;
+    require!(
+        session_key.delegate == signer && owners.contains(&session_key.owner),
+        ErrorCode::UnauthorizedAccess
+    );
+    require!(
+        session_key.permissions & permission == permission,
+        ErrorCode::SessionKeyNotPermitted
+    );
+    require!(
+        Clock::get()?.unix_timestamp < session_key.expires_at,
+        ErrorCode::SessionKeyExpired
+    );
+
+    Ok(())
+}
+
+// Creating a key for a delegate that already has one replaces its scope and expiry
+pub fn create_session_key(
+    ctx: Context<CreateSessionKey>,
+    delegate: Pubkey,
+    permissions: u8,
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        permissions != 0 && permissions & !ALL_SESSION_PERMISSIONS == 0,
+        ErrorCode::InvalidSessionKey
+    );
+    require!(
+        expires_at > now && expires_at <= now.checked_add(MAX_SESSION_DURATION_SECS).ok_or(ErrorCode::MathOverflow)?,
+        ErrorCode::InvalidSessionKey
+    );
+
+    let session_key = &mut ctx.accounts.session_key;
+    session_key.owner = ctx.accounts.owner.key();
+    session_key.delegate = delegate;
+    session_key.permissions = permissions;
+    session_key.expires_at = expires_at;
+    session_key.bump = ctx.bumps.session_key;
+    session_key.version = ACCOUNT_VERSION;
+
+    emit!(SessionKeyCreated {
+        owner: session_key.owner,
+        delegate,
+        permissions,
+        expires_at,
+    });
+
+    Ok(())
+}
+
+pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+    emit!(SessionKeyRevoked {
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.session_key.delegate,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct CreateSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SessionKey::SIZE,
+        seeds = [b"session-key", owner.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"session-key", owner.key().as_ref(), session_key.delegate.as_ref()],
+        bump = session_key.bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+}
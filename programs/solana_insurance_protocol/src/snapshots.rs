@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolInfo, ErrorCode};
+
+pub const MAX_SNAPSHOT_ACCOUNTS: usize = 32;
+
+// Hash of a set of a covered protocol's accounts as they were at a slot. A claimant
+// can cite it in their claim's evidence to show a position existed before an exploit:
+// the resolver recomputes the hash from the accounts at that slot and compares.
+#[account]
+pub struct SnapshotAttestation {
+    pub protocol: Pubkey,
+    pub slot: u64,
+    pub taken_at: i64,
+    pub snapshot_hash: [u8; 32],
+    pub account_count: u16,
+    pub taken_by: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SnapshotAttestation {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           8 +      // slot
+                           8 +      // taken_at
+                           32 +     // snapshot_hash
+                           2 +      // account_count
+                           32 +     // taken_by
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct SnapshotAttested {
+    pub snapshot: Pubkey,
+    pub protocol: Pubkey,
+    pub slot: u64,
+    pub snapshot_hash: [u8; 32],
+    pub account_count: u16,
+}
+
+// Chains each account's key, owner, lamports and data in the order given
+pub fn hash_accounts(accounts: &[AccountInfo]) -> Result<[u8; 32]> {
+    let mut snapshot_hash = [0u8; 32];
+    for info in accounts {
+        let data = info.try_borrow_data()?;
+        snapshot_hash = hashv(&[
+            &snapshot_hash,
+            info.key.as_ref(),
+            info.owner.as_ref(),
+            &info.lamports().to_le_bytes(),
+            &data,
+        ]).to_bytes();
+    }
+    Ok(snapshot_hash)
+}
+
+// Permissionless. The accounts to hash are passed as remaining accounts, and the slot
+// must be the current one since that is the only state the program can see.
+pub fn attest_snapshot(ctx: Context<AttestSnapshot>, slot: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(slot == clock.slot, ErrorCode::SnapshotSlotMismatch);
+    require!(
+        !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() <= MAX_SNAPSHOT_ACCOUNTS,
+        ErrorCode::InvalidSnapshotAccounts
+    );
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.protocol = ctx.accounts.protocol_info.key();
+    snapshot.slot = slot;
+    snapshot.taken_at = clock.unix_timestamp;
+    snapshot.snapshot_hash = hash_accounts(ctx.remaining_accounts)?;
+    snapshot.account_count = ctx.remaining_accounts.len() as u16;
+    snapshot.taken_by = ctx.accounts.payer.key();
+    snapshot.bump = ctx.bumps.snapshot;
+    snapshot.version = ACCOUNT_VERSION;
+
+    emit!(SnapshotAttested {
+        snapshot: snapshot.key(),
+        protocol: snapshot.protocol,
+        slot,
+        snapshot_hash: snapshot.snapshot_hash,
+        account_count: snapshot.account_count,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(slot: u64)]
+pub struct AttestSnapshot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = SnapshotAttestation::SIZE,
+        seeds = [b"snapshot", protocol_info.key().as_ref(), &slot.to_le_bytes()],
+        bump
+    )]
+    pub snapshot: Account<'info, SnapshotAttestation>,
+
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::capital_management::POOL_TYPES;
+use crate::integration::capital_pool_address;
+use crate::{CapitalPool, ErrorCode};
+
+#[account]
+pub struct SolvencyReport {
+    pub report_id: u64,
+    pub run_by: Pubkey,
+    pub run_time: i64,
+    pub loss_bps: u64,          // Hypothetical loss as a share of active coverage
+    pub pool_count: u8,         // Tiers with a pool
+    pub total_active_coverage: u64,
+    pub total_available_capital: u64,
+    pub total_reinsurance: u64,
+    pub projected_loss: u64,
+    pub total_shortfall: u64,   // Sum of per-pool shortfalls
+    pub insolvent_pool_count: u8,
+    pub is_solvent: bool,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SolvencyReport {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // report_id
+                           32 +     // run_by
+                           8 +      // run_time
+                           8 +      // loss_bps
+                           1 +      // pool_count
+                           8 +      // total_active_coverage
+                           8 +      // total_available_capital
+                           8 +      // total_reinsurance
+                           8 +      // projected_loss
+                           8 +      // total_shortfall
+                           1 +      // insolvent_pool_count
+                           1 +      // is_solvent
+                           1 +      // bump
+                           1;       // version
+}
+
+// Runs the loss scenario against every capital pool tier. `remaining_accounts` holds
+// each tier's pool PDA in pool type order; a tier whose pool was never created is
+// passed all the same and counts as empty, so the caller can't leave a pool out.
+// Each pool has to absorb its own losses since pool capital isn't shared across tiers.
+pub fn run_stress_test(
+    ctx: Context<RunStressTest>,
+    report_id: u64,
+    loss_bps: u64,
+) -> Result<()> {
+    require!(loss_bps <= 10000, ErrorCode::InvalidLossScenario);
+    require!(ctx.remaining_accounts.len() == POOL_TYPES.len(), ErrorCode::InvalidStressTestPool);
+
+    let mut pool_count: u8 = 0;
+    let mut total_active_coverage: u64 = 0;
+    let mut total_available_capital: u64 = 0;
+    let mut total_reinsurance: u64 = 0;
+    let mut projected_loss: u64 = 0;
+    let mut total_shortfall: u64 = 0;
+    let mut insolvent_pool_count: u8 = 0;
+
+    for (pool_info, pool_type) in ctx.remaining_accounts.iter().zip(POOL_TYPES) {
+        require!(pool_info.key() == capital_pool_address(pool_type).0, ErrorCode::InvalidStressTestPool);
+        if pool_info.owner != &crate::ID {
+            continue;
+        }
+        pool_count += 1;
+
+        let pool = CapitalPool::try_deserialize(&mut &pool_info.data.borrow()[..])?;
+
+        let pool_loss = (pool.active_coverage as u128 * loss_bps as u128 / 10000) as u64;
+        let pool_resources = pool.available_capital.saturating_add(pool.reinsurance_capacity);
+        if pool_loss > pool_resources {
+            insolvent_pool_count = insolvent_pool_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+            total_shortfall = total_shortfall.checked_add(pool_loss - pool_resources).ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        total_active_coverage = total_active_coverage.checked_add(pool.active_coverage).ok_or(ErrorCode::MathOverflow)?;
+        total_available_capital = total_available_capital.checked_add(pool.available_capital).ok_or(ErrorCode::MathOverflow)?;
+        total_reinsurance = total_reinsurance.checked_add(pool.reinsurance_capacity).ok_or(ErrorCode::MathOverflow)?;
+        projected_loss = projected_loss.checked_add(pool_loss).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    let report = &mut ctx.accounts.solvency_report;
+    report.report_id = report_id;
+    report.run_by = ctx.accounts.payer.key();
+    report.run_time = Clock::get()?.unix_timestamp;
+    report.loss_bps = loss_bps;
+    report.pool_count = pool_count;
+    report.total_active_coverage = total_active_coverage;
+    report.total_available_capital = total_available_capital;
+    report.total_reinsurance = total_reinsurance;
+    report.projected_loss = projected_loss;
+    report.total_shortfall = total_shortfall;
+    report.insolvent_pool_count = insolvent_pool_count;
+    report.is_solvent = insolvent_pool_count == 0;
+    report.bump = ctx.bumps.solvency_report;
+    report.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(report_id: u64)]
+pub struct RunStressTest<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = SolvencyReport::SIZE,
+        seeds = [b"solvency-report", payer.key().as_ref(), &report_id.to_le_bytes()],
+        bump
+    )]
+    pub solvency_report: Account<'info, SolvencyReport>,
+
+    pub system_program: Program<'info, System>,
+}
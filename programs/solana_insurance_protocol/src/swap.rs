@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{program::invoke, pubkey};
+use anchor_spl::token::TokenAccount;
+use crate::ErrorCode;
+
+// Jupiter v6 aggregator
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+// Runs a Jupiter route quoted off-chain, passed as the Jupiter program followed by the
+// route's accounts, and checks that it paid at least `min_amount_out` into `destination`.
+// The route signs with the same keys the outer transaction did and nothing else.
+pub fn swap_into(
+    accounts: &[AccountInfo],
+    destination: &mut Account<TokenAccount>,
+    swap_data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<u64> {
+    let (jupiter_program, route_accounts) = accounts.split_first().ok_or(ErrorCode::InvalidSwapProgram)?;
+    require_keys_eq!(jupiter_program.key(), JUPITER_PROGRAM_ID, ErrorCode::InvalidSwapProgram);
+    require!(min_amount_out > 0, ErrorCode::SwapSlippageExceeded);
+
+    let instruction = Instruction {
+        program_id: JUPITER_PROGRAM_ID,
+        accounts: route_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+
+    let balance_before = destination.amount;
+    invoke(&instruction, accounts)?;
+    destination.reload()?;
+
+    let amount_out = destination.amount.saturating_sub(balance_before);
+    require!(amount_out >= min_amount_out, ErrorCode::SwapSlippageExceeded);
+
+    Ok(amount_out)
+}
@@ -0,0 +1,397 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData};
+use anchor_spl::token;
+use crate::capital_management::CapitalPoolType;
+use crate::integration::{
+    capital_pool_address, capital_provider_address, claim_address, config_address, global_stats_address,
+    legacy_claim_address, mint_registry_address, policy_address, policy_counter_address,
+    protocol_entry_address, protocol_info_address, protocol_state_address, treasury_vault_address,
+};
+
+// Instruction builders for end-to-end scenarios, behind the `testing` feature. They
+// fill in every derived address, so a test on solana-program-test (or any other bank)
+// only supplies signers, mints and token accounts. The program-test crate at the repo
+// root wraps them in a bank with a movable clock.
+//
+// A fresh deployment takes, in order: `initialize`, `initialize_config`,
+// `initialize_global_stats`, `initialize_mint_registry` and `register_mint` for each
+// token, then `register_protocol`, `initialize_capital_pool` and `provide_capital` for
+// each protocol and pool the scenario needs. Selling policies also takes
+// `initialize_treasury`, `add_treasury_vault` and `set_fee_vault` for the premium token.
+
+pub fn protocol_registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"protocol-registry"], &crate::ID).0
+}
+
+pub fn treasury_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury"], &crate::ID).0
+}
+
+pub fn claims_committee_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"claims-committee"], &crate::ID).0
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize(authority: Pubkey, protocol_fee: u64) -> Instruction {
+    instruction(
+        crate::accounts::Initialize {
+            authority,
+            protocol_state: protocol_state_address().0,
+            registry: protocol_registry_address(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::Initialize { protocol_fee },
+    )
+}
+
+pub fn initialize_config(authority: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitializeConfig {
+            authority,
+            config: config_address().0,
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitializeConfig {},
+    )
+}
+
+pub fn initialize_global_stats(authority: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitializeGlobalStats {
+            authority,
+            global_stats: global_stats_address().0,
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitializeGlobalStats {},
+    )
+}
+
+pub fn initialize_mint_registry(authority: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitializeMintRegistry {
+            authority,
+            mint_registry: mint_registry_address().0,
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitializeMintRegistry {},
+    )
+}
+
+// Lists the mint without a price feed, caps or minimum premium
+pub fn register_mint(authority: Pubkey, mint: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::RegisterMint {
+            authority,
+            mint_registry: mint_registry_address().0,
+            mint,
+            protocol_state: protocol_state_address().0,
+        },
+        crate::instruction::RegisterMint {
+            price_feed_id: [0; 32],
+            max_policy_coverage: 0,
+            max_pool_capital: 0,
+            min_premium: 0,
+        },
+    )
+}
+
+// `registry_index` is the registry's next_index, which is the number of protocols
+// registered so far in a fresh deployment
+pub fn register_protocol(
+    protocol_authority: Pubkey,
+    registry_index: u64,
+    protocol_name: String,
+    tvl_usd: u64,
+) -> Instruction {
+    instruction(
+        crate::accounts::RegisterProtocol {
+            authority: protocol_authority,
+            protocol_info: protocol_info_address(&protocol_authority).0,
+            registry: protocol_registry_address(),
+            protocol_entry: protocol_entry_address(registry_index).0,
+            registration_approval: None,
+            directory: None,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RegisterProtocol { protocol_name, tvl_usd },
+    )
+}
+
+pub fn initialize_capital_pool(
+    authority: Pubkey,
+    pool_type: CapitalPoolType,
+    yield_rate_bps: u64,
+    token_mint: Pubkey,
+    pool_token_account: Pubkey,
+) -> Instruction {
+    instruction(
+        crate::accounts::InitializeCapitalPool {
+            authority,
+            capital_pool: capital_pool_address(pool_type).0,
+            token_mint,
+            pool_token_account,
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            rent: sysvar::rent::ID,
+            mint_registry: mint_registry_address().0,
+        },
+        crate::instruction::InitializeCapitalPool { pool_type, yield_rate_bps },
+    )
+}
+
+pub fn provide_capital(
+    owner: Pubkey,
+    pool_type: CapitalPoolType,
+    token_mint: Pubkey,
+    provider_token: Pubkey,
+    pool_token_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let capital_pool = capital_pool_address(pool_type).0;
+    instruction(
+        crate::accounts::ProvideCapital {
+            owner,
+            capital_provider: capital_provider_address(&owner, &capital_pool).0,
+            capital_pool,
+            provider_token,
+            pool_token_account,
+            protocol_state: protocol_state_address().0,
+            global_stats: global_stats_address().0,
+            system_program: system_program::ID,
+            token_mint,
+            token_program: token::ID,
+            rent: sysvar::rent::ID,
+            mint_registry: mint_registry_address().0,
+            governance: None,
+            governance_stake: None,
+            config: config_address().0,
+        },
+        crate::instruction::ProvideCapital { amount },
+    )
+}
+
+pub fn checkpoint_rewards(owner: Pubkey, pool_type: CapitalPoolType) -> Instruction {
+    let capital_pool = capital_pool_address(pool_type).0;
+    instruction(
+        crate::accounts::CheckpointRewards {
+            owner,
+            capital_provider: capital_provider_address(&owner, &capital_pool).0,
+            capital_pool,
+            governance: None,
+            governance_stake: None,
+            config: config_address().0,
+        },
+        crate::instruction::CheckpointRewards {},
+    )
+}
+
+pub fn initialize_treasury(authority: Pubkey, treasurer: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitializeTreasury {
+            authority,
+            treasury: treasury_address(),
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitializeTreasury { treasurer },
+    )
+}
+
+// `vault_token` is a token account of the mint owned by `treasury_address()`
+pub fn add_treasury_vault(authority: Pubkey, mint: Pubkey, vault_token: Pubkey, spend_limit: u64) -> Instruction {
+    instruction(
+        crate::accounts::AddTreasuryVault {
+            authority,
+            treasury: treasury_address(),
+            mint,
+            treasury_vault: treasury_vault_address(&mint).0,
+            vault_token,
+            protocol_state: protocol_state_address().0,
+            system_program: system_program::ID,
+        },
+        crate::instruction::AddTreasuryVault { spend_limit, window_secs: 24 * 60 * 60 },
+    )
+}
+
+// `fee_vault` is a token account owned by `protocol_state_address()`
+pub fn set_fee_vault(authority: Pubkey, fee_vault: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::SetFeeVault {
+            authority,
+            protocol_state: protocol_state_address().0,
+            fee_vault,
+        },
+        crate::instruction::SetFeeVault {},
+    )
+}
+
+pub fn add_claim_resolver(authority: Pubkey, resolver: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ConfigureClaimResolvers {
+            authority,
+            protocol_state: protocol_state_address().0,
+        },
+        crate::instruction::AddClaimResolver { resolver },
+    )
+}
+
+// The insured's side of a policy purchase
+pub struct PremiumPayment {
+    pub insured_token: Pubkey, // Pays the premium
+    pub premium_mint: Pubkey,  // Needs a treasury vault
+    pub fee_vault: Pubkey,     // The protocol state's fee vault
+}
+
+// `policy_index` is the insured's policy counter, 0 for a first purchase
+pub fn create_policy(
+    insured: Pubkey,
+    protocol_info: Pubkey,
+    policy_index: u64,
+    pool_type: CapitalPoolType,
+    payment: &PremiumPayment,
+    terms: crate::instruction::CreatePolicy,
+) -> Instruction {
+    let policy = policy_address(&insured, &protocol_info, policy_index).0;
+    instruction(
+        crate::accounts::CreatePolicy {
+            insured,
+            policy_counter: policy_counter_address(&insured).0,
+            policy,
+            protocol_info,
+            protocol_state: protocol_state_address().0,
+            global_stats: global_stats_address().0,
+            config: config_address().0,
+            capital_pool: capital_pool_address(pool_type).0,
+            insured_token: payment.insured_token,
+            treasury_vault: treasury_vault_address(&payment.premium_mint).0,
+            fee_vault: Some(payment.fee_vault),
+            revenue_share_token: None,
+            governance: None,
+            governance_stake: None,
+            premium_mint: payment.premium_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            premium_escrow: Pubkey::find_program_address(&[b"premium-escrow", policy.as_ref()], &crate::ID).0,
+            memo_program: None,
+            mint_registry: mint_registry_address().0,
+        },
+        terms,
+    )
+}
+
+// `claim_index` is the policy's claim_count, 0 for its first claim
+pub fn submit_claim(
+    claimant: Pubkey,
+    policy: Pubkey,
+    protocol_info: Pubkey,
+    claim_index: u32,
+    amount: u64,
+    evidence: String,
+) -> Instruction {
+    instruction(
+        crate::accounts::SubmitClaim {
+            claimant,
+            policy,
+            protocol_info,
+            protocol_state: protocol_state_address().0,
+            config: config_address().0,
+            loss_event: None,
+            claim: claim_address(&policy, claim_index).0,
+            system_program: system_program::ID,
+            legacy_claim: legacy_claim_address(&policy).0,
+        },
+        crate::instruction::SubmitClaim { amount, evidence },
+    )
+}
+
+pub fn resolve_claim(
+    resolver: Pubkey,
+    policy: Pubkey,
+    claim_index: u32,
+    protocol_info: Pubkey,
+    pool_type: CapitalPoolType,
+    approve: bool,
+) -> Instruction {
+    instruction(
+        crate::accounts::ResolveClaim {
+            resolver,
+            claim: claim_address(&policy, claim_index).0,
+            policy,
+            protocol_info,
+            protocol_state: protocol_state_address().0,
+            global_stats: global_stats_address().0,
+            config: config_address().0,
+            capital_pool: capital_pool_address(pool_type).0,
+            claims_committee: claims_committee_address(),
+        },
+        crate::instruction::ResolveClaim { approve, resolution_notes: String::new() },
+    )
+}
+
+fn pending_change_address(change_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"pending-change", &change_id.to_le_bytes()], &crate::ID).0
+}
+
+// `change_id` is the protocol state's change_count, 0 for the first change scheduled.
+// Claims committee changes also need the realm and governance, so aren't covered here.
+pub fn schedule_change(authority: Pubkey, change_id: u64, change: crate::timelock::ParameterChange) -> Instruction {
+    instruction(
+        crate::accounts::ScheduleChange {
+            authority,
+            protocol_state: protocol_state_address().0,
+            pending_change: pending_change_address(change_id),
+            realm: None,
+            governance: None,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ScheduleChange { change },
+    )
+}
+
+// Executes a change to the protocol state itself, once its timelock has passed
+pub fn execute_change(caller: Pubkey, change_id: u64) -> Instruction {
+    instruction(
+        crate::accounts::ExecuteChange {
+            caller,
+            pending_change: pending_change_address(change_id),
+            protocol_state: protocol_state_address().0,
+            capital_pool: None,
+            config: None,
+            oracle_config: None,
+            watcher_registry: None,
+            treasury_vault: None,
+            price_feeds: None,
+            protocol_info: None,
+            claims_committee: None,
+        },
+        crate::instruction::ExecuteChange {},
+    )
+}
+
+// Must follow an Ed25519 instruction with the risk oracle's signature over the
+// attestation; for a registry without a directory
+pub fn submit_risk_attestation(attestation: crate::risk_attestation::RiskAttestation) -> Instruction {
+    instruction(
+        crate::accounts::SubmitRiskAttestation {
+            protocol_info: attestation.protocol,
+            protocol_state: protocol_state_address().0,
+            instructions_sysvar: sysvar::instructions::ID,
+            registry: protocol_registry_address(),
+            directory: None,
+        },
+        crate::instruction::SubmitRiskAttestation { attestation },
+    )
+}
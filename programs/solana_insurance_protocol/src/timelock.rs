@@ -0,0 +1,585 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::capital_management::{CapitalPool, CapitalPoolType, PayoutLimits};
+use crate::circuit_breaker::MAX_CIRCUIT_BREAKER_THRESHOLD;
+use crate::config::{Config, ConfigParams, FeeRates, ALL_FEATURES};
+use crate::exploit_detection::AlertResponseConfig;
+use crate::fees::{set_protocol_fee_terms, validate_protocol_fee_terms};
+use crate::oracle_deviation::PriceFeedPair;
+use crate::oracle_guards::{OracleConfig, OracleGuards};
+use crate::realms::{check_realms_governance, set_claims_committee, ClaimsCommittee};
+use crate::risk_assessment::{is_valid_premium_curve, PREMIUM_CURVE_POINTS, SAFEGUARD_COUNT};
+use crate::treasury::TreasuryVault;
+use crate::tvl_monitor::TvlAnomalyConfig;
+use crate::watchers::{WatcherRegistry, MAX_ALERT_CONFIRMATIONS};
+use crate::{PremiumCurvePoint, ProtocolInfo, ProtocolState, RiskWeights, ErrorCode};
+
+// Delay between scheduling a configuration change and applying it,
+// giving policy holders and LPs time to exit first
+pub const CHANGE_TIMELOCK_SECS: i64 = 2 * 24 * 60 * 60;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ParameterChange {
+    MaxInsurableRiskScore {
+        max_insurable_risk_score: u8,
+    },
+    PremiumCurve {
+        premium_curve: [PremiumCurvePoint; PREMIUM_CURVE_POINTS],
+    },
+    SafeguardDiscounts {
+        safeguard_discount_bps: [u64; SAFEGUARD_COUNT],
+    },
+    RiskOracle {
+        risk_oracle: Pubkey,
+    },
+    AlertResponse {
+        alert_response: AlertResponseConfig,
+    },
+    TvlAnomaly {
+        tvl_anomaly: TvlAnomalyConfig,
+    },
+    CircuitBreaker {
+        threshold: u8,
+        window_secs: i64,
+    },
+    PoolRiskProfile {
+        pool_type: CapitalPoolType,
+        risk_weights: RiskWeights,
+        max_risk_score: u8,
+    },
+    PoolReinsurance {
+        pool_type: CapitalPoolType,
+        reinsurance_capacity: u64,
+    },
+    Config {
+        params: ConfigParams,
+        fee_rates: FeeRates,
+    },
+    PoolPayoutLimits {
+        pool_type: CapitalPoolType,
+        payout_limits: PayoutLimits,
+    },
+    OracleGuards {
+        feed_id: [u8; 32],            // Zero for the default guards
+        guards: Option<OracleGuards>, // None removes the feed's override
+    },
+    AlertBounty {
+        max_alert_bounty: u64,
+    },
+    WatcherSlashBps {
+        slash_bps: u64,
+    },
+    AlertConfirmationThreshold {
+        confirmation_threshold: u8,
+    },
+    WatcherRateLimit {
+        max_alerts_per_window: u8,
+        alert_window_secs: i64,
+    },
+    TrustedWatchers {
+        trusted_reputation: u8,
+        trusted_confirmation_threshold: u8,
+    },
+    AlertBond {
+        alert_bond: u64,
+    },
+    // Lowering a limit doesn't need to wait; see set_treasury_spend_limit
+    TreasurySpendLimit {
+        mint: Pubkey,
+        spend_limit: u64,
+        window_secs: i64,
+    },
+    // Switching features off doesn't need to wait; see set_features
+    Features {
+        features: u8,
+    },
+    // A protocol's first feed pair is set up directly; see configure_price_feeds
+    PriceFeeds {
+        protocol: Pubkey,
+        primary_feed: Pubkey,
+        secondary_feed: Pubkey,
+        max_deviation_bps: u64,
+    },
+    ProtocolFeeTerms {
+        protocol: Pubkey,
+        fee_override_bps: Option<u64>,
+        revenue_share_bps: u64,
+        revenue_share_account: Pubkey, // The protocol's treasury token account
+    },
+    // Counts against the protocol's operational risk from its next assessment, and
+    // enables price deviation monitoring
+    OracleDependency {
+        protocol: Pubkey,
+        oracle_dependency: bool,
+    },
+    // A first committee is set up directly; see configure_claims_committee
+    ClaimsCommittee {
+        realm: Pubkey,
+        governance: Pubkey,
+        large_claim_threshold: u64,
+    },
+}
+
+impl ParameterChange {
+    // Largest variant is PriceFeeds
+    pub const SIZE: usize = 1 + 32 + 32 + 32 + 8;
+
+    // Changes are checked when scheduled so a due change can't fail on execution
+    fn validate(&self) -> Result<()> {
+        match self {
+            ParameterChange::MaxInsurableRiskScore { max_insurable_risk_score } => {
+                require!(*max_insurable_risk_score <= 100, ErrorCode::InvalidRiskScore);
+            }
+            ParameterChange::PremiumCurve { premium_curve } => {
+                require!(is_valid_premium_curve(premium_curve), ErrorCode::InvalidPremiumCurve);
+            }
+            ParameterChange::SafeguardDiscounts { safeguard_discount_bps } => {
+                // Even with every safeguard attested the premium can't go negative
+                let total_discount_bps = safeguard_discount_bps
+                    .iter()
+                    .try_fold(0u64, |total, discount| total.checked_add(*discount));
+                require!(
+                    matches!(total_discount_bps, Some(total) if total <= 10000),
+                    ErrorCode::InvalidSafeguardDiscount
+                );
+            }
+            ParameterChange::RiskOracle { .. } => {}
+            ParameterChange::AlertResponse { alert_response } => {
+                require!(alert_response.is_valid(), ErrorCode::InvalidAlertResponseConfig);
+            }
+            ParameterChange::TvlAnomaly { tvl_anomaly } => {
+                require!(tvl_anomaly.is_valid(), ErrorCode::InvalidTvlAnomalyConfig);
+            }
+            ParameterChange::CircuitBreaker { threshold, window_secs } => {
+                require!(
+                    *threshold > 0 && *threshold as usize <= MAX_CIRCUIT_BREAKER_THRESHOLD && *window_secs > 0,
+                    ErrorCode::InvalidCircuitBreakerConfig
+                );
+            }
+            ParameterChange::PoolRiskProfile { risk_weights, max_risk_score, .. } => {
+                require!(risk_weights.is_valid(), ErrorCode::InvalidRiskWeights);
+                require!(*max_risk_score <= 100, ErrorCode::InvalidRiskScore);
+            }
+            ParameterChange::PoolReinsurance { .. } => {}
+            ParameterChange::Config { params, fee_rates } => {
+                require!(params.is_valid(), ErrorCode::InvalidConfig);
+                require!(fee_rates.is_valid(), ErrorCode::InvalidConfig);
+            }
+            ParameterChange::PoolPayoutLimits { payout_limits, .. } => {
+                require!(payout_limits.is_valid(), ErrorCode::InvalidPayoutLimits);
+            }
+            ParameterChange::OracleGuards { feed_id, guards } => {
+                match guards {
+                    Some(guards) => require!(guards.is_valid(), ErrorCode::InvalidOracleGuards),
+                    None => require!(*feed_id != [0; 32], ErrorCode::InvalidOracleGuards),
+                }
+            }
+            ParameterChange::AlertBounty { .. } => {}
+            ParameterChange::WatcherSlashBps { slash_bps } => {
+                require!(*slash_bps <= 10000, ErrorCode::InvalidSlashBps);
+            }
+            ParameterChange::AlertConfirmationThreshold { confirmation_threshold } => {
+                require!(
+                    *confirmation_threshold > 0 && *confirmation_threshold as usize <= MAX_ALERT_CONFIRMATIONS,
+                    ErrorCode::InvalidConfirmationThreshold
+                );
+            }
+            ParameterChange::WatcherRateLimit { max_alerts_per_window, alert_window_secs } => {
+                require!(
+                    *max_alerts_per_window > 0 && *alert_window_secs > 0,
+                    ErrorCode::InvalidWatcherRateLimit
+                );
+            }
+            ParameterChange::TrustedWatchers { trusted_reputation, trusted_confirmation_threshold } => {
+                require!(
+                    *trusted_reputation <= 100 && *trusted_confirmation_threshold > 0,
+                    ErrorCode::InvalidTrustedWatcherConfig
+                );
+            }
+            ParameterChange::AlertBond { .. } => {}
+            ParameterChange::TreasurySpendLimit { window_secs, .. } => {
+                require!(*window_secs > 0, ErrorCode::InvalidTreasuryConfig);
+            }
+            ParameterChange::Features { features } => {
+                require!(features & !ALL_FEATURES == 0, ErrorCode::InvalidConfig);
+            }
+            ParameterChange::PriceFeeds { primary_feed, secondary_feed, max_deviation_bps, .. } => {
+                require!(
+                    primary_feed != secondary_feed && *max_deviation_bps > 0,
+                    ErrorCode::InvalidPriceFeedConfig
+                );
+            }
+            ParameterChange::ProtocolFeeTerms { fee_override_bps, revenue_share_bps, revenue_share_account, .. } => {
+                validate_protocol_fee_terms(*fee_override_bps, *revenue_share_bps, revenue_share_account)?;
+            }
+            ParameterChange::OracleDependency { .. } => {}
+            ParameterChange::ClaimsCommittee { large_claim_threshold, .. } => {
+                require!(*large_claim_threshold > 0, ErrorCode::InvalidRealmsGovernance);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[account]
+pub struct PendingChange {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub change: ParameterChange,
+    pub scheduled_at: i64,
+    pub executable_at: i64,
+    pub is_executed: bool,
+    pub is_cancelled: bool,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl PendingChange {
+    pub const SIZE: usize = 8 +     // discriminator
+                           8 +      // id
+                           32 +     // proposer
+                           ParameterChange::SIZE + // change
+                           8 +      // scheduled_at
+                           8 +      // executable_at
+                           1 +      // is_executed
+                           1 +      // is_cancelled
+                           1 +      // bump
+                           1;       // version
+
+    // Executing and cancelling both end the change, so neither can happen twice
+    pub fn is_pending(&self) -> bool {
+        !self.is_executed && !self.is_cancelled
+    }
+}
+
+#[event]
+pub struct ChangeScheduled {
+    pub pending_change: Pubkey,
+    pub id: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub pending_change: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub pending_change: Pubkey,
+    pub id: u64,
+}
+
+pub fn schedule_change(
+    ctx: Context<ScheduleChange>,
+    change: ParameterChange,
+) -> Result<()> {
+    change.validate()?;
+    // The governance is checked against its realm while the accounts are at hand
+    if let ParameterChange::ClaimsCommittee { realm, governance, .. } = &change {
+        let realm_account = ctx.accounts.realm.as_ref().ok_or(ErrorCode::ChangeAccountMissing)?;
+        let governance_account = ctx.accounts.governance.as_ref().ok_or(ErrorCode::ChangeAccountMissing)?;
+        require!(
+            realm_account.key() == *realm && governance_account.key() == *governance,
+            ErrorCode::ChangeAccountMissing
+        );
+        check_realms_governance(realm_account, governance_account)?;
+    }
+
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let pending_change = &mut ctx.accounts.pending_change;
+    let clock = Clock::get()?;
+
+    pending_change.id = protocol_state.change_count;
+    pending_change.proposer = ctx.accounts.authority.key();
+    pending_change.change = change;
+    pending_change.scheduled_at = clock.unix_timestamp;
+    pending_change.executable_at = clock.unix_timestamp.checked_add(CHANGE_TIMELOCK_SECS).ok_or(ErrorCode::MathOverflow)?;
+    pending_change.is_executed = false;
+    pending_change.is_cancelled = false;
+    pending_change.bump = ctx.bumps.pending_change;
+    pending_change.version = ACCOUNT_VERSION;
+
+    protocol_state.change_count = protocol_state.change_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(ChangeScheduled {
+        pending_change: pending_change.key(),
+        id: pending_change.id,
+        executable_at: pending_change.executable_at,
+    });
+
+    Ok(())
+}
+
+// Anyone can apply a scheduled change once its delay has passed
+pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+    let pending_change = &mut ctx.accounts.pending_change;
+
+    require!(pending_change.is_pending(), ErrorCode::ChangeNotPending);
+    require!(
+        Clock::get()?.unix_timestamp >= pending_change.executable_at,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    match &pending_change.change {
+        ParameterChange::MaxInsurableRiskScore { max_insurable_risk_score } => {
+            protocol_state.max_insurable_risk_score = *max_insurable_risk_score;
+        }
+        ParameterChange::PremiumCurve { premium_curve } => {
+            protocol_state.premium_curve = *premium_curve;
+        }
+        ParameterChange::SafeguardDiscounts { safeguard_discount_bps } => {
+            protocol_state.safeguard_discount_bps = *safeguard_discount_bps;
+        }
+        ParameterChange::RiskOracle { risk_oracle } => {
+            protocol_state.risk_oracle = *risk_oracle;
+        }
+        ParameterChange::AlertResponse { alert_response } => {
+            protocol_state.alert_response = *alert_response;
+        }
+        ParameterChange::TvlAnomaly { tvl_anomaly } => {
+            protocol_state.tvl_anomaly = *tvl_anomaly;
+        }
+        ParameterChange::CircuitBreaker { threshold, window_secs } => {
+            protocol_state.circuit_breaker.threshold = *threshold;
+            protocol_state.circuit_breaker.window_secs = *window_secs;
+        }
+        ParameterChange::PoolRiskProfile { pool_type, risk_weights, max_risk_score } => {
+            let capital_pool = ctx.accounts.capital_pool.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(capital_pool.pool_type == *pool_type, ErrorCode::ChangeAccountMissing);
+            capital_pool.risk_weights = *risk_weights;
+            capital_pool.max_risk_score = *max_risk_score;
+        }
+        ParameterChange::PoolReinsurance { pool_type, reinsurance_capacity } => {
+            let capital_pool = ctx.accounts.capital_pool.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(capital_pool.pool_type == *pool_type, ErrorCode::ChangeAccountMissing);
+            capital_pool.reinsurance_capacity = *reinsurance_capacity;
+        }
+        ParameterChange::Config { params, fee_rates } => {
+            let config = ctx.accounts.config.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            config.params = *params;
+            config.fee_rates = *fee_rates;
+        }
+        ParameterChange::PoolPayoutLimits { pool_type, payout_limits } => {
+            let capital_pool = ctx.accounts.capital_pool.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(capital_pool.pool_type == *pool_type, ErrorCode::ChangeAccountMissing);
+            capital_pool.payout_limits = *payout_limits;
+        }
+        ParameterChange::OracleGuards { feed_id, guards } => {
+            let oracle_config = ctx.accounts.oracle_config.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            oracle_config.set_guards(feed_id, *guards)?;
+        }
+        ParameterChange::AlertBounty { max_alert_bounty } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.max_alert_bounty = *max_alert_bounty;
+        }
+        ParameterChange::WatcherSlashBps { slash_bps } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.slash_bps = *slash_bps;
+        }
+        ParameterChange::AlertConfirmationThreshold { confirmation_threshold } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.confirmation_threshold = *confirmation_threshold;
+        }
+        ParameterChange::WatcherRateLimit { max_alerts_per_window, alert_window_secs } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.max_alerts_per_window = *max_alerts_per_window;
+            watcher_registry.alert_window_secs = *alert_window_secs;
+        }
+        ParameterChange::TrustedWatchers { trusted_reputation, trusted_confirmation_threshold } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.trusted_reputation = *trusted_reputation;
+            watcher_registry.trusted_confirmation_threshold = *trusted_confirmation_threshold;
+        }
+        ParameterChange::AlertBond { alert_bond } => {
+            let watcher_registry = ctx.accounts.watcher_registry.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            watcher_registry.alert_bond = *alert_bond;
+        }
+        ParameterChange::TreasurySpendLimit { mint, spend_limit, window_secs } => {
+            let treasury_vault = ctx.accounts.treasury_vault.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(treasury_vault.mint == *mint, ErrorCode::ChangeAccountMissing);
+            treasury_vault.spend_limit = *spend_limit;
+            treasury_vault.window_secs = *window_secs;
+        }
+        ParameterChange::Features { features } => {
+            let config = ctx.accounts.config.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            config.features = *features;
+        }
+        ParameterChange::PriceFeeds { protocol, primary_feed, secondary_feed, max_deviation_bps } => {
+            let price_feeds = ctx.accounts.price_feeds.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(price_feeds.protocol == *protocol, ErrorCode::ChangeAccountMissing);
+            price_feeds.primary_feed = *primary_feed;
+            price_feeds.secondary_feed = *secondary_feed;
+            price_feeds.max_deviation_bps = *max_deviation_bps;
+        }
+        ParameterChange::ProtocolFeeTerms { protocol, fee_override_bps, revenue_share_bps, revenue_share_account } => {
+            let protocol_info = ctx.accounts.protocol_info.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(protocol_info.key() == *protocol, ErrorCode::ChangeAccountMissing);
+            set_protocol_fee_terms(protocol_info, *fee_override_bps, *revenue_share_bps, *revenue_share_account);
+        }
+        ParameterChange::OracleDependency { protocol, oracle_dependency } => {
+            let protocol_info = ctx.accounts.protocol_info.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            require!(protocol_info.key() == *protocol, ErrorCode::ChangeAccountMissing);
+            protocol_info.oracle_dependency = *oracle_dependency;
+        }
+        ParameterChange::ClaimsCommittee { realm, governance, large_claim_threshold } => {
+            let claims_committee = ctx.accounts.claims_committee.as_mut().ok_or(ErrorCode::ChangeAccountMissing)?;
+            set_claims_committee(claims_committee, *realm, *governance, *large_claim_threshold);
+        }
+    }
+
+    pending_change.is_executed = true;
+
+    emit!(ChangeExecuted {
+        pending_change: pending_change.key(),
+        id: pending_change.id,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+    let pending_change = &mut ctx.accounts.pending_change;
+
+    require!(pending_change.is_pending(), ErrorCode::ChangeNotPending);
+    pending_change.is_cancelled = true;
+
+    emit!(ChangeCancelled {
+        pending_change: pending_change.key(),
+        id: pending_change.id,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ScheduleChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingChange::SIZE,
+        seeds = [b"pending-change".as_ref(), &protocol_state.change_count.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: only needed for a claims committee change; checked in the handler
+    pub realm: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: only needed for a claims committee change; checked in the handler
+    pub governance: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending-change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    // Only needed for changes to a capital pool
+    #[account(
+        mut,
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Option<Account<'info, CapitalPool>>,
+
+    // Only needed for changes to the config
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Option<Account<'info, Config>>,
+
+    // Only needed for changes to the oracle guards
+    #[account(
+        mut,
+        seeds = [b"oracle-config"],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Option<Account<'info, OracleConfig>>,
+
+    // Only needed for changes to the watcher rules
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Option<Account<'info, WatcherRegistry>>,
+
+    // Only needed for changes to a treasury vault's spend limit
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Option<Account<'info, TreasuryVault>>,
+
+    // Only needed for changes to a protocol's price feeds
+    #[account(
+        mut,
+        seeds = [b"price-feeds", price_feeds.protocol.as_ref()],
+        bump = price_feeds.bump
+    )]
+    pub price_feeds: Option<Account<'info, PriceFeedPair>>,
+
+    // Only needed for changes to a protocol's fee terms
+    #[account(mut)]
+    pub protocol_info: Option<Account<'info, ProtocolInfo>>,
+
+    // Only needed for changes to the claims committee
+    #[account(
+        mut,
+        seeds = [b"claims-committee"],
+        bump = claims_committee.bump
+    )]
+    pub claims_committee: Option<Account<'info, ClaimsCommittee>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending-change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
@@ -0,0 +1,401 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::{ProtocolState, ErrorCode};
+
+#[account]
+pub struct Treasury {
+    pub treasurer: Pubkey, // Can withdraw within the spending limits besides the admin
+    pub vault_count: u16,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl Treasury {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // treasurer
+                           2 +      // vault_count
+                           1 +      // bump
+                           1;       // version
+}
+
+// Premium vault for one accepted mint, owned by the treasury PDA
+#[account]
+pub struct TreasuryVault {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub spend_limit: u64,        // Most that can be withdrawn per window
+    pub window_secs: i64,
+    pub window_started_at: i64,
+    pub spent_in_window: u64,
+    pub total_inflows: u64,      // Premiums received, net of the protocol fee
+    pub total_withdrawn: u64,
+    pub total_refunded: u64,     // Premiums returned on cancelled policies
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TreasuryVault {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // mint
+                           32 +     // token_account
+                           8 +      // spend_limit
+                           8 +      // window_secs
+                           8 +      // window_started_at
+                           8 +      // spent_in_window
+                           8 +      // total_inflows
+                           8 +      // total_withdrawn
+                           8 +      // total_refunded
+                           1 +      // bump
+                           1;       // version
+
+    // Counts a withdrawal against the current window, starting a new window once it has passed
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.window_started_at.saturating_add(self.window_secs) {
+            self.window_started_at = now;
+            self.spent_in_window = 0;
+        }
+
+        let spent_in_window = self.spent_in_window.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(spent_in_window <= self.spend_limit, ErrorCode::TreasurySpendLimitExceeded);
+        self.spent_in_window = spent_in_window;
+        self.total_withdrawn = self.total_withdrawn.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct TreasuryWithdrawal {
+    pub mint: Pubkey,
+    pub withdrawn_by: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub spent_in_window: u64,
+}
+
+#[event]
+pub struct TreasuryLamportsWithdrawn {
+    pub withdrawn_by: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+pub fn initialize_treasury(
+    ctx: Context<InitializeTreasury>,
+    treasurer: Pubkey,
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.treasurer = treasurer;
+    treasury.vault_count = 0;
+    treasury.bump = ctx.bumps.treasury;
+    treasury.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+pub fn set_treasurer(
+    ctx: Context<ConfigureTreasury>,
+    treasurer: Pubkey,
+) -> Result<()> {
+    ctx.accounts.treasury.treasurer = treasurer;
+
+    Ok(())
+}
+
+pub fn add_treasury_vault(
+    ctx: Context<AddTreasuryVault>,
+    spend_limit: u64,
+    window_secs: i64,
+) -> Result<()> {
+    require!(window_secs > 0, ErrorCode::InvalidTreasuryConfig);
+
+    let treasury_vault = &mut ctx.accounts.treasury_vault;
+    treasury_vault.mint = ctx.accounts.mint.key();
+    treasury_vault.token_account = ctx.accounts.vault_token.key();
+    treasury_vault.spend_limit = spend_limit;
+    treasury_vault.window_secs = window_secs;
+    treasury_vault.window_started_at = 0;
+    treasury_vault.spent_in_window = 0;
+    treasury_vault.total_inflows = 0;
+    treasury_vault.total_withdrawn = 0;
+    treasury_vault.total_refunded = 0;
+    treasury_vault.bump = ctx.bumps.treasury_vault;
+    treasury_vault.version = ACCOUNT_VERSION;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.vault_count = treasury.vault_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+// Only tightens the limit, which takes effect immediately so spending can be reined in
+// when a key is at risk. Loosening it goes through `ParameterChange::TreasurySpendLimit`.
+pub fn set_treasury_spend_limit(
+    ctx: Context<SetTreasurySpendLimit>,
+    spend_limit: u64,
+    window_secs: i64,
+) -> Result<()> {
+    require!(window_secs > 0, ErrorCode::InvalidTreasuryConfig);
+
+    let treasury_vault = &mut ctx.accounts.treasury_vault;
+    require!(
+        spend_limit <= treasury_vault.spend_limit && window_secs >= treasury_vault.window_secs,
+        ErrorCode::ChangeRequiresTimelock
+    );
+    treasury_vault.spend_limit = spend_limit;
+    treasury_vault.window_secs = window_secs;
+
+    Ok(())
+}
+
+pub fn withdraw_treasury(
+    ctx: Context<WithdrawTreasury>,
+    amount: u64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let treasury = &ctx.accounts.treasury;
+    let treasury_vault = &mut ctx.accounts.treasury_vault;
+
+    require!(
+        authority == ctx.accounts.protocol_state.authority || authority == treasury.treasurer,
+        ErrorCode::UnauthorizedAccess
+    );
+    treasury_vault.record_spend(amount, Clock::get()?.unix_timestamp)?;
+
+    transfer_from_treasury(
+        treasury,
+        &ctx.accounts.vault_token,
+        &ctx.accounts.destination,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    emit!(TreasuryWithdrawal {
+        mint: treasury_vault.mint,
+        withdrawn_by: authority,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        spent_in_window: treasury_vault.spent_in_window,
+    });
+
+    Ok(())
+}
+
+// Takes out the rent reclaimed by the cleanup instructions; the treasury keeps its own rent
+pub fn withdraw_treasury_lamports(
+    ctx: Context<WithdrawTreasuryLamports>,
+    amount: u64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let treasury = ctx.accounts.treasury.to_account_info();
+
+    require!(
+        authority == ctx.accounts.protocol_state.authority || authority == ctx.accounts.treasury.treasurer,
+        ErrorCode::UnauthorizedAccess
+    );
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury.data_len());
+    let withdrawable = treasury.lamports().saturating_sub(rent_exempt_minimum);
+    require!(amount <= withdrawable, ErrorCode::InsufficientTreasuryLamports);
+
+    // The treasury is owned by the program, so its lamports are moved directly
+    **treasury.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.destination.try_borrow_mut_lamports()? += amount;
+
+    emit!(TreasuryLamportsWithdrawn {
+        withdrawn_by: authority,
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn transfer_from_treasury<'info>(
+    treasury: &Account<'info, Treasury>,
+    vault_token: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    mint: &Account<'info, Mint>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"treasury".as_ref(),
+        &[treasury.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault_token.to_account_info(),
+        mint: mint.to_account_info(),
+        to: destination.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+    let cpi_program = token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::SIZE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct AddTreasuryVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = TreasuryVault::SIZE,
+        seeds = [b"treasury-vault", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(
+        constraint = vault_token.mint == mint.key() @ ErrorCode::InvalidTreasuryVault,
+        constraint = vault_token.owner == treasury.key() @ ErrorCode::InvalidTreasuryVault
+    )]
+    pub vault_token: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasurySpendLimit<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", treasury_vault.mint.as_ref()],
+        bump = treasury_vault.bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(
+        mut,
+        constraint = vault_token.key() == treasury_vault.token_account @ ErrorCode::InvalidTreasuryVault
+    )]
+    pub vault_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == treasury_vault.mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(address = treasury_vault.mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryLamports<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::mint_registry::MintRegistry;
+use crate::oracle_guards::{load_guarded_price, OracleConfig};
+use crate::{ProtocolInfo, ProtocolState, ErrorCode};
+
+pub const MAX_TVL_ADAPTER_ACCOUNTS: usize = 8;
+
+// How an adapter's accounts are read
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TvlAdapterKind {
+    // Token accounts holding the protocol's deposits, each valued with a Pyth price
+    TokenVaultSum,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TvlAdapterAccount {
+    pub account: Pubkey,
+    pub price_feed: [u8; 32], // Pyth feed id pricing the holdings in USD, unless the mint registry has one
+}
+
+impl TvlAdapterAccount {
+    pub const SIZE: usize = 32 +    // account
+                           32;      // price_feed
+}
+
+// Where a protocol's TVL can be read from on-chain, so the TVL used to score
+// economic risk doesn't rest on reported figures
+#[account]
+pub struct TvlAdapter {
+    pub protocol: Pubkey,
+    pub program_id: Pubkey, // Program that owns the accounts read
+    pub kind: TvlAdapterKind,
+    pub accounts: [TvlAdapterAccount; MAX_TVL_ADAPTER_ACCOUNTS],
+    pub account_count: u8,
+    pub last_refreshed_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TvlAdapter {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // program_id
+                           1 +      // kind
+                           TvlAdapterAccount::SIZE * MAX_TVL_ADAPTER_ACCOUNTS + // accounts
+                           1 +      // account_count
+                           8 +      // last_refreshed_at
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct TvlRefreshedFromAdapter {
+    pub protocol: Pubkey,
+    pub previous_tvl: u64,
+    pub tvl_usd: u64,
+}
+
+pub fn configure_tvl_adapter(
+    ctx: Context<ConfigureTvlAdapter>,
+    program_id: Pubkey,
+    kind: TvlAdapterKind,
+    accounts: Vec<TvlAdapterAccount>,
+) -> Result<()> {
+    require!(
+        !accounts.is_empty() && accounts.len() <= MAX_TVL_ADAPTER_ACCOUNTS,
+        ErrorCode::InvalidTvlAdapter
+    );
+    match kind {
+        TvlAdapterKind::TokenVaultSum => require_keys_eq!(program_id, token::ID, ErrorCode::InvalidTvlAdapter),
+    }
+
+    let tvl_adapter = &mut ctx.accounts.tvl_adapter;
+    if tvl_adapter.protocol == Pubkey::default() {
+        tvl_adapter.protocol = ctx.accounts.protocol_info.key();
+        tvl_adapter.last_refreshed_at = 0;
+        tvl_adapter.bump = ctx.bumps.tvl_adapter;
+        tvl_adapter.version = ACCOUNT_VERSION;
+    }
+    tvl_adapter.program_id = program_id;
+    tvl_adapter.kind = kind;
+    tvl_adapter.accounts = [TvlAdapterAccount::default(); MAX_TVL_ADAPTER_ACCOUNTS];
+    tvl_adapter.accounts[..accounts.len()].copy_from_slice(&accounts);
+    tvl_adapter.account_count = accounts.len() as u8;
+
+    Ok(())
+}
+
+// Permissionless. For TokenVaultSum the remaining accounts are, for each configured
+// vault in order, the vault, its mint and a Pyth price update for its feed. Tokens
+// the mint registry prices are valued with the registry's feed instead.
+pub fn refresh_tvl_from_adapter(ctx: Context<RefreshTvlFromAdapter>) -> Result<()> {
+    let tvl_adapter = &mut ctx.accounts.tvl_adapter;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let configured = &tvl_adapter.accounts[..tvl_adapter.account_count as usize];
+
+    let mut tvl_usd: u64 = 0;
+    match tvl_adapter.kind {
+        TvlAdapterKind::TokenVaultSum => {
+            require!(
+                ctx.remaining_accounts.len() == configured.len() * 3,
+                ErrorCode::TvlAdapterAccountMismatch
+            );
+            for (entry, infos) in configured.iter().zip(ctx.remaining_accounts.chunks(3)) {
+                require_keys_eq!(infos[0].key(), entry.account, ErrorCode::TvlAdapterAccountMismatch);
+                require_keys_eq!(*infos[0].owner, tvl_adapter.program_id, ErrorCode::TvlAdapterAccountMismatch);
+                require_keys_eq!(*infos[1].owner, tvl_adapter.program_id, ErrorCode::TvlAdapterAccountMismatch);
+
+                let vault = TokenAccount::try_deserialize(&mut &infos[0].data.borrow()[..])?;
+                let mint = Mint::try_deserialize(&mut &infos[1].data.borrow()[..])?;
+                require_keys_eq!(infos[1].key(), vault.mint, ErrorCode::TvlAdapterAccountMismatch);
+                let feed = ctx.accounts.mint_registry.price_feed(&vault.mint).unwrap_or(entry.price_feed);
+                let price = load_guarded_price(&ctx.accounts.oracle_config, &infos[2], &feed)?;
+
+                tvl_usd = tvl_usd.saturating_add(price.usd_value(vault.amount, mint.decimals));
+            }
+        }
+    }
+
+    let previous_tvl = protocol_info.tvl_usd;
+    protocol_info.tvl_usd = tvl_usd;
+    tvl_adapter.last_refreshed_at = Clock::get()?.unix_timestamp;
+
+    emit!(TvlRefreshedFromAdapter {
+        protocol: protocol_info.key(),
+        previous_tvl,
+        tvl_usd,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTvlAdapter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TvlAdapter::SIZE,
+        seeds = [b"tvl-adapter", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub tvl_adapter: Account<'info, TvlAdapter>,
+
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshTvlFromAdapter<'info> {
+    #[account(
+        mut,
+        seeds = [b"tvl-adapter", protocol_info.key().as_ref()],
+        bump = tvl_adapter.bump
+    )]
+    pub tvl_adapter: Account<'info, TvlAdapter>,
+
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    #[account(
+        seeds = [b"oracle-config"],
+        bump = oracle_config.bump
+    )]
+    pub oracle_config: Account<'info, OracleConfig>,
+
+    #[account(
+        seeds = [b"mint-registry"],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+}
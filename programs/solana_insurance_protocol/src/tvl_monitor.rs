@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::alert_subscriptions::{notify_subscribers, AlertNotification};
+use crate::directory::{update_directory, ProtocolDirectory};
+use crate::exploit_detection::{raise_system_alert, AlertSeverity, AnomalyType, SystemAlert};
+use crate::risk_attestation::{verify_oracle_signature, MAX_ATTESTATION_AGE_SECS};
+use crate::{ProtocolInfo, ProtocolRegistry, ProtocolState, ErrorCode};
+
+// Weight of the existing baseline when folding in a new observation (1/8 for the new value)
+pub const TVL_BASELINE_SMOOTHING: u64 = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TvlAnomalyConfig {
+    pub drop_bps: u64,     // Drop below the baseline that raises an alert
+    pub window_secs: i64,  // Observations further apart than this reseed the baseline
+}
+
+impl TvlAnomalyConfig {
+    pub const SIZE: usize = 8 +     // drop_bps
+                           8;       // window_secs
+    
+    pub fn is_valid(&self) -> bool {
+        self.drop_bps > 0 && self.drop_bps <= 10000 && self.window_secs > 0
+    }
+}
+
+pub const DEFAULT_TVL_ANOMALY_CONFIG: TvlAnomalyConfig = TvlAnomalyConfig {
+    drop_bps: 3000, // 30% drop
+    window_secs: 60 * 60,
+};
+
+// TVL reported by the risk oracle. The oracle signs the Borsh serialization of this struct;
+// its length differs from RiskAttestation so one can't be passed off as the other.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TvlAttestation {
+    pub protocol: Pubkey,
+    pub tvl_usd: u64,
+    pub attested_at: i64,
+}
+
+#[account]
+pub struct TvlMonitor {
+    pub protocol: Pubkey,
+    pub baseline_tvl: u64,
+    pub last_tvl: u64,
+    pub last_observed_at: i64,
+    pub last_alert_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl TvlMonitor {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           8 +      // baseline_tvl
+                           8 +      // last_tvl
+                           8 +      // last_observed_at
+                           8 +      // last_alert_at
+                           1 +      // bump
+                           1;       // version
+}
+
+pub fn check_tvl_anomaly(
+    ctx: Context<CheckTvlAnomaly>,
+    attestation: TvlAttestation,
+) -> Result<()> {
+    let tvl_monitor = &mut ctx.accounts.tvl_monitor;
+    let protocol_info = &mut ctx.accounts.protocol_info;
+    let protocol_state = &ctx.accounts.protocol_state;
+    let config = protocol_state.tvl_anomaly;
+    let clock = Clock::get()?;
+    
+    require!(protocol_state.risk_oracle != Pubkey::default(), ErrorCode::RiskOracleNotSet);
+    require!(
+        attestation.protocol == protocol_info.key(),
+        ErrorCode::AttestationProtocolMismatch
+    );
+    require!(
+        attestation.attested_at > tvl_monitor.last_observed_at &&
+        attestation.attested_at <= clock.unix_timestamp &&
+        clock.unix_timestamp - attestation.attested_at <= MAX_ATTESTATION_AGE_SECS,
+        ErrorCode::StaleRiskAttestation
+    );
+    
+    let message = attestation.try_to_vec()?;
+    verify_oracle_signature(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &protocol_state.risk_oracle,
+        &message,
+    )?;
+    
+    let tvl = attestation.tvl_usd;
+    
+    // The first observation only seeds the baseline
+    if tvl_monitor.protocol == Pubkey::default() {
+        tvl_monitor.protocol = protocol_info.key();
+        tvl_monitor.baseline_tvl = tvl;
+        tvl_monitor.last_alert_at = 0;
+        tvl_monitor.bump = ctx.bumps.tvl_monitor;
+        tvl_monitor.version = ACCOUNT_VERSION;
+    } else if attestation.attested_at - tvl_monitor.last_observed_at > config.window_secs {
+        // Too long since the last observation to attribute a drop to the window
+        tvl_monitor.baseline_tvl = tvl;
+    } else {
+        let baseline = tvl_monitor.baseline_tvl;
+        let drop_bps = if baseline > 0 && tvl < baseline {
+            ((baseline - tvl) as u128 * 10000 / baseline as u128) as u64
+        } else {
+            0
+        };
+        
+        if drop_bps >= config.drop_bps {
+            // Keep the pre-drop baseline and raise at most one alert per window
+            if clock.unix_timestamp - tvl_monitor.last_alert_at > config.window_secs {
+                raise_system_alert(
+                    &ctx.accounts.payer,
+                    &ctx.accounts.exploit_alert,
+                    protocol_info,
+                    protocol_state,
+                    &ctx.accounts.system_program,
+                    ctx.bumps.exploit_alert,
+                    SystemAlert {
+                        anomaly_type: AnomalyType::TvlDrop,
+                        severity: AlertSeverity::High,
+                        details: format!("TVL dropped {} bps below baseline", drop_bps),
+                    },
+                )?;
+                ctx.accounts.registry.sync_active(protocol_info)?;
+                update_directory(&ctx.accounts.registry, &ctx.accounts.directory, |directory| {
+                    directory.record(protocol_info)
+                })?;
+                notify_subscribers(
+                    ctx.remaining_accounts,
+                    protocol_info.key(),
+                    ctx.accounts.exploit_alert.key(),
+                    AlertNotification::Activated,
+                )?;
+                tvl_monitor.last_alert_at = clock.unix_timestamp;
+            }
+        } else {
+            tvl_monitor.baseline_tvl = ((baseline as u128 * (TVL_BASELINE_SMOOTHING - 1) as u128 +
+                tvl as u128) / TVL_BASELINE_SMOOTHING as u128) as u64;
+        }
+    }
+    
+    tvl_monitor.last_tvl = tvl;
+    tvl_monitor.last_observed_at = attestation.attested_at;
+    protocol_info.tvl_usd = tvl;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckTvlAnomaly<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TvlMonitor::SIZE,
+        seeds = [b"tvl-monitor", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub tvl_monitor: Account<'info, TvlMonitor>,
+    
+    /// CHECK: only created, at this PDA, when the check raises an alert
+    #[account(
+        mut,
+        seeds = [b"exploit-alert", protocol_info.key().as_ref(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        bump
+    )]
+    pub exploit_alert: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub protocol_info: Account<'info, ProtocolInfo>,
+    
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// CHECK: address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    #[account(
+        mut,
+        seeds = [b"protocol-registry"],
+        bump
+    )]
+    pub registry: Account<'info, ProtocolRegistry>,
+    
+    // Required once the registry has a directory
+    #[account(
+        mut,
+        address = registry.directory @ ErrorCode::InvalidProtocolDirectory
+    )]
+    pub directory: Option<AccountLoader<'info, ProtocolDirectory>>,
+}
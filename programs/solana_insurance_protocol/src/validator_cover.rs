@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT;
+use anchor_lang::solana_program::vote;
+use anchor_spl::token::spl_token;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PARAMETRIC_CLAIMS};
+use crate::loss_events::{LossEvent, LossEventCreated};
+use crate::{ProtocolInfo, ProtocolState, ErrorCode};
+
+// Vote state versions laid out with landed votes (Current) or plain lockouts (V1_14_11)
+const VOTE_STATE_V1_14_11: u32 = 1;
+const VOTE_STATE_CURRENT: u32 = 2;
+const LOCKOUT_SIZE: usize = 12;
+const LANDED_VOTE_SIZE: usize = 13;
+const AUTHORIZED_VOTER_SIZE: usize = 40;
+const PRIOR_VOTERS_SIZE: usize = 48 * 32 + 8 + 1;
+const EPOCH_CREDITS_ENTRY_SIZE: usize = 24;
+
+// Downtime trigger for a protocol that is a validator. Policies on that protocol pay out
+// when it earns fewer vote credits in an epoch than the minimum, which is what missed
+// votes cost its stakers in rewards.
+//
+// Downtime is the only cover. The runtime doesn't slash stake, so there is no slashing
+// event to settle, and settlement reads only the vote account: a policy's coverage is
+// its coverage_amount, not sized from a stake account or its history in the stake
+// history sysvar.
+#[account]
+pub struct ValidatorCover {
+    pub protocol: Pubkey,
+    pub vote_account: Pubkey,
+    pub min_epoch_credits: u64,
+    pub trigger_count: u32,
+    pub last_triggered_epoch: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ValidatorCover {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // protocol
+                           32 +     // vote_account
+                           8 +      // min_epoch_credits
+                           4 +      // trigger_count
+                           8 +      // last_triggered_epoch
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct ValidatorDowntimeTriggered {
+    pub protocol: Pubkey,
+    pub vote_account: Pubkey,
+    pub loss_event: Pubkey,
+    pub epoch: u64,
+    pub epoch_credits: u64,
+}
+
+struct VoteReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl VoteReader<'_> {
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.offset = self.offset.checked_add(len).ok_or(ErrorCode::MathOverflow)?;
+        require!(self.offset <= self.data.len(), ErrorCode::InvalidVoteAccount);
+        Ok(())
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        self.skip(1)?;
+        Ok(self.data[self.offset - 1])
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let start = self.offset;
+        self.skip(8)?;
+        Ok(u64::from_le_bytes(self.data[start..self.offset].try_into().unwrap()))
+    }
+
+    fn read_len(&mut self, entry_size: usize) -> Result<usize> {
+        let len = self.read_u64()? as usize;
+        require!(len <= self.data.len() / entry_size, ErrorCode::InvalidVoteAccount);
+        Ok(len)
+    }
+}
+
+// Vote credits the vote account earned in `epoch`. The vote program can't be called
+// to deserialize it on-chain, so the bincode layout is walked up to epoch_credits.
+pub fn load_epoch_credits(vote_account: &AccountInfo, epoch: u64) -> Result<u64> {
+    require_keys_eq!(*vote_account.owner, vote::program::ID, ErrorCode::InvalidVoteAccount);
+
+    let data = vote_account.try_borrow_data()?;
+    require!(data.len() >= 4, ErrorCode::InvalidVoteAccount);
+    let vote_size = match u32::from_le_bytes(data[..4].try_into().unwrap()) {
+        VOTE_STATE_V1_14_11 => LOCKOUT_SIZE,
+        VOTE_STATE_CURRENT => LANDED_VOTE_SIZE,
+        _ => return err!(ErrorCode::InvalidVoteAccount),
+    };
+
+    let mut reader = VoteReader { data: &data, offset: 4 };
+    reader.skip(32 + 32 + 1)?; // node_pubkey, authorized_withdrawer, commission
+    let votes = reader.read_len(vote_size)?;
+    reader.skip(votes * vote_size)?;
+    if reader.read_u8()? == 1 {
+        reader.skip(8)?; // root_slot
+    }
+    let authorized_voters = reader.read_len(AUTHORIZED_VOTER_SIZE)?;
+    reader.skip(authorized_voters * AUTHORIZED_VOTER_SIZE)?;
+    reader.skip(PRIOR_VOTERS_SIZE)?;
+
+    // Entries are (epoch, credits, prev_credits), and epochs without votes have none
+    let entries = reader.read_len(EPOCH_CREDITS_ENTRY_SIZE)?;
+    for _ in 0..entries {
+        let entry_epoch = reader.read_u64()?;
+        let credits = reader.read_u64()?;
+        let prev_credits = reader.read_u64()?;
+        if entry_epoch == epoch {
+            return Ok(credits.saturating_sub(prev_credits));
+        }
+    }
+    Ok(0)
+}
+
+pub fn configure_validator_cover(
+    ctx: Context<ConfigureValidatorCover>,
+    min_epoch_credits: u64,
+) -> Result<()> {
+    require!(min_epoch_credits > 0, ErrorCode::InvalidValidatorCover);
+    // Fails unless it is a vote account we can read
+    load_epoch_credits(&ctx.accounts.vote_account, Clock::get()?.epoch)?;
+
+    let cover = &mut ctx.accounts.validator_cover;
+    if cover.protocol == Pubkey::default() {
+        cover.protocol = ctx.accounts.protocol_info.key();
+        cover.trigger_count = 0;
+        cover.last_triggered_epoch = 0;
+        cover.bump = ctx.bumps.validator_cover;
+        cover.version = ACCOUNT_VERSION;
+    }
+    cover.vote_account = ctx.accounts.vote_account.key();
+    cover.min_epoch_credits = min_epoch_credits;
+
+    Ok(())
+}
+
+// Permissionless. Settles the last completed epoch as a loss event if the validator was
+// down through enough of it.
+pub fn trigger_validator_downtime(ctx: Context<TriggerValidatorDowntime>, epoch: u64) -> Result<()> {
+    let cover = &mut ctx.accounts.validator_cover;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.config.is_enabled(FEATURE_PARAMETRIC_CLAIMS),
+        ErrorCode::FeatureDisabled
+    );
+    require!(epoch.checked_add(1) == Some(clock.epoch), ErrorCode::InvalidValidatorCover);
+
+    let epoch_credits = load_epoch_credits(&ctx.accounts.vote_account, epoch)?;
+    require!(epoch_credits < cover.min_epoch_credits, ErrorCode::ValidatorNotDown);
+
+    // Slots carry no timestamps, so the epoch's bounds are estimated back from now
+    let epoch_schedule = EpochSchedule::get()?;
+    let slot_time = |slot: u64| {
+        clock.unix_timestamp - (clock.slot.saturating_sub(slot) * DEFAULT_MS_PER_SLOT / 1000) as i64
+    };
+
+    let loss_event = &mut ctx.accounts.loss_event;
+    loss_event.alert = cover.key();
+    loss_event.protocol = cover.protocol;
+    loss_event.affected_mint = spl_token::native_mint::ID;
+    loss_event.incident_start = slot_time(epoch_schedule.get_first_slot_in_epoch(epoch));
+    loss_event.incident_end = slot_time(epoch_schedule.get_first_slot_in_epoch(clock.epoch));
+    loss_event.created_by = ctx.accounts.payer.key();
+    loss_event.bump = ctx.bumps.loss_event;
+    loss_event.version = ACCOUNT_VERSION;
+
+    cover.trigger_count = cover.trigger_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    cover.last_triggered_epoch = epoch;
+
+    emit!(LossEventCreated {
+        loss_event: loss_event.key(),
+        alert: loss_event.alert,
+        protocol: loss_event.protocol,
+        affected_mint: loss_event.affected_mint,
+        incident_start: loss_event.incident_start,
+    });
+    emit!(ValidatorDowntimeTriggered {
+        protocol: cover.protocol,
+        vote_account: cover.vote_account,
+        loss_event: loss_event.key(),
+        epoch,
+        epoch_credits,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureValidatorCover<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ValidatorCover::SIZE,
+        seeds = [b"validator-cover", protocol_info.key().as_ref()],
+        bump
+    )]
+    pub validator_cover: Account<'info, ValidatorCover>,
+
+    pub protocol_info: Account<'info, ProtocolInfo>,
+
+    /// CHECK: owner and layout are validated by load_epoch_credits
+    pub vote_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct TriggerValidatorDowntime<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"validator-cover", validator_cover.protocol.as_ref()],
+        bump = validator_cover.bump,
+        has_one = vote_account
+    )]
+    pub validator_cover: Account<'info, ValidatorCover>,
+
+    /// CHECK: must be the cover's vote account; layout is validated by load_epoch_credits
+    pub vote_account: UncheckedAccount<'info>,
+
+    // One loss event per epoch of downtime
+    #[account(
+        init,
+        payer = payer,
+        space = LossEvent::SIZE,
+        seeds = [b"loss-event", validator_cover.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub loss_event: Account<'info, LossEvent>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
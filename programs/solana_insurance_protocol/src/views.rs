@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use crate::capital_management::{CapitalPool, CapitalPoolType};
+use crate::claims::{Claim, ClaimStatus};
+use crate::deregistration::unexpired_premium;
+use crate::Policy;
+
+// Read-only instructions that return their result as return data. Frontends call them
+// with simulateTransaction instead of reimplementing the program's math.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PolicyStatusView {
+    pub is_active: bool,
+    pub is_claimed: bool,
+    pub is_expired: bool,
+    pub coverage_amount: u64,
+    pub seconds_remaining: i64,
+    pub refundable_premium: u64, // What cancel_policy would refund now
+    pub remaining_coverage: u64,  // Coverage not yet paid out or reserved for an approved claim
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolStatsView {
+    pub pool_type: CapitalPoolType,
+    pub total_capital: u64,
+    pub available_capital: u64,
+    pub reserved_capital: u64,
+    pub active_coverage: u64,
+    pub reinsurance_capacity: u64,
+    pub utilization_bps: u64,     // Active coverage over capital and reinsurance
+    pub withdrawals_paused: bool,
+    pub payouts_frozen: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimStatusView {
+    pub status: ClaimStatus,
+    pub amount: u64,
+    pub is_fast_track: bool,
+    pub challenge_ends_at: i64,
+    pub is_payable: bool,         // Approved and past its challenge window
+}
+
+pub fn get_policy_status(ctx: Context<GetPolicyStatus>) -> Result<PolicyStatusView> {
+    let policy = &ctx.accounts.policy;
+    let now = Clock::get()?.unix_timestamp;
+
+    Ok(PolicyStatusView {
+        is_active: policy.is_active,
+        is_claimed: policy.is_claimed,
+        is_expired: now >= policy.end_time,
+        coverage_amount: policy.coverage_amount,
+        seconds_remaining: policy.end_time.saturating_sub(now).max(0),
+        refundable_premium: if policy.is_active && !policy.is_claimed { unexpired_premium(policy, now) } else { 0 },
+        remaining_coverage: policy.remaining_coverage()?,
+    })
+}
+
+pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStatsView> {
+    let pool = &ctx.accounts.capital_pool;
+    let capacity = pool.total_capital.saturating_add(pool.reinsurance_capacity);
+    let utilization_bps = if capacity == 0 {
+        0
+    } else {
+        (pool.active_coverage as u128 * 10000 / capacity as u128) as u64
+    };
+
+    Ok(PoolStatsView {
+        pool_type: pool.pool_type,
+        total_capital: pool.total_capital,
+        available_capital: pool.available_capital,
+        reserved_capital: pool.reserved_capital,
+        active_coverage: pool.active_coverage,
+        reinsurance_capacity: pool.reinsurance_capacity,
+        utilization_bps,
+        withdrawals_paused: pool.withdrawals_paused,
+        payouts_frozen: pool.payouts_frozen,
+    })
+}
+
+pub fn get_claim_status(ctx: Context<GetClaimStatus>) -> Result<ClaimStatusView> {
+    let claim = &ctx.accounts.claim;
+    let now = Clock::get()?.unix_timestamp;
+
+    Ok(ClaimStatusView {
+        status: claim.status,
+        amount: claim.amount,
+        is_fast_track: claim.is_fast_track,
+        challenge_ends_at: claim.challenge_ends_at,
+        is_payable: claim.status == ClaimStatus::Approved && now >= claim.challenge_ends_at,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetPolicyStatus<'info> {
+    pub policy: Account<'info, Policy>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [b"capital-pool", &[capital_pool.pool_type as u8][..]],
+        bump = capital_pool.bump
+    )]
+    pub capital_pool: Account<'info, CapitalPool>,
+}
+
+#[derive(Accounts)]
+pub struct GetClaimStatus<'info> {
+    pub claim: Account<'info, Claim>,
+}
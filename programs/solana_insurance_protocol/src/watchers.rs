@@ -0,0 +1,558 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, TransferChecked};
+use crate::migrations::ACCOUNT_VERSION;
+use crate::exploit_detection::{AlertSeverity, ExploitAlert};
+use crate::{ProtocolState, ErrorCode};
+
+pub const DEFAULT_SLASH_BPS: u64 = 1000; // 10% of stake per malicious alert
+pub const MAX_WATCHER_STRIKES: u8 = 3;
+pub const MAX_ALERT_CONFIRMATIONS: usize = 5;
+pub const DEFAULT_MAX_ALERTS_PER_WINDOW: u8 = 5;
+pub const DEFAULT_ALERT_WINDOW_SECS: i64 = 24 * 60 * 60;
+pub const MIN_REPUTATION_SAMPLE: u32 = 5;              // Resolved alerts before a reputation counts
+pub const REPUTATION_LATENCY_STEP_SECS: u64 = 10 * 60; // One point lost per step of average latency
+pub const MAX_REPUTATION_LATENCY_PENALTY: u64 = 20;
+
+#[account]
+pub struct WatcherRegistry {
+    pub stake_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub min_stake: u64,
+    pub watcher_count: u64,
+    pub total_staked: u64,
+    pub bounty_vault: Pubkey,     // Funded from the treasury, pays confirmed alert bounties
+    pub max_alert_bounty: u64,    // Bounty for a critical alert, scaled down for lower severities
+    pub slash_bps: u64,           // Share of stake slashed for a malicious alert
+    pub confirmation_threshold: u8, // Watchers, reporter included, needed to activate an alert
+    pub max_alerts_per_window: u8,
+    pub alert_window_secs: i64,
+    pub trusted_reputation: u8,   // 0 disables the trusted threshold
+    pub trusted_confirmation_threshold: u8,
+    pub alert_bond: u64,          // Posted by non-watchers to raise an alert; 0 disables bonded alerts
+    pub total_bonded: u64,        // Outstanding bonds held in the stake vault
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl WatcherRegistry {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // stake_mint
+                           32 +     // stake_vault
+                           8 +      // min_stake
+                           8 +      // watcher_count
+                           8 +      // total_staked
+                           32 +     // bounty_vault
+                           8 +      // max_alert_bounty
+                           8 +      // slash_bps
+                           1 +      // confirmation_threshold
+                           1 +      // max_alerts_per_window
+                           8 +      // alert_window_secs
+                           1 +      // trusted_reputation
+                           1 +      // trusted_confirmation_threshold
+                           8 +      // alert_bond
+                           8 +      // total_bonded
+                           1 +      // bump
+                           1;       // version
+    
+    // Confirmations needed to activate an alert once this watcher has reported or confirmed it
+    pub fn confirmation_threshold_for(&self, watcher: &Watcher) -> u8 {
+        if self.trusted_reputation > 0 && watcher.reputation() >= self.trusted_reputation {
+            std::cmp::min(self.trusted_confirmation_threshold, self.confirmation_threshold)
+        } else {
+            self.confirmation_threshold
+        }
+    }
+    
+    pub fn alert_bounty(&self, severity: AlertSeverity) -> u64 {
+        (self.max_alert_bounty as u128 * severity.bounty_pct() as u128 / 100) as u64
+    }
+}
+
+#[account]
+pub struct Watcher {
+    pub owner: Pubkey,
+    pub staked_amount: u64,
+    pub registered_at: i64,
+    pub strikes: u8,
+    pub is_revoked: bool,
+    pub window_start: i64,
+    pub alerts_in_window: u8,
+    pub alerts_confirmed: u32,        // Reported alerts resolved as confirmed
+    pub alerts_false: u32,            // Reported alerts resolved as unconfirmed
+    pub confirmations_given: u32,     // Confirmations of other watchers' alerts
+    pub total_confirmation_latency: u64, // Seconds between those alerts and the confirmations
+    pub bump: u8,
+    pub version: u8,
+    // Version 14
+    pub open_alerts: u32,             // Reported alerts not yet resolved or expired; stake is locked meanwhile
+}
+
+impl Watcher {
+    pub const SIZE: usize = 8 +     // discriminator
+                           32 +     // owner
+                           8 +      // staked_amount
+                           8 +      // registered_at
+                           1 +      // strikes
+                           1 +      // is_revoked
+                           8 +      // window_start
+                           1 +      // alerts_in_window
+                           4 +      // alerts_confirmed
+                           4 +      // alerts_false
+                           4 +      // confirmations_given
+                           8 +      // total_confirmation_latency
+                           1 +      // bump
+                           1 +      // version
+                           4;       // open_alerts
+
+    pub fn is_staked(&self, registry: &WatcherRegistry) -> bool {
+        !self.is_revoked && self.staked_amount >= registry.min_stake && self.staked_amount > 0
+    }
+    
+    // Counts an alert against the watcher's fixed-window rate limit
+    pub fn record_alert(&mut self, registry: &WatcherRegistry, now: i64) -> Result<()> {
+        if now - self.window_start >= registry.alert_window_secs {
+            self.window_start = now;
+            self.alerts_in_window = 0;
+        }
+        require!(
+            self.alerts_in_window < registry.max_alerts_per_window,
+            ErrorCode::WatcherRateLimited
+        );
+        self.alerts_in_window = self.alerts_in_window.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.open_alerts = self.open_alerts.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        
+        Ok(())
+    }
+    
+    // Share of reported alerts that were confirmed, in percent, minus a penalty for slow
+    // confirmations. Watchers without enough resolved alerts have no reputation yet.
+    pub fn reputation(&self) -> u8 {
+        let resolved = self.alerts_confirmed as u64 + self.alerts_false as u64;
+        if resolved < MIN_REPUTATION_SAMPLE as u64 {
+            return 0;
+        }
+        let accuracy = self.alerts_confirmed as u64 * 100 / resolved;
+        let latency_penalty = if self.confirmations_given == 0 {
+            0
+        } else {
+            let average_latency = self.total_confirmation_latency / self.confirmations_given as u64;
+            std::cmp::min(average_latency / REPUTATION_LATENCY_STEP_SECS, MAX_REPUTATION_LATENCY_PENALTY)
+        };
+        accuracy.saturating_sub(latency_penalty) as u8
+    }
+    
+    pub fn record_confirmation(&mut self, latency_secs: i64) -> Result<()> {
+        self.confirmations_given = self.confirmations_given.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_confirmation_latency = self.total_confirmation_latency
+            .checked_add(latency_secs.max(0) as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+    
+    pub fn record_resolution(&mut self, is_confirmed: bool) -> Result<()> {
+        if is_confirmed {
+            self.alerts_confirmed = self.alerts_confirmed.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            self.alerts_false = self.alerts_false.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.release_alert();
+        Ok(())
+    }
+    
+    // Alerts raised before open alerts were counted aren't in the count
+    pub fn release_alert(&mut self) {
+        self.open_alerts = self.open_alerts.saturating_sub(1);
+    }
+}
+
+#[event]
+pub struct WatcherSlashed {
+    pub watcher: Pubkey,
+    pub alert: Pubkey,
+    pub amount: u64,
+    pub strikes: u8,
+    pub is_revoked: bool,
+}
+
+#[event]
+pub struct WatcherStakeChanged {
+    pub watcher: Pubkey,
+    pub owner: Pubkey,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+pub fn initialize_watcher_registry(
+    ctx: Context<InitializeWatcherRegistry>,
+    min_stake: u64,
+) -> Result<()> {
+    let watcher_registry = &mut ctx.accounts.watcher_registry;
+    watcher_registry.stake_mint = ctx.accounts.stake_mint.key();
+    watcher_registry.stake_vault = ctx.accounts.stake_vault.key();
+    watcher_registry.min_stake = min_stake;
+    watcher_registry.watcher_count = 0;
+    watcher_registry.total_staked = 0;
+    watcher_registry.bounty_vault = Pubkey::default();
+    watcher_registry.max_alert_bounty = 0;
+    watcher_registry.slash_bps = DEFAULT_SLASH_BPS;
+    watcher_registry.confirmation_threshold = 1;
+    watcher_registry.max_alerts_per_window = DEFAULT_MAX_ALERTS_PER_WINDOW;
+    watcher_registry.alert_window_secs = DEFAULT_ALERT_WINDOW_SECS;
+    watcher_registry.trusted_reputation = 0;
+    watcher_registry.trusted_confirmation_threshold = 1;
+    watcher_registry.alert_bond = 0;
+    watcher_registry.total_bonded = 0;
+    watcher_registry.bump = ctx.bumps.watcher_registry;
+    watcher_registry.version = ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+// Takes effect immediately: the vault has to be owned by the registry, so moving it
+// can't redirect bounties, and the bounty amounts go through the timelocked
+// `ParameterChange::AlertBounty` like the rest of the watcher rules
+pub fn set_bounty_vault(ctx: Context<SetBountyVault>) -> Result<()> {
+    ctx.accounts.watcher_registry.bounty_vault = ctx.accounts.bounty_vault.key();
+
+    Ok(())
+}
+
+// Slashes the watcher's stake to the treasury and records a strike.
+// Returns the amount slashed.
+pub fn slash_watcher<'info>(
+    watcher: &mut Account<'info, Watcher>,
+    watcher_registry: &mut Account<'info, WatcherRegistry>,
+    stake_vault: &Account<'info, TokenAccount>,
+    treasury_token: &Account<'info, TokenAccount>,
+    stake_mint: &Account<'info, Mint>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let amount = (watcher.staked_amount as u128 * watcher_registry.slash_bps as u128 / 10000) as u64;
+
+    watcher.staked_amount = watcher.staked_amount.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    watcher.strikes = watcher.strikes.saturating_add(1);
+    if watcher.strikes >= MAX_WATCHER_STRIKES {
+        watcher.is_revoked = true;
+    }
+    watcher_registry.total_staked = watcher_registry.total_staked.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    if amount > 0 {
+        let seeds = &[
+            b"watcher-registry".as_ref(),
+            &[watcher_registry.bump]
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: stake_vault.to_account_info(),
+            mint: stake_mint.to_account_info(),
+            to: treasury_token.to_account_info(),
+            authority: watcher_registry.to_account_info(),
+        };
+
+        let cpi_program = token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer_checked(cpi_ctx, amount, stake_mint.decimals)?;
+    }
+
+    Ok(amount)
+}
+
+pub fn claim_alert_bounty(ctx: Context<ClaimAlertBounty>) -> Result<()> {
+    let exploit_alert = &mut ctx.accounts.exploit_alert;
+    let watcher_registry = &ctx.accounts.watcher_registry;
+
+    require!(exploit_alert.is_confirmed, ErrorCode::AlertNotConfirmed);
+    require!(!exploit_alert.bounty_claimed, ErrorCode::BountyAlreadyClaimed);
+
+    let bounty = watcher_registry.alert_bounty(exploit_alert.severity);
+    require!(bounty > 0, ErrorCode::NoBountyConfigured);
+
+    exploit_alert.bounty_claimed = true;
+
+    let seeds = &[
+        b"watcher-registry".as_ref(),
+        &[watcher_registry.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.bounty_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.reporter_token.to_account_info(),
+        authority: ctx.accounts.watcher_registry.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token::transfer_checked(cpi_ctx, bounty, ctx.accounts.stake_mint.decimals)?;
+
+    Ok(())
+}
+
+pub fn stake_watcher(
+    ctx: Context<StakeWatcher>,
+    amount: u64,
+) -> Result<()> {
+    let watcher = &mut ctx.accounts.watcher;
+    let watcher_registry = &mut ctx.accounts.watcher_registry;
+
+    // First stake registers the watcher
+    if watcher.owner == Pubkey::default() {
+        watcher.owner = ctx.accounts.owner.key();
+        watcher.registered_at = Clock::get()?.unix_timestamp;
+        watcher.strikes = 0;
+        watcher.is_revoked = false;
+        watcher.window_start = 0;
+        watcher.alerts_in_window = 0;
+        watcher.alerts_confirmed = 0;
+        watcher.alerts_false = 0;
+        watcher.confirmations_given = 0;
+        watcher.total_confirmation_latency = 0;
+        watcher.bump = ctx.bumps.watcher;
+        watcher.version = ACCOUNT_VERSION;
+        watcher.open_alerts = 0;
+        watcher_registry.watcher_count = watcher_registry.watcher_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    watcher.staked_amount = watcher.staked_amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    watcher_registry.total_staked = watcher_registry.total_staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.owner_token.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+    emit!(WatcherStakeChanged {
+        watcher: watcher.key(),
+        owner: watcher.owner,
+        staked_amount: watcher.staked_amount,
+        total_staked: watcher_registry.total_staked,
+    });
+
+    Ok(())
+}
+
+pub fn unstake_watcher(
+    ctx: Context<UnstakeWatcher>,
+    amount: u64,
+) -> Result<()> {
+    let watcher = &mut ctx.accounts.watcher;
+    let watcher_registry = &mut ctx.accounts.watcher_registry;
+
+    require!(watcher.staked_amount >= amount, ErrorCode::InsufficientWatcherStake);
+    // Stake stays slashable until every alert the watcher raised is settled
+    require!(watcher.open_alerts == 0, ErrorCode::WatcherHasOpenAlerts);
+
+    // Dropping below the minimum stake revokes the right to raise alerts
+    watcher.staked_amount = watcher.staked_amount.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    watcher_registry.total_staked = watcher_registry.total_staked.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[
+        b"watcher-registry".as_ref(),
+        &[watcher_registry.bump]
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.owner_token.to_account_info(),
+        authority: ctx.accounts.watcher_registry.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+    emit!(WatcherStakeChanged {
+        watcher: ctx.accounts.watcher.key(),
+        owner: ctx.accounts.watcher.owner,
+        staked_amount: ctx.accounts.watcher.staked_amount,
+        total_staked: ctx.accounts.watcher_registry.total_staked,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeWatcherRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = WatcherRegistry::SIZE,
+        seeds = [b"watcher-registry"],
+        bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    pub stake_mint: Account<'info, anchor_spl::token::Mint>,
+
+    #[account(
+        constraint = stake_vault.mint == stake_mint.key(),
+        constraint = stake_vault.owner == watcher_registry.key()
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBountyVault<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        constraint = bounty_vault.mint == watcher_registry.stake_mint,
+        constraint = bounty_vault.owner == watcher_registry.key(),
+        constraint = bounty_vault.key() != watcher_registry.stake_vault
+    )]
+    pub bounty_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAlertBounty<'info> {
+    pub reporter: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = exploit_alert.reporter == reporter.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub exploit_alert: Account<'info, ExploitAlert>,
+
+    #[account(
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        mut,
+        constraint = bounty_vault.key() == watcher_registry.bounty_vault
+    )]
+    pub bounty_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = reporter_token.mint == watcher_registry.stake_mint,
+        constraint = reporter_token.owner == reporter.key()
+    )]
+    pub reporter_token: Account<'info, TokenAccount>,
+
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct StakeWatcher<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Watcher::SIZE,
+        seeds = [b"watcher", owner.key().as_ref()],
+        bump
+    )]
+    pub watcher: Account<'info, Watcher>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        mut,
+        constraint = owner_token.mint == watcher_registry.stake_mint,
+        constraint = owner_token.owner == owner.key()
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = stake_vault.key() == watcher_registry.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeWatcher<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher", owner.key().as_ref()],
+        bump = watcher.bump,
+        constraint = watcher.owner == owner.key()
+    )]
+    pub watcher: Account<'info, Watcher>,
+
+    #[account(
+        mut,
+        seeds = [b"watcher-registry"],
+        bump = watcher_registry.bump
+    )]
+    pub watcher_registry: Account<'info, WatcherRegistry>,
+
+    #[account(
+        mut,
+        constraint = owner_token.mint == watcher_registry.stake_mint,
+        constraint = owner_token.owner == owner.key()
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = stake_vault.key() == watcher_registry.stake_vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(address = watcher_registry.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use anchor_spl::token::Mint;
+use crate::migrations::ACCOUNT_VERSION;
+use crate::config::{Config, FEATURE_PARAMETRIC_CLAIMS};
+use crate::loss_events::{LossEvent, LossEventCreated};
+use crate::{ProtocolState, ErrorCode};
+
+// Wormhole core bridge. It only writes a PostedVAA account once the guardian
+// signatures over the message have been verified.
+pub const WORMHOLE_CORE_BRIDGE_PROGRAM_ID: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+pub const POSTED_VAA_MAGIC: &[u8; 3] = b"vaa";
+
+// Layout of the core bridge's PostedVAA after its magic
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PostedVaa {
+    pub vaa_version: u8,
+    pub consistency_level: u8,
+    pub vaa_time: u32,
+    pub vaa_signature_account: Pubkey,
+    pub submission_time: u32,
+    pub nonce: u32,
+    pub sequence: u64,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+// Payload a registered emitter publishes for a loss on its chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LossProof {
+    pub protocol: Pubkey,
+    pub affected_mint: Pubkey,
+    pub incident_start: i64,
+    pub incident_end: i64,
+}
+
+// Contract on another chain trusted to attest to losses there
+#[account]
+pub struct LossEmitter {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub added_by: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl LossEmitter {
+    pub const SIZE: usize = 8 +     // discriminator
+                           2 +      // emitter_chain
+                           32 +     // emitter_address
+                           32 +     // added_by
+                           1 +      // bump
+                           1;       // version
+}
+
+#[event]
+pub struct LossEmitterUpdated {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub is_registered: bool,
+}
+
+pub fn load_posted_vaa(posted_vaa: &AccountInfo) -> Result<PostedVaa> {
+    require_keys_eq!(*posted_vaa.owner, WORMHOLE_CORE_BRIDGE_PROGRAM_ID, ErrorCode::InvalidVaa);
+
+    let data = posted_vaa.try_borrow_data()?;
+    require!(
+        data.len() > POSTED_VAA_MAGIC.len() && data[..POSTED_VAA_MAGIC.len()] == POSTED_VAA_MAGIC[..],
+        ErrorCode::InvalidVaa
+    );
+    PostedVaa::deserialize(&mut &data[POSTED_VAA_MAGIC.len()..]).map_err(|_| error!(ErrorCode::InvalidVaa))
+}
+
+pub fn register_loss_emitter(
+    ctx: Context<RegisterLossEmitter>,
+    emitter_chain: u16,
+    emitter_address: [u8; 32],
+) -> Result<()> {
+    let loss_emitter = &mut ctx.accounts.loss_emitter;
+    loss_emitter.emitter_chain = emitter_chain;
+    loss_emitter.emitter_address = emitter_address;
+    loss_emitter.added_by = ctx.accounts.authority.key();
+    loss_emitter.bump = ctx.bumps.loss_emitter;
+    loss_emitter.version = ACCOUNT_VERSION;
+
+    emit!(LossEmitterUpdated {
+        emitter_chain,
+        emitter_address,
+        is_registered: true,
+    });
+
+    Ok(())
+}
+
+pub fn remove_loss_emitter(ctx: Context<RemoveLossEmitter>) -> Result<()> {
+    emit!(LossEmitterUpdated {
+        emitter_chain: ctx.accounts.loss_emitter.emitter_chain,
+        emitter_address: ctx.accounts.loss_emitter.emitter_address,
+        is_registered: false,
+    });
+
+    Ok(())
+}
+
+// Creates a loss event from a VAA a registered emitter published about a loss on its
+// chain. Anyone can submit it since the guardians have already signed the message;
+// the loss event is keyed by the posted VAA so each proof is used once.
+pub fn create_cross_chain_loss_event(ctx: Context<CreateCrossChainLossEvent>) -> Result<()> {
+    let loss_emitter = &ctx.accounts.loss_emitter;
+    let loss_event = &mut ctx.accounts.loss_event;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.config.is_enabled(FEATURE_PARAMETRIC_CLAIMS),
+        ErrorCode::FeatureDisabled
+    );
+
+    let vaa = load_posted_vaa(&ctx.accounts.posted_vaa)?;
+    require!(
+        vaa.emitter_chain == loss_emitter.emitter_chain && vaa.emitter_address == loss_emitter.emitter_address,
+        ErrorCode::VaaEmitterMismatch
+    );
+
+    let proof = LossProof::try_from_slice(&vaa.payload).map_err(|_| error!(ErrorCode::InvalidVaa))?;
+    require!(
+        proof.affected_mint == ctx.accounts.affected_mint.key(),
+        ErrorCode::InvalidLossProof
+    );
+    require!(
+        proof.incident_start > 0 &&
+        proof.incident_start <= proof.incident_end &&
+        proof.incident_end <= clock.unix_timestamp,
+        ErrorCode::InvalidLossProof
+    );
+
+    loss_event.alert = ctx.accounts.posted_vaa.key();
+    loss_event.protocol = proof.protocol;
+    loss_event.affected_mint = proof.affected_mint;
+    loss_event.incident_start = proof.incident_start;
+    loss_event.incident_end = proof.incident_end;
+    loss_event.created_by = ctx.accounts.payer.key();
+    loss_event.bump = ctx.bumps.loss_event;
+    loss_event.version = ACCOUNT_VERSION;
+
+    emit!(LossEventCreated {
+        loss_event: loss_event.key(),
+        alert: loss_event.alert,
+        protocol: loss_event.protocol,
+        affected_mint: loss_event.affected_mint,
+        incident_start: loss_event.incident_start,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(emitter_chain: u16, emitter_address: [u8; 32])]
+pub struct RegisterLossEmitter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LossEmitter::SIZE,
+        seeds = [b"loss-emitter".as_ref(), &emitter_chain.to_le_bytes(), emitter_address.as_ref()],
+        bump
+    )]
+    pub loss_emitter: Account<'info, LossEmitter>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLossEmitter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"loss-emitter".as_ref(), &loss_emitter.emitter_chain.to_le_bytes(), loss_emitter.emitter_address.as_ref()],
+        bump = loss_emitter.bump
+    )]
+    pub loss_emitter: Account<'info, LossEmitter>,
+
+    #[account(
+        seeds = [b"protocol-state"],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ ErrorCode::UnauthorizedAccess
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCrossChainLossEvent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: owner and layout are validated by load_posted_vaa
+    pub posted_vaa: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"loss-emitter".as_ref(), &loss_emitter.emitter_chain.to_le_bytes(), loss_emitter.emitter_address.as_ref()],
+        bump = loss_emitter.bump
+    )]
+    pub loss_emitter: Account<'info, LossEmitter>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = LossEvent::SIZE,
+        seeds = [b"loss-event", posted_vaa.key().as_ref()],
+        bump
+    )]
+    pub loss_event: Account<'info, LossEvent>,
+
+    pub affected_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,9 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SolanaInsuranceProtocol } from "../target/types/solana_insurance_protocol";
-import { TOKEN_PROGRAM_ID, Token } from "@solana/spl-token";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, Token } from "@solana/spl-token";
 import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { assert } from "chai";
+import { createHash } from "crypto";
 
 describe("solana-insurance-protocol", () => {
   // Configure the client to use the local cluster
@@ -23,6 +24,14 @@ describe("solana-insurance-protocol", () => {
   let protocolRegistryPda: PublicKey;
   let protocolInfoPda: PublicKey;
   let capitalPoolPda: PublicKey;
+  let watcherRegistryPda: PublicKey;
+  let watcherPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let treasuryVaultPda: PublicKey;
+  let configPda: PublicKey;
+  let globalStatsPda: PublicKey;
+  let claimsCommitteePda: PublicKey;
+  let mintRegistryPda: PublicKey;
   
   // SPL Token mint and accounts
   let mint: Token;
@@ -30,8 +39,33 @@ describe("solana-insurance-protocol", () => {
   let treasuryTokenAccount: PublicKey;
   let poolTokenAccount: PublicKey;
   let providerTokenAccount: PublicKey;
+  let adminTokenAccount: PublicKey;
+  let stakeVaultAccount: PublicKey;
+  let feeVaultAccount: PublicKey;
   
-  const POOL_TYPE_MEDIUM_RISK = 2;
+  const POOL_TYPE_MEDIUM_RISK = 2; // CapitalPoolType::MediumRisk, also the pool's PDA seed
+  
+  // Compute budgets of hot instructions, to catch regressions as they grow. Raise one
+  // only alongside the change that needs it.
+  const COMPUTE_UNIT_BUDGETS: Record<string, number> = {
+    resolveClaim: 40_000,
+  };
+  
+  const computeUnitsConsumed = async (signature: string): Promise<number> => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return tx.meta.computeUnitsConsumed;
+  };
+  
+  const checkComputeUnits = async (instruction: string, signature: string) => {
+    assert.isAtMost(
+      await computeUnitsConsumed(signature),
+      COMPUTE_UNIT_BUDGETS[instruction],
+      `${instruction} compute units`
+    );
+  };
 
   before(async () => {
     // Airdrop SOL to test accounts
@@ -76,6 +110,41 @@ describe("solana-insurance-protocol", () => {
       program.programId
     );
     
+    [watcherRegistryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("watcher-registry")],
+      program.programId
+    );
+    
+    [watcherPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("watcher"), admin.publicKey.toBuffer()],
+      program.programId
+    );
+    
+    [treasuryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("treasury")],
+      program.programId
+    );
+    
+    [configPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("config")],
+      program.programId
+    );
+    
+    [globalStatsPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("global-stats")],
+      program.programId
+    );
+    
+    [claimsCommitteePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("claims-committee")],
+      program.programId
+    );
+    
+    [mintRegistryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("mint-registry")],
+      program.programId
+    );
+    
     // Create SPL token mint
     mint = await Token.createMint(
       provider.connection,
@@ -88,9 +157,17 @@ describe("solana-insurance-protocol", () => {
     
     // Create token accounts
     insuredTokenAccount = await mint.createAccount(insured.publicKey);
-    treasuryTokenAccount = await mint.createAccount(admin.publicKey);
+    treasuryTokenAccount = await mint.createAccount(treasuryPda);
     poolTokenAccount = await mint.createAccount(capitalPoolPda);
     providerTokenAccount = await mint.createAccount(capitalProvider.publicKey);
+    adminTokenAccount = await mint.createAccount(admin.publicKey);
+    stakeVaultAccount = await mint.createAccount(watcherRegistryPda);
+    feeVaultAccount = await mint.createAccount(protocolStatePda);
+    
+    [treasuryVaultPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("treasury-vault"), mint.publicKey.toBuffer()],
+      program.programId
+    );
     
     // Mint tokens to users
     await mint.mintTo(
@@ -106,6 +183,13 @@ describe("solana-insurance-protocol", () => {
       [],
       10000 * 1000000 // 10000 tokens with 6 decimals
     );
+    
+    await mint.mintTo(
+      adminTokenAccount,
+      admin.publicKey,
+      [],
+      100 * 1000000 // 100 tokens with 6 decimals
+    );
   });
   
   it("Initializes the protocol", async () => {
@@ -127,13 +211,137 @@ describe("solana-insurance-protocol", () => {
     assert.equal(protocolState.protocolFee.toString(), "500");
   });
   
+  it("Sets the protocol fee vault", async () => {
+    await program.methods
+      .setFeeVault()
+      .accounts({
+        authority: admin.publicKey,
+        protocolState: protocolStatePda,
+        feeVault: feeVaultAccount,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const protocolState = await program.account.protocolState.fetch(protocolStatePda);
+    assert.equal(protocolState.feeVault.toString(), feeVaultAccount.toString());
+  });
+  
+  it("Initializes the config", async () => {
+    await program.methods
+      .initializeConfig()
+      .accounts({
+        authority: admin.publicKey,
+        config: configPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const config = await program.account.config.fetch(configPda);
+    assert.equal(config.params.minPolicyDurationDays, 1);
+    assert.equal(config.params.maxPolicyDurationDays, 365);
+    assert.equal(config.features, 3); // every feature starts enabled
+  });
+  
+  it("Initializes the global stats", async () => {
+    await program.methods
+      .initializeGlobalStats()
+      .accounts({
+        authority: admin.publicKey,
+        globalStats: globalStatsPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const globalStats = await program.account.globalStats.fetch(globalStatsPda);
+    assert.equal(globalStats.activePolicies.toNumber(), 0);
+  });
+  
+  it("Sets up the treasury vault", async () => {
+    await program.methods
+      .initializeTreasury(admin.publicKey)
+      .accounts({
+        authority: admin.publicKey,
+        treasury: treasuryPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    await program.methods
+      .addTreasuryVault(new anchor.BN(50 * 1000000), new anchor.BN(24 * 60 * 60)) // 50 tokens per day
+      .accounts({
+        authority: admin.publicKey,
+        treasury: treasuryPda,
+        mint: mint.publicKey,
+        treasuryVault: treasuryVaultPda,
+        vaultToken: treasuryTokenAccount,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const treasuryVault = await program.account.treasuryVault.fetch(treasuryVaultPda);
+    assert.equal(treasuryVault.tokenAccount.toString(), treasuryTokenAccount.toString());
+    assert.equal(treasuryVault.spendLimit.toString(), (50 * 1000000).toString());
+  });
+  
+  it("Lists the token in the mint registry", async () => {
+    await program.methods
+      .initializeMintRegistry()
+      .accounts({
+        authority: admin.publicKey,
+        mintRegistry: mintRegistryPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    await program.methods
+      .registerMint(
+        new Array(32).fill(0),
+        new anchor.BN(500 * 1000000), // 500 tokens of coverage per policy
+        new anchor.BN(0),
+        new anchor.BN(1000000)        // 1 token minimum premium
+      )
+      .accounts({
+        authority: admin.publicKey,
+        mintRegistry: mintRegistryPda,
+        mint: mint.publicKey,
+        protocolState: protocolStatePda,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const mintRegistry = await program.account.mintRegistry.fetch(mintRegistryPda);
+    assert.equal(mintRegistry.mintCount, 1);
+    assert.equal(mintRegistry.mints[0].mint.toString(), mint.publicKey.toString());
+    assert.equal(mintRegistry.mints[0].decimals, 6);
+    assert.equal(mintRegistry.mints[0].maxPolicyCoverage.toString(), (500 * 1000000).toString());
+    assert.equal(mintRegistry.mints[0].minPremium.toString(), "1000000");
+    assert.equal(mintRegistry.mints[0].isAccepted, true);
+  });
+  
   it("Registers a protocol", async () => {
+    const [protocolEntryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("protocol-entry"), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    
     await program.methods
       .registerProtocol("Test Protocol", new anchor.BN(10000000)) // $10M TVL
       .accounts({
         authority: protocol.publicKey,
         protocolInfo: protocolInfoPda,
         registry: protocolRegistryPda,
+        protocolEntry: protocolEntryPda,
+        registrationApproval: null, // the allowlist is off
         systemProgram: SystemProgram.programId,
       })
       .signers([protocol])
@@ -144,15 +352,22 @@ describe("solana-insurance-protocol", () => {
     assert.equal(protocolInfo.protocolName, "Test Protocol");
     assert.equal(protocolInfo.tvlUsd.toString(), "10000000");
     assert.equal(protocolInfo.isActive, true);
+    assert.deepEqual(protocolInfo.incidentState, { normal: {} });
     
     // Check registry was updated
     const registry = await program.account.protocolRegistry.fetch(protocolRegistryPda);
-    assert.equal(registry.protocolCount.toString(), "1");
+    assert.equal(registry.registeredCount.toString(), "1");
+    assert.equal(registry.activeCount.toString(), "1");
+    assert.equal(registry.nextIndex.toString(), "1");
+    
+    // The protocol can be found through its registry entry
+    const protocolEntry = await program.account.protocolEntry.fetch(protocolEntryPda);
+    assert.equal(protocolEntry.protocol.toString(), protocolInfoPda.toString());
+    assert.equal(protocolEntry.isRegistered, true);
   });
   
   it("Updates protocol risk assessment", async () => {
     const codeRiskParams = {
-      auditCount: 2,
       bugBountySize: new anchor.BN(250000),
       complexityScore: 50,
     };
@@ -165,17 +380,35 @@ describe("solana-insurance-protocol", () => {
     const operationalRiskParams = {
       governanceCount: 5,
       adminCount: 3,
-      oracleDependency: true,
     };
     
+    // Only risk committee members can score a protocol
+    const [riskCommitteePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("risk-committee")],
+      program.programId
+    );
+    await program.methods
+      .initializeRiskCommittee([admin.publicKey], 1)
+      .accounts({
+        authority: admin.publicKey,
+        riskCommittee: riskCommitteePda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
     await program.methods
       .updateProtocolRisk(codeRiskParams, economicRiskParams, operationalRiskParams)
       .accounts({
-        authority: protocol.publicKey,
+        authority: admin.publicKey,
+        riskCommittee: riskCommitteePda,
         protocolInfo: protocolInfoPda,
         protocolState: protocolStatePda,
+        registry: protocolRegistryPda,
+        directory: null,
       })
-      .signers([protocol])
+      .signers([admin])
       .rpc();
     
     // Fetch and check the updated risk score
@@ -186,7 +419,7 @@ describe("solana-insurance-protocol", () => {
   
   it("Initializes a capital pool", async () => {
     await program.methods
-      .initializeCapitalPool(POOL_TYPE_MEDIUM_RISK, new anchor.BN(300)) // 3% yield rate
+      .initializeCapitalPool({ mediumRisk: {} } as any, new anchor.BN(300)) // 3% yield rate
       .accounts({
         authority: admin.publicKey,
         capitalPool: capitalPoolPda,
@@ -196,13 +429,14 @@ describe("solana-insurance-protocol", () => {
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        mintRegistry: mintRegistryPda,
       })
       .signers([admin])
       .rpc();
     
     // Fetch and check the capital pool
     const capitalPool = await program.account.capitalPool.fetch(capitalPoolPda);
-    assert.equal(capitalPool.poolType, POOL_TYPE_MEDIUM_RISK);
+    assert.deepEqual(capitalPool.poolType, { mediumRisk: {} });
     assert.equal(capitalPool.yieldRateBps.toString(), "300");
     assert.equal(capitalPool.tokenMint.toString(), mint.publicKey.toString());
     assert.equal(capitalPool.tokenAccount.toString(), poolTokenAccount.toString());
@@ -211,8 +445,17 @@ describe("solana-insurance-protocol", () => {
   let policyPda: PublicKey;
   
   it("Creates an insurance policy", async () => {
+    const [policyCounterPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("policy-counter"), insured.publicKey.toBuffer()],
+      program.programId
+    );
+    // The insured's first policy, at index 0
     [policyPda] = await PublicKey.findProgramAddress(
-      [Buffer.from("policy"), insured.publicKey.toBuffer(), protocolInfoPda.toBuffer()],
+      [Buffer.from("policy"), insured.publicKey.toBuffer(), protocolInfoPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [premiumEscrowPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("premium-escrow"), policyPda.toBuffer()],
       program.programId
     );
     
@@ -224,12 +467,25 @@ describe("solana-insurance-protocol", () => {
       .createPolicy(coverageAmount, premiumAmount, durationDays)
       .accounts({
         insured: insured.publicKey,
+        policyCounter: policyCounterPda,
         policy: policyPda,
         protocolInfo: protocolInfoPda,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        config: configPda,
+        capitalPool: capitalPoolPda,
         insuredToken: insuredTokenAccount,
-        treasuryToken: treasuryTokenAccount,
+        treasuryVault: treasuryVaultPda,
+        feeVault: feeVaultAccount,
+        revenueShareToken: null,
+        governance: null,
+        governanceStake: null,
+        premiumMint: mint.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        premiumEscrow: premiumEscrowPda,
+        memoProgram: null,
+        mintRegistry: mintRegistryPda,
       })
       .signers([insured])
       .rpc();
@@ -238,14 +494,33 @@ describe("solana-insurance-protocol", () => {
     const policy = await program.account.policy.fetch(policyPda);
     assert.equal(policy.insured.toString(), insured.publicKey.toString());
     assert.equal(policy.protocol.toString(), protocolInfoPda.toString());
+    assert.equal(policy.pool.toString(), capitalPoolPda.toString());
     assert.equal(policy.coverageAmount.toString(), coverageAmount.toString());
     assert.equal(policy.premiumAmount.toString(), premiumAmount.toString());
     assert.equal(policy.isActive, true);
     assert.equal(policy.isClaimed, false);
+    assert.equal(policy.index.toNumber(), 0);
+    
+    const policyCounter = await program.account.policyCounter.fetch(policyCounterPda);
+    assert.equal(policyCounter.nextIndex.toNumber(), 1);
+    
+    const globalStats = await program.account.globalStats.fetch(globalStatsPda);
+    assert.equal(globalStats.activePolicies.toNumber(), 1);
+    const mintTotals = globalStats.mintTotals.find((totals) => totals.mint.equals(mint.publicKey));
+    assert.equal(mintTotals.activeCoverage.toString(), coverageAmount.toString());
+    assert.equal(mintTotals.premiumsCollected.toString(), premiumAmount.toString());
+    
+    // Check that premium was split between the fee vault (5%) and the policy's escrow,
+    // which releases it to the treasury as it's earned
+    const feeAmount = premiumAmount.muln(500).divn(10000);
+    const feeVaultBalance = await provider.connection.getTokenAccountBalance(feeVaultAccount);
+    assert.equal(feeVaultBalance.value.amount, feeAmount.toString());
+    const escrowBalance = await provider.connection.getTokenAccountBalance(premiumEscrowPda);
+    assert.equal(escrowBalance.value.amount, premiumAmount.sub(feeAmount).toString());
+    assert.equal(policy.premiumEscrowed.toString(), premiumAmount.sub(feeAmount).toString());
     
-    // Check that premium was transferred
-    const treasuryBalance = await provider.connection.getTokenAccountBalance(treasuryTokenAccount);
-    assert.equal(treasuryBalance.value.amount, premiumAmount.toString());
+    const treasuryVault = await program.account.treasuryVault.fetch(treasuryVaultPda);
+    assert.equal(treasuryVault.totalInflows.toString(), "0");
   });
   
   it("Allows capital providers to provide capital", async () => {
@@ -268,9 +543,16 @@ describe("solana-insurance-protocol", () => {
         capitalPool: capitalPoolPda,
         providerToken: providerTokenAccount,
         poolTokenAccount: poolTokenAccount,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        tokenMint: mint.publicKey,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        mintRegistry: mintRegistryPda,
+        governance: null,
+        governanceStake: null,
+        config: configPda,
       })
       .signers([capitalProvider])
       .rpc();
@@ -293,7 +575,12 @@ describe("solana-insurance-protocol", () => {
   let claimPda: PublicKey;
   
   it("Allows insured to submit a claim", async () => {
+    // The policy's first claim, at index 0
     [claimPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("claim"), policyPda.toBuffer(), Buffer.from([0, 0, 0, 0])],
+      program.programId
+    );
+    const [legacyClaimPda] = await PublicKey.findProgramAddress(
       [Buffer.from("claim"), policyPda.toBuffer()],
       program.programId
     );
@@ -306,8 +593,13 @@ describe("solana-insurance-protocol", () => {
       .accounts({
         claimant: insured.publicKey,
         policy: policyPda,
+        protocolInfo: protocolInfoPda,
+        protocolState: protocolStatePda,
+        config: configPda,
+        lossEvent: null,
         claim: claimPda,
         systemProgram: SystemProgram.programId,
+        legacyClaim: legacyClaimPda,
       })
       .signers([insured])
       .rpc();
@@ -318,30 +610,49 @@ describe("solana-insurance-protocol", () => {
     assert.equal(claim.claimant.toString(), insured.publicKey.toString());
     assert.equal(claim.amount.toString(), claimAmount.toString());
     assert.equal(claim.evidence, evidence);
-    assert.equal(claim.status, 0); // PENDING
+    assert.deepEqual(claim.status, { pending: {} });
+  });
+  
+  it("Registers a claim resolver", async () => {
+    await program.methods
+      .addClaimResolver(admin.publicKey)
+      .accounts({
+        authority: admin.publicKey,
+        protocolState: protocolStatePda,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const protocolState = await program.account.protocolState.fetch(protocolStatePda);
+    assert.equal(protocolState.claimResolverCount, 1);
+    assert.equal(protocolState.claimResolvers[0].toString(), admin.publicKey.toString());
   });
   
   it("Resolves a claim", async () => {
     const resolutionNotes = "Verified hack on specified date. Approving claim.";
     
-    await program.methods
+    const signature = await program.methods
       .resolveClaim(true, resolutionNotes) // true = approve
       .accounts({
         resolver: admin.publicKey,
         claim: claimPda,
         policy: policyPda,
         protocolInfo: protocolInfoPda,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        config: configPda,
         capitalPool: capitalPoolPda,
-        poolTokenAccount: poolTokenAccount,
-        claimantToken: insuredTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        claimsCommittee: claimsCommitteePda,
       })
       .signers([admin])
-      .rpc();
+      .rpc({ commitment: "confirmed" });
+    
+    await checkComputeUnits("resolveClaim", signature);
     
     // Fetch and check the claim
     const claim = await program.account.claim.fetch(claimPda);
-    assert.equal(claim.status, 1); // APPROVED
+    assert.deepEqual(claim.status, { approved: {} });
+    assert.isTrue(claim.challengeEndsAt.toNumber() > 0);
     assert.equal(claim.resolutionNotes, resolutionNotes);
     assert.equal(claim.resolver.toString(), admin.publicKey.toString());
     
@@ -349,18 +660,95 @@ describe("solana-insurance-protocol", () => {
     const policy = await program.account.policy.fetch(policyPda);
     assert.equal(policy.isClaimed, true);
     
-    // Check insured token account balance (should have received claim amount)
+    // The payout waits for the challenge window, so nothing has been paid yet
     const insuredBalance = await provider.connection.getTokenAccountBalance(insuredTokenAccount);
     assert.equal(
       insuredBalance.value.amount, 
-      (1000 * 1000000 - 5 * 1000000 + 50 * 1000000).toString()
-    ); // Initial - premium + claim
+      (1000 * 1000000 - 5 * 1000000).toString()
+    ); // Initial - premium
+    
+    // Paying out before the window has passed fails
+    const claimantToken = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      mint.publicKey,
+      insured.publicKey
+    );
+    try {
+      await program.methods
+        .payClaim()
+        .accounts({
+          caller: admin.publicKey,
+          claim: claimPda,
+          policy: policyPda,
+          protocolInfo: protocolInfoPda,
+          protocolState: protocolStatePda,
+          globalStats: globalStatsPda,
+          capitalPool: capitalPoolPda,
+          poolTokenAccount: poolTokenAccount,
+          tokenMint: mint.publicKey,
+          insured: insured.publicKey,
+          claimantToken,
+          solPayout: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          memoProgram: null,
+          mintRegistry: mintRegistryPda,
+        })
+        .signers([admin])
+        .rpc();
+      assert.fail("Claim was paid during its challenge window");
+    } catch (err) {
+      assert.include(err.toString(), "ChallengeWindowOpen");
+    }
+  });
+  
+  it("Registers a staked watcher", async () => {
+    const minStake = new anchor.BN(10 * 1000000); // 10 tokens
+    
+    await program.methods
+      .initializeWatcherRegistry(minStake)
+      .accounts({
+        authority: admin.publicKey,
+        watcherRegistry: watcherRegistryPda,
+        stakeMint: mint.publicKey,
+        stakeVault: stakeVaultAccount,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    await program.methods
+      .stakeWatcher(minStake)
+      .accounts({
+        owner: admin.publicKey,
+        watcher: watcherPda,
+        watcherRegistry: watcherRegistryPda,
+        ownerToken: adminTokenAccount,
+        stakeVault: stakeVaultAccount,
+        stakeMint: mint.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const watcher = await program.account.watcher.fetch(watcherPda);
+    assert.equal(watcher.owner.toString(), admin.publicKey.toString());
+    assert.equal(watcher.stakedAmount.toString(), minStake.toString());
+    
+    const registry = await program.account.watcherRegistry.fetch(watcherRegistryPda);
+    assert.equal(registry.watcherCount.toString(), "1");
   });
   
   it("Creates an exploit alert", async () => {
-    const anomalyType = 1; // TVL drop
-    const severity = 85; // High severity
+    const anomalyType = { tvlDrop: {} };
+    const severity = { high: {} };
     const details = "TVL dropped by 50% in 1 hour. Possible exploit in progress.";
+    const detailsHash = Array.from(createHash("sha256").update(details).digest());
+    const detailsUri = "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U";
     
     const [exploitAlertPda] = await PublicKey.findProgramAddress(
       [
@@ -372,13 +760,18 @@ describe("solana-insurance-protocol", () => {
     );
     
     await program.methods
-      .createExploitAlert(anomalyType, severity, details)
+      .createExploitAlert(anomalyType, severity, detailsHash, detailsUri)
       .accounts({
         authority: admin.publicKey,
         exploitAlert: exploitAlertPda,
         protocolInfo: protocolInfoPda,
         protocolState: protocolStatePda,
+        affectedPool: null,
+        watcher: watcherPda,
+        watcherRegistry: watcherRegistryPda,
         systemProgram: SystemProgram.programId,
+        registry: protocolRegistryPda,
+        directory: null,
       })
       .signers([admin])
       .rpc();
@@ -386,10 +779,16 @@ describe("solana-insurance-protocol", () => {
     // Fetch and check the exploit alert
     const exploitAlert = await program.account.exploitAlert.fetch(exploitAlertPda);
     assert.equal(exploitAlert.protocol.toString(), protocolInfoPda.toString());
-    assert.equal(exploitAlert.anomalyType, anomalyType);
-    assert.equal(exploitAlert.severity, severity);
-    assert.equal(exploitAlert.details, details);
+    assert.equal(exploitAlert.reporter.toString(), admin.publicKey.toString());
+    assert.deepEqual(exploitAlert.anomalyType, anomalyType);
+    assert.deepEqual(exploitAlert.severity, severity);
+    assert.deepEqual(exploitAlert.detailsHash, detailsHash);
+    assert.equal(exploitAlert.detailsUri, detailsUri);
     assert.equal(exploitAlert.isConfirmed, false);
+    
+    // A high-severity alert pauses new policies for the protocol
+    const protocolInfo = await program.account.protocolInfo.fetch(protocolInfoPda);
+    assert.equal(protocolInfo.isActive, false);
   });
   
   it("Allows capital provider to withdraw capital with rewards", async () => {
@@ -414,8 +813,14 @@ describe("solana-insurance-protocol", () => {
         capitalPool: capitalPoolPda,
         providerToken: providerTokenAccount,
         poolTokenAccount: poolTokenAccount,
+        protocolState: protocolStatePda,
+        globalStats: globalStatsPda,
+        tokenMint: mint.publicKey,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        governance: null,
+        governanceStake: null,
+        config: configPda,
       })
       .signers([capitalProvider])
       .rpc();
@@ -441,4 +846,418 @@ describe("solana-insurance-protocol", () => {
       10000 * 1000000 - 1000 * 1000000 + 400 * 1000000
     );
   });
+  
+  it("Rejects a risk attestation before a risk oracle is set", async () => {
+    // Setting the oracle goes through the two-day timelock, so the signed path is
+    // exercised in program-test, which can move the clock
+    const attestation = {
+      protocol: protocolInfoPda,
+      codeRisk: 10,
+      economicRisk: 20,
+      operationalRisk: 30,
+      attestedAt: new anchor.BN(Math.floor(Date.now() / 1000)),
+    };
+    
+    try {
+      await program.methods
+        .submitRiskAttestation(attestation)
+        .accounts({
+          protocolInfo: protocolInfoPda,
+          protocolState: protocolStatePda,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          registry: protocolRegistryPda,
+          directory: null,
+        })
+        .rpc();
+      assert.fail("Attestation was accepted without a risk oracle");
+    } catch (err) {
+      assert.include(err.toString(), "RiskOracleNotSet");
+    }
+  });
+  
+  // A second protocol, since the first was paused by its exploit alert
+  const quotedProtocol = anchor.web3.Keypair.generate();
+  let quotedProtocolInfoPda: PublicKey;
+  let policyCounterPda: PublicKey;
+  let quotedPolicyPda: PublicKey;
+  let quotedEscrowPda: PublicKey;
+  
+  it("Sells a policy at its quoted premium", async () => {
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(quotedProtocol.publicKey, 10 * LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+    [quotedProtocolInfoPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("protocol-info"), quotedProtocol.publicKey.toBuffer()],
+      program.programId
+    );
+    const [protocolEntryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("protocol-entry"), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .registerProtocol("Quoted Protocol", new anchor.BN(10000000))
+      .accounts({
+        authority: quotedProtocol.publicKey,
+        protocolInfo: quotedProtocolInfoPda,
+        registry: protocolRegistryPda,
+        protocolEntry: protocolEntryPda,
+        registrationApproval: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([quotedProtocol])
+      .rpc();
+    
+    [policyCounterPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("policy-counter"), insured.publicKey.toBuffer()],
+      program.programId
+    );
+    // The insured's second policy, at index 1
+    [quotedPolicyPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("policy"), insured.publicKey.toBuffer(), quotedProtocolInfoPda.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [quotedEscrowPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("premium-escrow"), quotedPolicyPda.toBuffer()],
+      program.programId
+    );
+    const accounts = {
+      insured: insured.publicKey,
+      policyCounter: policyCounterPda,
+      policy: quotedPolicyPda,
+      protocolInfo: quotedProtocolInfoPda,
+      protocolState: protocolStatePda,
+      globalStats: globalStatsPda,
+      config: configPda,
+      capitalPool: capitalPoolPda,
+      insuredToken: insuredTokenAccount,
+      treasuryVault: treasuryVaultPda,
+      feeVault: feeVaultAccount,
+      revenueShareToken: null,
+      governance: null,
+      governanceStake: null,
+      premiumMint: mint.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      premiumEscrow: quotedEscrowPda,
+      memoProgram: null,
+      mintRegistry: mintRegistryPda,
+    };
+    
+    // 100 tokens for 30 days at the default 1% rate is well under the token's
+    // 1 token minimum premium, so the quote is the minimum
+    const coverageAmount = new anchor.BN(100 * 1000000);
+    const quotedPremium = new anchor.BN(1000000);
+    
+    try {
+      await program.methods
+        .createPolicyAtQuote(coverageAmount, quotedPremium.subn(1), 30)
+        .accounts(accounts)
+        .signers([insured])
+        .rpc();
+      assert.fail("Policy was sold above the insured's maximum premium");
+    } catch (err) {
+      assert.include(err.toString(), "QuoteAboveMaxPremium");
+    }
+    
+    await program.methods
+      .createPolicyAtQuote(coverageAmount, new anchor.BN(10 * 1000000), 30) // Up to 10 tokens
+      .accounts(accounts)
+      .signers([insured])
+      .rpc();
+    
+    const policy = await program.account.policy.fetch(quotedPolicyPda);
+    assert.equal(policy.protocol.toString(), quotedProtocolInfoPda.toString());
+    assert.equal(policy.coverageAmount.toString(), coverageAmount.toString());
+    assert.equal(policy.premiumAmount.toString(), quotedPremium.toString());
+    assert.equal(policy.index.toNumber(), 1);
+    
+    const feeAmount = quotedPremium.muln(500).divn(10000);
+    assert.equal(policy.premiumEscrowed.toString(), quotedPremium.sub(feeAmount).toString());
+  });
+  
+  it("Releases earned premium from escrow", async () => {
+    const accounts = {
+      caller: admin.publicKey,
+      policy: quotedPolicyPda,
+      premiumEscrow: quotedEscrowPda,
+      treasuryVault: treasuryVaultPda,
+      treasuryToken: treasuryTokenAccount,
+      tokenMint: mint.publicKey,
+      insured: insured.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    
+    try {
+      await program.methods
+        .releaseEarnedPremium()
+        .accounts({ ...accounts, treasuryToken: adminTokenAccount })
+        .signers([admin])
+        .rpc();
+      assert.fail("Premium was released outside the treasury vault");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidTreasuryVault");
+    }
+    
+    const treasuryBefore = await provider.connection.getTokenAccountBalance(treasuryTokenAccount);
+    await program.methods
+      .releaseEarnedPremium()
+      .accounts(accounts)
+      .signers([admin])
+      .rpc();
+    
+    // Whatever has been earned so far moved to the treasury; the rest stays in escrow
+    const policy = await program.account.policy.fetch(quotedPolicyPda);
+    const escrowBalance = await provider.connection.getTokenAccountBalance(quotedEscrowPda);
+    assert.equal(escrowBalance.value.amount, policy.premiumEscrowed.sub(policy.premiumReleased).toString());
+    const treasuryAfter = await provider.connection.getTokenAccountBalance(treasuryTokenAccount);
+    assert.equal(
+      new anchor.BN(treasuryAfter.value.amount).sub(new anchor.BN(treasuryBefore.value.amount)).toString(),
+      policy.premiumReleased.toString()
+    );
+  });
+  
+  it("Refunds the unearned premium when a protocol winds down", async () => {
+    const [legacyClaimPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("claim"), quotedPolicyPda.toBuffer()],
+      program.programId
+    );
+    const accounts = {
+      authority: insured.publicKey,
+      policy: quotedPolicyPda,
+      protocolInfo: quotedProtocolInfoPda,
+      protocolState: protocolStatePda,
+      globalStats: globalStatsPda,
+      capitalPool: capitalPoolPda,
+      legacyClaim: legacyClaimPda,
+      premiumEscrow: quotedEscrowPda,
+      treasury: treasuryPda,
+      treasuryVault: treasuryVaultPda,
+      treasuryToken: treasuryTokenAccount,
+      tokenMint: mint.publicKey,
+      insured: insured.publicKey,
+      insuredToken: insuredTokenAccount,
+      solRefund: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      memoProgram: null,
+    };
+    
+    // Policies can only be cancelled once their protocol is being deregistered
+    try {
+      await program.methods
+        .cancelPolicy()
+        .accounts(accounts)
+        .signers([insured])
+        .rpc();
+      assert.fail("Policy was cancelled while its protocol was still listed");
+    } catch (err) {
+      assert.include(err.toString(), "ProtocolNotDeregistering");
+    }
+    
+    await program.methods
+      .beginProtocolDeregistration()
+      .accounts({
+        authority: quotedProtocol.publicKey,
+        protocolInfo: quotedProtocolInfoPda,
+        protocolState: protocolStatePda,
+        registry: protocolRegistryPda,
+        directory: null,
+      })
+      .signers([quotedProtocol])
+      .rpc();
+    
+    const insuredBefore = await provider.connection.getTokenAccountBalance(insuredTokenAccount);
+    await program.methods
+      .cancelPolicy()
+      .accounts(accounts)
+      .signers([insured])
+      .rpc();
+    
+    // The insured gets back what hadn't been earned and the escrow is closed
+    const policy = await program.account.policy.fetch(quotedPolicyPda);
+    assert.equal(policy.isActive, false);
+    assert.isTrue(policy.premiumRefunded.toNumber() > 0);
+    const insuredAfter = await provider.connection.getTokenAccountBalance(insuredTokenAccount);
+    assert.equal(
+      new anchor.BN(insuredAfter.value.amount).sub(new anchor.BN(insuredBefore.value.amount)).toString(),
+      policy.premiumRefunded.toString()
+    );
+    assert.isNull(await provider.connection.getAccountInfo(quotedEscrowPda));
+  });
+  
+  let emergencyCouncilPda: PublicKey;
+  
+  it("Lets the emergency council veto an approved claim", async () => {
+    [emergencyCouncilPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("emergency-council")],
+      program.programId
+    );
+    await program.methods
+      .initializeEmergencyCouncil([admin.publicKey])
+      .accounts({
+        authority: admin.publicKey,
+        emergencyCouncil: emergencyCouncilPda,
+        protocolState: protocolStatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const [councilActionPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("council-action"), claimPda.toBuffer()],
+      program.programId
+    );
+    const reason = "Exploit report was fabricated";
+    
+    try {
+      await program.methods
+        .vetoClaim(reason)
+        .accounts({
+          member: insured.publicKey,
+          emergencyCouncil: emergencyCouncilPda,
+          claim: claimPda,
+          policy: policyPda,
+          capitalPool: capitalPoolPda,
+          councilAction: councilActionPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([insured])
+        .rpc();
+      assert.fail("Claim was vetoed by a non-member");
+    } catch (err) {
+      assert.include(err.toString(), "NotCouncilMember");
+    }
+    
+    const poolBefore = await program.account.capitalPool.fetch(capitalPoolPda);
+    await program.methods
+      .vetoClaim(reason)
+      .accounts({
+        member: admin.publicKey,
+        emergencyCouncil: emergencyCouncilPda,
+        claim: claimPda,
+        policy: policyPda,
+        capitalPool: capitalPoolPda,
+        councilAction: councilActionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    
+    const claim = await program.account.claim.fetch(claimPda);
+    assert.deepEqual(claim.status, { vetoed: {} });
+    
+    // The claim's reserved payout goes back to the pool
+    const poolAfter = await program.account.capitalPool.fetch(capitalPoolPda);
+    assert.equal(
+      poolAfter.availableCapital.sub(poolBefore.availableCapital).toString(),
+      claim.amount.toString()
+    );
+    
+    const councilAction = await program.account.councilAction.fetch(councilActionPda);
+    assert.equal(councilAction.target.toString(), claimPda.toString());
+    assert.deepEqual(councilAction.kind, { vetoClaim: {} });
+    assert.equal(councilAction.reason, reason);
+    assert.equal(councilAction.signerCount, 1);
+  });
+  
+  it("Lets the emergency council freeze a pool's payouts", async () => {
+    const [councilActionPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("council-action"), capitalPoolPda.toBuffer()],
+      program.programId
+    );
+    const accounts = (member: PublicKey) => ({
+      member,
+      emergencyCouncil: emergencyCouncilPda,
+      capitalPool: capitalPoolPda,
+      councilAction: councilActionPda,
+      systemProgram: SystemProgram.programId,
+    });
+    
+    try {
+      await program.methods
+        .setPoolPayoutsFrozen(true, "Oracle incident under review")
+        .accounts(accounts(insured.publicKey))
+        .signers([insured])
+        .rpc();
+      assert.fail("Pool was frozen by a non-member");
+    } catch (err) {
+      assert.include(err.toString(), "NotCouncilMember");
+    }
+    
+    await program.methods
+      .setPoolPayoutsFrozen(true, "Oracle incident under review")
+      .accounts(accounts(admin.publicKey))
+      .signers([admin])
+      .rpc();
+    
+    let capitalPool = await program.account.capitalPool.fetch(capitalPoolPda);
+    assert.equal(capitalPool.payoutsFrozen, true);
+    
+    // Lifting the freeze overwrites the pool's council action
+    await program.methods
+      .setPoolPayoutsFrozen(false, "Incident resolved")
+      .accounts(accounts(admin.publicKey))
+      .signers([admin])
+      .rpc();
+    
+    capitalPool = await program.account.capitalPool.fetch(capitalPoolPda);
+    assert.equal(capitalPool.payoutsFrozen, false);
+    const councilAction = await program.account.councilAction.fetch(councilActionPda);
+    assert.deepEqual(councilAction.kind, { unfreezePayouts: {} });
+    assert.equal(councilAction.reason, "Incident resolved");
+  });
+  
+  it("Runs a solvency stress test", async () => {
+    const reportId = new anchor.BN(0);
+    const [solvencyReportPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("solvency-report"), admin.publicKey.toBuffer(), reportId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    // Every tier's pool in pool type order, whether or not it was created
+    const poolAccounts = await Promise.all([1, 2, 3].map(async (poolType) => {
+      const [pool] = await PublicKey.findProgramAddress(
+        [Buffer.from("capital-pool"), Buffer.from([poolType])],
+        program.programId
+      );
+      return { pubkey: pool, isWritable: false, isSigner: false };
+    }));
+    const accounts = {
+      payer: admin.publicKey,
+      solvencyReport: solvencyReportPda,
+      systemProgram: SystemProgram.programId,
+    };
+    
+    try {
+      await program.methods
+        .runStressTest(reportId, new anchor.BN(10001))
+        .accounts(accounts)
+        .remainingAccounts(poolAccounts)
+        .signers([admin])
+        .rpc();
+      assert.fail("Stress test ran with a loss above 100%");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidLossScenario");
+    }
+    
+    // Losing all active coverage
+    await program.methods
+      .runStressTest(reportId, new anchor.BN(10000))
+      .accounts(accounts)
+      .remainingAccounts(poolAccounts)
+      .signers([admin])
+      .rpc();
+    
+    // Only the medium risk pool exists
+    const capitalPool = await program.account.capitalPool.fetch(capitalPoolPda);
+    const report = await program.account.solvencyReport.fetch(solvencyReportPda);
+    assert.equal(report.poolCount, 1);
+    assert.equal(report.totalActiveCoverage.toString(), capitalPool.activeCoverage.toString());
+    assert.equal(report.totalAvailableCapital.toString(), capitalPool.availableCapital.toString());
+    assert.equal(report.projectedLoss.toString(), capitalPool.activeCoverage.toString());
+    assert.equal(
+      report.isSolvent,
+      capitalPool.activeCoverage.lte(capitalPool.availableCapital.add(capitalPool.reinsuranceCapacity))
+    );
+  });
 });
\ No newline at end of file
@@ -0,0 +1,215 @@
+// Account layouts and constants of the insurance program, for indexers, keepers and
+// tests that only need to read its accounts. Depends on nothing but borsh, so it can
+// sit next to any solana-program version; the program checks at compile time that
+// these layouts still match its own.
+//
+// Layouts are the current `ACCOUNT_VERSION`. Accounts written by an older program
+// version are shorter until migrated and fail to deserialize here.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::io;
+
+#[cfg(feature = "solana-program")]
+pub use solana_program::pubkey::Pubkey;
+
+#[cfg(not(feature = "solana-program"))]
+pub type Pubkey = [u8; 32];
+
+pub const PROGRAM_ID: &str = "4LLgpV6Hu42KLg8W2GzdxjRxXmVoybSwb897WEdmXWQE";
+
+// Layout version written by the program
+pub const ACCOUNT_VERSION: u8 = 17;
+
+// PDA seeds
+pub const PROTOCOL_STATE_SEED: &[u8] = b"protocol-state";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const CAPITAL_POOL_SEED: &[u8] = b"capital-pool";       // + [pool_type as u8]
+pub const CAPITAL_PROVIDER_SEED: &[u8] = b"capital-provider"; // + owner + capital pool
+pub const CLAIM_SEED: &[u8] = b"claim";                     // + policy + claim index
+pub const TREASURY_VAULT_SEED: &[u8] = b"treasury-vault";   // + mint
+pub const GOVERNANCE_STAKE_SEED: &[u8] = b"governance-stake"; // + owner
+
+pub const MAX_EVIDENCE_LEN: usize = 96;
+pub const MAX_RESOLUTION_NOTES_LEN: usize = 96;
+
+// An account the program owns, identified by Anchor's 8-byte discriminator
+pub trait ProgramAccount: BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+    const SIZE: usize;
+
+    // Reads the account from its raw data, discriminator included. Trailing bytes
+    // (space reserved for later versions) are ignored.
+    fn try_from_account_data(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 8 || data[..8] != Self::DISCRIMINATOR {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "account discriminator mismatch"));
+        }
+        Self::deserialize(&mut &data[8..])
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CapitalPoolType {
+    Unassigned,
+    LowRisk,
+    MediumRisk,
+    HighRisk,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Paid,
+    Vetoed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RiskWeights {
+    pub code: u8,
+    pub economic: u8,
+    pub operational: u8,
+    pub loss_experience: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PayoutLimits {
+    pub challenge_window_secs: i64,
+    pub max_claim_payout: u64,
+    pub daily_payout_cap: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Policy {
+    pub insured: Pubkey,
+    pub protocol: Pubkey,
+    pub pool: Pubkey,
+    pub coverage_amount: u64,
+    pub premium_amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub is_claimed: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub premium_mint: Pubkey,
+    pub premium_decimals: u8,
+    pub amount_paid: u64,
+    pub pending_reserved: u64,
+    pub index: u64,
+    pub premium_escrowed: u64,
+    pub premium_released: u64,
+    pub premium_refunded: u64,
+    pub claim_count: u32,
+    pub open_claims: u32,
+    pub coverage_released: u64,
+}
+
+impl ProgramAccount for Policy {
+    const DISCRIMINATOR: [u8; 8] = [222, 135, 7, 163, 235, 177, 33, 68];
+    const SIZE: usize = 8 + 32 * 3 + 8 * 4 + 4 + 32 + 1 + 8 * 6 + 4 * 2 + 8;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Claim {
+    pub policy: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub evidence: String,
+    pub submitted_time: i64,
+    pub status: ClaimStatus,
+    pub resolution_time: i64,
+    pub resolver: Pubkey,
+    pub resolution_notes: String,
+    pub loss_event: Pubkey,
+    pub is_fast_track: bool,
+    pub challenge_ends_at: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ProgramAccount for Claim {
+    const DISCRIMINATOR: [u8; 8] = [155, 70, 22, 176, 123, 215, 246, 102];
+    const SIZE: usize = 8 + 32 * 2 + 8 + 4 + MAX_EVIDENCE_LEN + 8 + 1 + 8 + 32 +
+                        4 + MAX_RESOLUTION_NOTES_LEN + 32 + 1 + 8 + 1 + 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CapitalPool {
+    pub pool_type: CapitalPoolType,
+    pub total_capital: u64,
+    pub available_capital: u64,
+    pub reserved_capital: u64,
+    pub yield_rate_bps: u64,
+    pub token_mint: Pubkey,
+    pub token_account: Pubkey,
+    pub authority: Pubkey,
+    pub risk_weights: RiskWeights,
+    pub max_risk_score: u8,
+    pub active_coverage: u64,
+    pub reinsurance_capacity: u64,
+    pub withdrawals_paused: bool,
+    pub payouts_frozen: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub token_decimals: u8,
+    pub payout_limits: PayoutLimits,
+    pub payout_day: i64,
+    pub paid_in_day: u64,
+}
+
+impl ProgramAccount for CapitalPool {
+    const DISCRIMINATOR: [u8; 8] = [88, 17, 217, 36, 67, 57, 75, 130];
+    const SIZE: usize = 8 + 1 + 8 * 4 + 32 * 3 + 4 + 1 + 8 * 2 + 5 + 24 + 8 * 2;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CapitalProvider {
+    pub owner: Pubkey,
+    pub capital_amount: u64,
+    pub pool: Pubkey,
+    pub rewards_earned: u64,
+    pub accrued_until: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ProgramAccount for CapitalProvider {
+    const DISCRIMINATOR: [u8; 8] = [213, 209, 172, 219, 30, 50, 90, 197];
+    const SIZE: usize = 8 + 32 + 8 + 32 + 8 + 8 + 1 + 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TreasuryVault {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub spend_limit: u64,
+    pub window_secs: i64,
+    pub window_started_at: i64,
+    pub spent_in_window: u64,
+    pub total_inflows: u64,
+    pub total_withdrawn: u64,
+    pub total_refunded: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl ProgramAccount for TreasuryVault {
+    const DISCRIMINATOR: [u8; 8] = [86, 102, 19, 109, 56, 58, 144, 81];
+    const SIZE: usize = 8 + 32 * 2 + 8 * 7 + 1 + 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct GovernanceStake {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub locked_until: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub staked_at: i64,
+}
+
+impl ProgramAccount for GovernanceStake {
+    const DISCRIMINATOR: [u8; 8] = [225, 140, 235, 65, 153, 137, 239, 212];
+    const SIZE: usize = 8 + 32 + 8 + 8 + 1 + 1 + 8;
+}